@@ -0,0 +1,375 @@
+//! The languages codes can be submitted in, identified by their `programTypeId`.
+//!
+//! A built-in catalog is used until it is refreshed from the language `<select>` of a submit
+//! page, after which the refreshed one is kept in `languages.json` of the configuration
+//! directory.
+
+use std::{collections::HashMap, fmt, fs, path::Path, path::PathBuf};
+
+use color_eyre::{
+    eyre::{bail, eyre, Context},
+    Result,
+};
+use nipper::Document;
+use serde::{Deserialize, Serialize};
+
+use crate::settings::{get_config_file_path, CFTemplate, SETTINGS};
+
+use super::{methods::CLIENT, utils::base_url};
+
+/// Languages as listed on the submit page, by `programTypeId`, name and compiler version.
+static BUILTIN_LANGUAGES: &[(u32, &str, &str)] = &[
+    (43, "GNU GCC C11", "5.1.0"),
+    (54, "GNU G++17", "7.3.0"),
+    (89, "GNU G++20", "13.2 (64 bit, winlibs)"),
+    (91, "GNU G++23", "14.2 (64 bit, msys2)"),
+    (79, "C#", "10, .NET SDK 6.0"),
+    (32, "Go", "1.22.2"),
+    (12, "Haskell GHC", "8.10.1"),
+    (60, "Java", "11.0.6"),
+    (87, "Java", "21 64bit"),
+    (55, "Node.js", "15.8.0 (64bit)"),
+    (34, "JavaScript V8", "4.8.0"),
+    (83, "Kotlin", "1.7.20"),
+    (88, "Kotlin", "1.9.21"),
+    (4, "Free Pascal", "3.2.2"),
+    (31, "Python", "3.8.10"),
+    (70, "PyPy", "3.10 (7.3.15, 64bit)"),
+    (67, "Ruby", "3.2.2"),
+    (75, "Rust", "1.75.0 (2021)"),
+];
+
+/// The language codes of each extension are submitted in unless configured otherwise.
+static DEFAULT_LANGUAGES: &[(&str, u32)] = &[
+    ("c", 43),
+    ("cpp", 54),
+    ("cc", 54),
+    ("cxx", 54),
+    ("cs", 79),
+    ("go", 32),
+    ("hs", 12),
+    ("java", 60),
+    ("js", 55),
+    ("kt", 83),
+    ("pas", 4),
+    ("py", 31),
+    ("rb", 67),
+    ("rs", 75),
+];
+
+/// Extensions of the codes of languages whose lowercase name contains the keyword.
+static EXTENSIONS: &[(&str, &[&str])] = &[
+    ("c++", &["cpp", "cc", "cxx"]),
+    ("g++", &["cpp", "cc", "cxx"]),
+    ("gcc c", &["c"]),
+    ("c#", &["cs"]),
+    ("go ", &["go"]),
+    ("haskell", &["hs"]),
+    ("javascript", &["js"]),
+    ("node.js", &["js"]),
+    ("java ", &["java"]),
+    ("kotlin", &["kt"]),
+    ("pascal", &["pas"]),
+    ("delphi", &["pas"]),
+    ("python", &["py"]),
+    ("pypy", &["py"]),
+    ("ruby", &["rb"]),
+    ("rust", &["rs"]),
+    ("scala", &["scala"]),
+    ("perl", &["pl"]),
+    ("php", &["php"]),
+    ("ocaml", &["ml"]),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Language {
+    pub id: u32,
+    pub name: String,
+    pub compiler: String,
+    pub extensions: Vec<String>,
+}
+
+impl Language {
+    pub fn new(id: u32, name: impl Into<String>, compiler: impl Into<String>) -> Self {
+        let name = name.into();
+        let extensions = guess_extensions(&name);
+        Self {
+            id,
+            name,
+            compiler: compiler.into(),
+            extensions,
+        }
+    }
+
+    /// Parses an option of the submit page like `GNU G++17 7.3.0`, where the compiler version
+    /// starts from the first word beginning with a digit.
+    pub fn from_option(id: u32, text: &str) -> Self {
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let split = text
+            .match_indices(' ')
+            .map(|(i, _)| i)
+            .find(|&i| text[i + 1..].starts_with(|c: char| c.is_ascii_digit()));
+        match split {
+            Some(i) => Self::new(id, &text[..i], &text[i + 1..]),
+            None => Self::new(id, text, ""),
+        }
+    }
+
+    /// A language missing from the catalog, only known by its id.
+    fn unknown(id: u32) -> Self {
+        Self {
+            id,
+            name: format!("programTypeId {id}"),
+            compiler: String::new(),
+            extensions: vec![],
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.compiler.is_empty() {
+            write!(f, "{}", self.name)
+        } else {
+            write!(f, "{} {}", self.name, self.compiler)
+        }
+    }
+}
+
+fn guess_extensions(name: &str) -> Vec<String> {
+    let name = format!("{} ", name.to_lowercase());
+    EXTENSIONS
+        .iter()
+        .find(|(keyword, _)| name.contains(keyword))
+        .map(|(_, extensions)| extensions.iter().map(|ext| ext.to_string()).collect())
+        .unwrap_or_default()
+}
+
+pub fn builtin_languages() -> Vec<Language> {
+    BUILTIN_LANGUAGES
+        .iter()
+        .map(|(id, name, compiler)| Language::new(*id, *name, *compiler))
+        .collect()
+}
+
+fn languages_file_path() -> Result<PathBuf> {
+    Ok(get_config_file_path()?.with_file_name("languages.json"))
+}
+
+/// Returns the refreshed catalog if there is one, or the built-in one.
+///
+/// A catalog which cannot be read is reported in the log and the built-in one is used instead.
+pub fn load_languages() -> Vec<Language> {
+    read_languages().unwrap_or_else(|err| {
+        tracing::error!("Using the built-in languages: {err:#}");
+        builtin_languages()
+    })
+}
+
+fn read_languages() -> Result<Vec<Language>> {
+    let path = languages_file_path()?;
+    if !path.try_exists()? {
+        return Ok(builtin_languages());
+    }
+    let content = fs::read_to_string(&path).wrap_err(format!(
+        "Failed when reading languages from {}",
+        path.display()
+    ))?;
+    serde_json::from_str(&content).wrap_err(format!("Failed to parse {}", path.display()))
+}
+
+/// Keeps `languages` as the catalog, returning the file it is saved to.
+pub fn save_languages(languages: &[Language]) -> Result<PathBuf> {
+    let path = languages_file_path()?;
+    fs::write(&path, serde_json::to_string_pretty(languages)?)
+        .wrap_err(format!("Error occured when writing to {}", path.display()))?;
+    Ok(path)
+}
+
+/// Returns the languages offered by the `programTypeId` select of a submit page.
+pub fn parse_languages(html: &str) -> Result<Vec<Language>> {
+    let document = Document::from(html);
+    let options = document.select(r#"select[name="programTypeId"] option"#);
+    let languages: Vec<Language> = options
+        .iter()
+        .filter_map(|option| {
+            let id = option.attr("value")?.parse().ok()?;
+            Some(Language::from_option(id, &option.text()))
+        })
+        .collect();
+    if languages.is_empty() {
+        bail!("Cannot find any language in the page. Maybe you are not logged in?");
+    }
+    Ok(languages)
+}
+
+/// Scrapes the languages from the submit page of the problemset, which requires the web
+/// session to be logged in.
+pub async fn fetch_languages() -> Result<Vec<Language>> {
+    let url = format!("{}problemset/submit", base_url());
+    let page = CLIENT
+        .get(&url)
+        .send()
+        .await
+        .wrap_err("Error occured when making a GET request")?
+        .error_for_status()
+        .wrap_err(format!("Failed to open {url}"))?
+        .text()
+        .await?;
+    parse_languages(&page).wrap_err(format!("Failed to read languages from {url}"))
+}
+
+/// Replaces the catalog with the languages currently offered by Codeforces.
+pub async fn refresh_languages() -> Result<Vec<Language>> {
+    let languages = fetch_languages().await?;
+    save_languages(&languages)?;
+    Ok(languages)
+}
+
+/// Returns the language of `id` in `catalog`, even if it is missing there.
+pub fn find_language(catalog: &[Language], id: u32) -> Language {
+    catalog
+        .iter()
+        .find(|language| language.id == id)
+        .cloned()
+        .unwrap_or_else(|| Language::unknown(id))
+}
+
+/// Resolves the language of codes with extension `ext`: the one configured for the extension,
+/// then the one configured for the first template of the extension, then the default one.
+pub(crate) fn language_for_extension(
+    ext: &str,
+    preferences: Option<&HashMap<String, u32>>,
+    templates: Option<&Vec<CFTemplate>>,
+    catalog: &[Language],
+) -> Option<Language> {
+    if let Some(id) = preferences.and_then(|preferences| preferences.get(ext)) {
+        return Some(find_language(catalog, *id));
+    }
+    let template_id = templates
+        .into_iter()
+        .flatten()
+        .find(|template| template.path.extension().and_then(|ext| ext.to_str()) == Some(ext))
+        .and_then(|template| template.program_type_id);
+    if let Some(id) = template_id {
+        return Some(find_language(catalog, id));
+    }
+    if let Some((_, id)) = DEFAULT_LANGUAGES.iter().find(|(known, _)| *known == ext) {
+        if catalog.iter().any(|language| language.id == *id) {
+            return Some(find_language(catalog, *id));
+        }
+    }
+    catalog
+        .iter()
+        .find(|language| language.extensions.iter().any(|known| known == ext))
+        .cloned()
+}
+
+/// Returns the language to submit the code at `path` in: the one of `template` if the code was
+/// generated from it, or else the one of its extension.
+pub fn preferred_language(path: &Path, template: Option<&CFTemplate>) -> Result<Language> {
+    if let Some(id) = template.and_then(|template| template.program_type_id) {
+        return Ok(find_language(&load_languages(), id));
+    }
+    let ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default();
+    language_for_extension(
+        ext,
+        SETTINGS.get().languages.as_ref(),
+        SETTINGS.get().templates.as_ref(),
+        &load_languages(),
+    )
+    .ok_or(eyre!(
+        "Cannot tell the language of {}.\nPlease configure it in `languages` of your configuration file.",
+        path.display()
+    ))
+}
+
+/// Returns the language codes generated from `template` are submitted in.
+pub fn template_language(template: &CFTemplate) -> Option<Language> {
+    let catalog = load_languages();
+    match template.program_type_id {
+        Some(id) => Some(find_language(&catalog, id)),
+        None => language_for_extension(
+            template.path.extension()?.to_str()?,
+            SETTINGS.get().languages.as_ref(),
+            None,
+            &catalog,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options() {
+        let language = Language::from_option(54, " GNU G++17 7.3.0\n");
+        assert_eq!(language, Language::new(54, "GNU G++17", "7.3.0"));
+        assert_eq!(language.extensions, vec!["cpp", "cc", "cxx"]);
+        assert_eq!(language.to_string(), "GNU G++17 7.3.0");
+        let language = Language::from_option(79, "C# 10, .NET SDK 6.0");
+        assert_eq!(
+            (language.name.as_str(), language.extensions[0].as_str()),
+            ("C#", "cs")
+        );
+        let language = Language::from_option(60, "Java 11.0.6");
+        assert_eq!(language.extensions, vec!["java"]);
+        let language = Language::from_option(34, "JavaScript V8 4.8.0");
+        assert_eq!(language.extensions, vec!["js"]);
+        let language = Language::from_option(100, "Clang++20 Diagnostics");
+        assert_eq!(language.to_string(), "Clang++20 Diagnostics");
+        assert!(builtin_languages()
+            .iter()
+            .all(|language| !language.extensions.is_empty()));
+    }
+
+    #[test]
+    fn submit_pages() {
+        let html = r#"<select name="programTypeId"><option value="43">GNU GCC C11 5.1.0</option>
+            <option value="70">PyPy 3.10 (7.3.15, 64bit)</option></select>"#;
+        let languages = parse_languages(html).unwrap();
+        assert_eq!(languages.len(), 2);
+        assert_eq!(
+            languages[1],
+            Language::new(70, "PyPy", "3.10 (7.3.15, 64bit)")
+        );
+        assert!(parse_languages("<html></html>").is_err());
+    }
+
+    #[test]
+    fn preferences() {
+        let catalog = builtin_languages();
+        let resolve = |ext, preferences, templates| {
+            language_for_extension(ext, preferences, templates, &catalog)
+                .map(|language| language.id)
+        };
+        assert_eq!(resolve("cpp", None, None), Some(54));
+        assert_eq!(resolve("sh", None, None), None);
+
+        let templates = vec![CFTemplate {
+            alias: String::from("fast"),
+            lang: String::from("cpp"),
+            path: PathBuf::from("fast.cpp"),
+            program_type_id: Some(89),
+        }];
+        assert_eq!(resolve("cpp", None, Some(&templates)), Some(89));
+        assert_eq!(resolve("py", None, Some(&templates)), Some(31));
+
+        let preferences = HashMap::from([(String::from("cpp"), 91), (String::from("py"), 1000)]);
+        assert_eq!(
+            resolve("cpp", Some(&preferences), Some(&templates)),
+            Some(91)
+        );
+        assert_eq!(resolve("py", Some(&preferences), None), Some(1000));
+
+        let catalog = vec![Language::new(70, "PyPy", "3.10")];
+        assert_eq!(
+            language_for_extension("py", None, None, &catalog).map(|language| language.id),
+            Some(70)
+        );
+    }
+}
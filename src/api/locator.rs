@@ -0,0 +1,370 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{eyre::bail, Result};
+
+use super::{
+    objects::{Contest, Problem},
+    utils::base_url,
+};
+
+/// Gym contests have ids starting from this.
+static GYM_MIN_ID: i32 = 100000;
+
+/// Where a problem is published on Codeforces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProblemSource {
+    /// `contest/{id}`
+    Contest(i32),
+    /// `gym/{id}`
+    Gym(i32),
+    /// `group/{code}/contest/{id}`
+    Group(String, i32),
+    /// `problemset/problem/{id}`
+    Problemset(i32),
+    /// `problemsets/acmsguru/problem/99999`
+    Acmsguru,
+}
+
+impl ProblemSource {
+    /// Returns the source of the contest with the id, telling Gym contests by their ids.
+    pub fn from_contest_id(contest_id: i32) -> Self {
+        if contest_id >= GYM_MIN_ID {
+            Self::Gym(contest_id)
+        } else {
+            Self::Contest(contest_id)
+        }
+    }
+
+    /// Returns the source of a contest given as an id or a URL, like `1800`, `gym/102000` or
+    /// `https://codeforces.com/group/xyz/contest/123`.
+    pub fn parse_contest(contest: &str) -> Result<Self> {
+        if let Ok(contest_id) = contest.trim().parse() {
+            return Ok(Self::from_contest_id(contest_id));
+        }
+        let segments = path_segments(contest);
+        let source = match segments.as_slice() {
+            ["contest", id, ..] => id.parse().map(Self::Contest),
+            ["gym", id, ..] => id.parse().map(Self::Gym),
+            ["group", code, "contest", id, ..] => {
+                id.parse().map(|id| Self::Group(code.to_string(), id))
+            }
+            _ => bail!("Cannot recognize contest {contest}."),
+        };
+        match source {
+            Ok(source) => Ok(source),
+            Err(_) => bail!("Cannot recognize contest {contest}."),
+        }
+    }
+
+    /// Returns the id of the contest the problem belongs to, if any.
+    pub fn contest_id(&self) -> Option<i32> {
+        match self {
+            Self::Contest(id) | Self::Gym(id) | Self::Group(_, id) | Self::Problemset(id) => {
+                Some(*id)
+            }
+            Self::Acmsguru => None,
+        }
+    }
+
+    fn path(&self) -> String {
+        match self {
+            Self::Contest(id) => format!("contest/{id}"),
+            Self::Gym(id) => format!("gym/{id}"),
+            Self::Group(code, id) => format!("group/{code}/contest/{id}"),
+            Self::Problemset(_) => String::from("problemset"),
+            Self::Acmsguru => String::from("problemsets/acmsguru"),
+        }
+    }
+
+    /// Returns the page of the contest.
+    pub fn url(&self) -> String {
+        format!("{}{}", base_url(), self.path())
+    }
+
+    /// Returns the page with the statements of every problem of the contest.
+    pub fn problems_url(&self) -> Option<String> {
+        match self {
+            Self::Contest(_) | Self::Gym(_) | Self::Group(_, _) => {
+                Some(format!("{}{}/problems", base_url(), self.path()))
+            }
+            Self::Problemset(_) | Self::Acmsguru => None,
+        }
+    }
+
+    /// Returns where the submit form of the contest is posted to.
+    pub fn submit_url(&self) -> String {
+        match self {
+            Self::Acmsguru => format!("{}problemsets/acmsguru/submit/99999", base_url()),
+            source => format!("{}/submit", source.url()),
+        }
+    }
+
+    /// Returns the page listing the submissions of the current user in the contest.
+    pub fn my_submissions_url(&self) -> String {
+        match self {
+            Self::Problemset(_) => format!("{}problemset/status?my=on", base_url()),
+            Self::Acmsguru => format!("{}problemsets/acmsguru/status?my=on", base_url()),
+            source => format!("{}/my", source.url()),
+        }
+    }
+
+    /// Returns the directory of the contest in the workspace under `home_dir`.
+    pub fn workspace_dir(&self, home_dir: &Path) -> PathBuf {
+        match self {
+            // Problemset problems are the problems of contests, so they share directories.
+            Self::Contest(id) | Self::Problemset(id) => {
+                home_dir.join("Contests").join(id.to_string())
+            }
+            Self::Gym(id) => home_dir.join("Gym").join(id.to_string()),
+            Self::Group(code, id) => home_dir.join("Groups").join(code).join(id.to_string()),
+            Self::Acmsguru => home_dir.join("acmsguru"),
+        }
+    }
+}
+
+impl From<&Contest> for ProblemSource {
+    fn from(contest: &Contest) -> Self {
+        Self::from_contest_id(contest.id)
+    }
+}
+
+impl Display for ProblemSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Contest(id) | Self::Problemset(id) => write!(f, "{id}"),
+            Self::Gym(id) => write!(f, "Gym {id}"),
+            Self::Group(code, id) => write!(f, "Group {code} {id}"),
+            Self::Acmsguru => write!(f, "acmsguru"),
+        }
+    }
+}
+
+/// A problem of a [`ProblemSource`], knowing its pages and where it is kept in the workspace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProblemLocator {
+    pub source: ProblemSource,
+    pub index: String,
+}
+
+impl ProblemLocator {
+    pub fn new(source: ProblemSource, index: impl Into<String>) -> Self {
+        Self {
+            source,
+            index: index.into(),
+        }
+    }
+
+    /// Returns the locator of a problem from the problemset. Problems without a contest are
+    /// taken as acmsguru problems.
+    pub fn from_problem(problem: &Problem) -> Self {
+        let source = match (problem.contestId, problem.problemsetName.as_deref()) {
+            (_, Some("acmsguru")) | (None, _) => ProblemSource::Acmsguru,
+            (Some(contest_id), _) if contest_id >= GYM_MIN_ID => ProblemSource::Gym(contest_id),
+            (Some(contest_id), _) => ProblemSource::Problemset(contest_id),
+        };
+        Self::new(source, problem.index.clone())
+    }
+
+    /// Returns the locator of a problem given as a URL, like
+    /// `https://codeforces.com/gym/102000/problem/B`.
+    pub fn parse(problem: &str) -> Result<Self> {
+        let segments = path_segments(problem);
+        let locator = match segments.as_slice() {
+            ["contest", id, "problem", index] => id
+                .parse()
+                .map(|id| Self::new(ProblemSource::Contest(id), *index)),
+            ["gym", id, "problem", index] => id
+                .parse()
+                .map(|id| Self::new(ProblemSource::Gym(id), *index)),
+            ["group", code, "contest", id, "problem", index] => id
+                .parse()
+                .map(|id| Self::new(ProblemSource::Group(code.to_string(), id), *index)),
+            ["problemset", "problem", id, index] => id
+                .parse()
+                .map(|id| Self::new(ProblemSource::Problemset(id), *index)),
+            ["problemsets", "acmsguru", "problem", "99999", index] => {
+                Ok(Self::new(ProblemSource::Acmsguru, *index))
+            }
+            _ => bail!("Cannot recognize problem {problem}."),
+        };
+        match locator {
+            Ok(locator) => Ok(locator),
+            Err(_) => bail!("Cannot recognize problem {problem}."),
+        }
+    }
+
+    /// Returns the page of the problem.
+    pub fn url(&self) -> String {
+        let index = &self.index;
+        match &self.source {
+            ProblemSource::Problemset(id) => {
+                format!("{}problemset/problem/{id}/{index}", base_url())
+            }
+            ProblemSource::Acmsguru => {
+                format!("{}problemsets/acmsguru/problem/99999/{index}", base_url())
+            }
+            source => format!("{}/problem/{index}", source.url()),
+        }
+    }
+
+    /// Returns the page to submit solutions of the problem.
+    pub fn submit_url(&self) -> String {
+        let index = &self.index;
+        match &self.source {
+            ProblemSource::Problemset(id) => {
+                format!("{}problemset/submit/{id}/{index}", base_url())
+            }
+            ProblemSource::Acmsguru => {
+                format!("{}problemsets/acmsguru/submit/99999/{index}", base_url())
+            }
+            source => format!("{}/submit/{index}", source.url()),
+        }
+    }
+
+    /// Returns the field and the value identifying the problem in the submit form.
+    pub fn submit_form_problem(&self) -> (&'static str, String) {
+        match &self.source {
+            ProblemSource::Problemset(id) => {
+                ("submittedProblemCode", format!("{id}{}", self.index))
+            }
+            ProblemSource::Acmsguru => ("submittedProblemCode", self.index.clone()),
+            _ => ("submittedProblemIndex", self.index.clone()),
+        }
+    }
+
+    /// Returns the directory of the problem in the workspace under `home_dir`.
+    pub fn workspace_dir(&self, home_dir: &Path) -> PathBuf {
+        self.source.workspace_dir(home_dir).join(&self.index)
+    }
+}
+
+impl Display for ProblemLocator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.source {
+            ProblemSource::Contest(_) | ProblemSource::Problemset(_) => {
+                write!(f, "{}{}", self.source, self.index)
+            }
+            source => write!(f, "{source} {}", self.index),
+        }
+    }
+}
+
+/// Splits a URL or a path into segments, dropping the scheme and the host.
+fn path_segments(url: &str) -> Vec<&str> {
+    let path = url.trim();
+    let path = match path.split_once("://") {
+        Some((_, rest)) => rest.split_once('/').map_or("", |(_, path)| path),
+        None => path,
+    };
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls() {
+        let base = base_url();
+        let locator = ProblemLocator::new(ProblemSource::Contest(1), "A");
+        assert_eq!(locator.url(), format!("{base}contest/1/problem/A"));
+        assert_eq!(locator.submit_url(), format!("{base}contest/1/submit/A"));
+        let locator = ProblemLocator::new(ProblemSource::Gym(102000), "B");
+        assert_eq!(locator.url(), format!("{base}gym/102000/problem/B"));
+        let locator = ProblemLocator::new(ProblemSource::Group("abc".to_string(), 3), "C");
+        assert_eq!(
+            locator.url(),
+            format!("{base}group/abc/contest/3/problem/C")
+        );
+        assert_eq!(
+            locator.source.problems_url(),
+            Some(format!("{base}group/abc/contest/3/problems"))
+        );
+        let locator = ProblemLocator::new(ProblemSource::Problemset(1), "A");
+        assert_eq!(locator.url(), format!("{base}problemset/problem/1/A"));
+        assert_eq!(locator.submit_url(), format!("{base}problemset/submit/1/A"));
+        assert_eq!(
+            locator.source.submit_url(),
+            format!("{base}problemset/submit")
+        );
+        assert_eq!(
+            locator.submit_form_problem(),
+            ("submittedProblemCode", "1A".to_string())
+        );
+        let locator = ProblemLocator::new(ProblemSource::Acmsguru, "100");
+        assert_eq!(
+            locator.url(),
+            format!("{base}problemsets/acmsguru/problem/99999/100")
+        );
+        assert_eq!(locator.source.problems_url(), None);
+    }
+
+    #[test]
+    fn workspace_dirs() {
+        let home = Path::new("/cf");
+        let dir = |source, index| ProblemLocator::new(source, index).workspace_dir(home);
+        assert_eq!(
+            dir(ProblemSource::Contest(1), "A"),
+            Path::new("/cf/Contests/1/A")
+        );
+        assert_eq!(
+            dir(ProblemSource::Problemset(1), "A"),
+            Path::new("/cf/Contests/1/A")
+        );
+        assert_eq!(
+            dir(ProblemSource::Gym(102000), "B"),
+            Path::new("/cf/Gym/102000/B")
+        );
+        assert_eq!(
+            dir(ProblemSource::Group("abc".to_string(), 3), "C"),
+            Path::new("/cf/Groups/abc/3/C")
+        );
+        assert_eq!(
+            dir(ProblemSource::Acmsguru, "100"),
+            Path::new("/cf/acmsguru/100")
+        );
+    }
+
+    #[test]
+    fn parse_urls() {
+        for url in [
+            "https://codeforces.com/contest/1/problem/A",
+            "https://codeforces.com/gym/102000/problem/B?locale=en",
+            "group/abc/contest/3/problem/C",
+            "/problemset/problem/1/A",
+            "https://codeforces.com/problemsets/acmsguru/problem/99999/100",
+        ] {
+            let locator = ProblemLocator::parse(url).unwrap();
+            assert!(url.starts_with("https://") || locator.url().ends_with(url));
+        }
+        assert_eq!(
+            ProblemLocator::parse("https://codeforces.com/gym/102000/problem/B").unwrap(),
+            ProblemLocator::new(ProblemSource::Gym(102000), "B")
+        );
+        assert!(ProblemLocator::parse("https://codeforces.com/blog/entry/1").is_err());
+    }
+
+    #[test]
+    fn parse_contests() {
+        assert_eq!(
+            ProblemSource::parse_contest("566").unwrap(),
+            ProblemSource::Contest(566)
+        );
+        assert_eq!(
+            ProblemSource::parse_contest("102000").unwrap(),
+            ProblemSource::Gym(102000)
+        );
+        assert_eq!(
+            ProblemSource::parse_contest("https://codeforces.com/group/abc/contest/3/standings")
+                .unwrap(),
+            ProblemSource::Group("abc".to_string(), 3)
+        );
+        assert!(ProblemSource::parse_contest("contest/abc").is_err());
+    }
+}
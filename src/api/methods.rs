@@ -2,33 +2,69 @@
 #![allow(non_camel_case_types)]
 //! Reference: [Codeforces Official API Documentation - Return objects](https://codeforces.com/apiHelp/methods)
 
-
+use std::{future::Future, time::Duration};
 
 use color_eyre::{
     eyre::{bail, WrapErr},
-    Result,
+    Report, Result,
 };
+use futures::{stream, Stream, TryStreamExt};
 use lazy_static::lazy_static;
-use reqwest::Client;
+use reqwest::{Client, Proxy};
 use serde::Deserialize;
 
-use crate::settings::SETTINGS;
+use crate::{
+    credentials::CREDENTIALS,
+    settings::{CFHttp, CLIENT_HTTP},
+};
 
 use super::{
     objects::{
         BlogEntry, Comment, Contest, Hack, ProblemSet, RatingChange, RecentAction, Standings,
         Submission, User,
     },
+    session::COOKIE_JAR,
     utils::{get_authorize, CFApiResponse, CFApiResponseStatus, CFApiUrl},
 };
 
+static DEFAULT_USER_AGENT: &str = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+/// Seconds to wait for a connection, unless `connect_timeout` is configured.
+static DEFAULT_CONNECT_TIMEOUT: u64 = 10;
+/// Seconds to wait for a response, unless `read_timeout` is configured.
+static DEFAULT_READ_TIMEOUT: u64 = 30;
+
 lazy_static! {
-    pub static ref CLIENT: Client = Client::builder()
-        .user_agent("Mozilla/5.0 (X11; U; Linux x86_64; zh-CN; rv:1.9.2.10) Gecko/20100922 Ubuntu/10.10 (maverick) Firefox/3.6.10")
-        .cookie_store(true)
-        .build()
-        .wrap_err("Failed to build Reqwest client")
-        .unwrap();
+    /// The client of every request. Invalid `http` settings are reported at startup and by
+    /// `doctor`, so they are only logged here and the defaults are used instead.
+    pub static ref CLIENT: Client = build_client(Some(&CLIENT_HTTP)).unwrap_or_else(|err| {
+        tracing::error!("Using the default http settings: {err:#}");
+        build_client(None).expect("The default client can be built")
+    });
+}
+
+/// Builds the client of every request to Codeforces as configured in `http`.
+///
+/// Without a configured proxy, the `HTTP_PROXY` and `HTTPS_PROXY` environment variables are
+/// used.
+pub fn build_client(http: Option<&CFHttp>) -> Result<Client> {
+    let http = http.cloned().unwrap_or_default();
+    let mut builder = Client::builder()
+        .user_agent(http.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT))
+        .connect_timeout(Duration::from_secs(
+            http.connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT),
+        ))
+        .timeout(Duration::from_secs(
+            http.read_timeout.unwrap_or(DEFAULT_READ_TIMEOUT),
+        ))
+        .cookie_provider(COOKIE_JAR.clone());
+    if let Some(proxy) = http
+        .proxy
+        .as_deref()
+        .filter(|proxy| !proxy.trim().is_empty())
+    {
+        builder = builder.proxy(Proxy::all(proxy).wrap_err(format!("Invalid proxy: {proxy}"))?);
+    }
+    Ok(builder.build()?)
 }
 
 async fn request<T>(url: String) -> Result<T>
@@ -67,11 +103,12 @@ async fn request_smart<T>(url: &mut CFApiUrl, must_authorize: bool) -> Result<T>
 where
     T: for<'a> Deserialize<'a>,
 {
+    let credentials = CREDENTIALS.get();
     if must_authorize {
-        let (key, secret) = get_authorize(&SETTINGS.key, &SETTINGS.secret)?;
+        let (key, secret) = get_authorize(&credentials.key, &credentials.secret)?;
         Ok(request(url.authorize(key, secret)).await?)
     } else {
-        match get_authorize(&SETTINGS.key, &SETTINGS.secret) {
+        match get_authorize(&credentials.key, &credentials.secret) {
             Ok((key, secret)) => Ok(request(url.authorize(key, secret))
                 .await
                 .unwrap_or(request(url.into_url()).await?)),
@@ -159,7 +196,7 @@ pub async fn contest_list(gym: Option<bool>) -> Result<Vec<Contest>> {
 /// **Example**: [https://codeforces.com/api/contest.ratingChanges?contestId=566](https://codeforces.com/api/contest.ratingChanges?contestId=566)
 pub async fn contest_ratingChanges(contestId: i32) -> Result<Vec<RatingChange>> {
     request_smart::<Vec<RatingChange>>(
-        CFApiUrl::new("contest.hacks").add_required_parameter("contestId", contestId),
+        CFApiUrl::new("contest.ratingChanges").add_required_parameter("contestId", contestId),
         false,
     )
     .await
@@ -235,6 +272,22 @@ pub async fn contest_status(
     .await
 }
 
+/// Streams submissions for specified contest, fetching `pageSize` submissions per request.
+///
+/// Pages are fetched lazily, so a caller that has seen enough can simply stop polling.
+/// The stream ends after the first error.
+///
+/// See [`contest_status`] for the parameters.
+pub fn contest_status_stream(
+    contestId: i32,
+    handle: Option<String>,
+    pageSize: i32,
+) -> impl Stream<Item = Result<Submission>> {
+    paginate(pageSize, move |from, count| {
+        contest_status(contestId, handle.clone(), Some(from), Some(count))
+    })
+}
+
 /// Returns all problems from problemset. Problems can be filtered by tags.
 ///
 /// | Parameter | Description |
@@ -279,7 +332,7 @@ pub async fn problemset_recentStatus(
     problemsetName: Option<String>,
 ) -> Result<Vec<Submission>> {
     request_smart::<Vec<Submission>>(
-        CFApiUrl::new("problemset.problems")
+        CFApiUrl::new("problemset.recentStatus")
             .add_required_parameter("count", count)
             .add_parameter("problemsetName", problemsetName),
         false,
@@ -315,7 +368,7 @@ pub async fn recentActions(maxCount: i32) -> Result<Vec<RecentAction>> {
 /// **Example**: [https://codeforces.com/api/user.blogEntries?handle=Fefer\_Ivan](https://codeforces.com/api/user.blogEntries?handle=Fefer_Ivan)
 pub async fn user_blogEntries(handle: String) -> Result<Vec<BlogEntry>> {
     request_smart::<Vec<BlogEntry>>(
-        CFApiUrl::new("u Note that this is the representation you get by default if you return an error from fn main instead of printing it explicitly yourser.blogEntries").add_required_parameter("handle", handle),
+        CFApiUrl::new("user.blogEntries").add_required_parameter("handle", handle),
         false,
     )
     .await
@@ -338,6 +391,12 @@ pub async fn user_friends(onlyOnline: Option<bool>) -> Result<Vec<String>> {
     .await
 }
 
+/// Checks that the API key and secret are accepted, with an authorized call to `user.friends`.
+pub async fn check_authorization() -> Result<()> {
+    request_smart::<Vec<String>>(&mut CFApiUrl::new("user.friends"), true).await?;
+    Ok(())
+}
+
 /// Returns information about one or several users.
 ///
 /// | Parameter | Description |
@@ -424,6 +483,48 @@ pub async fn user_status(
     .await
 }
 
+/// Streams submissions of specified user, fetching `pageSize` submissions per request.
+///
+/// Pages are fetched lazily, so a caller that has seen enough can simply stop polling.
+/// The stream ends after the first error.
+///
+/// See [`user_status`] for the parameters.
+pub fn user_status_stream(handle: String, pageSize: i32) -> impl Stream<Item = Result<Submission>> {
+    paginate(pageSize, move |from, count| {
+        user_status(handle.clone(), Some(from), Some(count))
+    })
+}
+
+/// Turns a method taking 1-based `from` and `count` into a stream of its items.
+///
+/// A page shorter than `count` is taken as the last one.
+fn paginate<T, F, Fut>(count: i32, fetch: F) -> impl Stream<Item = Result<T>>
+where
+    F: Fn(i32, i32) -> Fut,
+    Fut: Future<Output = Result<Vec<T>>>,
+{
+    let count = count.max(1);
+    stream::try_unfold(Some(1), move |from| {
+        let page = from.map(|from| (from, fetch(from, count)));
+        async move {
+            match page {
+                Some((from, page)) => {
+                    let items = page.await?;
+                    let next = if items.len() < count as usize {
+                        None
+                    } else {
+                        Some(from + count)
+                    };
+                    let items = stream::iter(items.into_iter().map(Ok::<T, Report>));
+                    Ok::<_, Report>(Some((items, next)))
+                }
+                None => Ok(None),
+            }
+        }
+    })
+    .try_flatten()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -437,6 +538,75 @@ mod tests {
         }
     }
 
+    mod build_client_test {
+        use super::{build_client, CFHttp};
+
+        #[test]
+        fn proxies() {
+            let http = |proxy: &str| CFHttp {
+                proxy: Some(proxy.to_string()),
+                ..Default::default()
+            };
+            build_client(None).unwrap();
+            build_client(Some(&http("http://127.0.0.1:7890"))).unwrap();
+            build_client(Some(&http("socks5://127.0.0.1:1080"))).unwrap();
+            build_client(Some(&http(""))).unwrap();
+            assert!(build_client(Some(&http("not a proxy"))).is_err());
+        }
+    }
+
+    mod paginate_test {
+        use color_eyre::{eyre::eyre, Result};
+        use futures::{executor::block_on, StreamExt, TryStreamExt};
+        use std::sync::{Arc, Mutex};
+
+        use super::paginate;
+
+        fn fetch(
+            total: i32,
+            requests: Arc<Mutex<Vec<(i32, i32)>>>,
+        ) -> impl Fn(i32, i32) -> futures::future::Ready<Result<Vec<i32>>> {
+            move |from, count| {
+                requests.lock().unwrap().push((from, count));
+                futures::future::ready(Ok((from..=total).take(count as usize).collect()))
+            }
+        }
+
+        #[test]
+        fn all_pages() {
+            let requests = Arc::new(Mutex::new(vec![]));
+            let items: Vec<i32> =
+                block_on(paginate(3, fetch(7, requests.clone())).try_collect()).unwrap();
+            assert_eq!(items, (1..=7).collect::<Vec<i32>>());
+            assert_eq!(*requests.lock().unwrap(), vec![(1, 3), (4, 3), (7, 3)]);
+        }
+
+        #[test]
+        fn stops_early() {
+            let requests = Arc::new(Mutex::new(vec![]));
+            let items: Vec<Result<i32>> =
+                block_on(paginate(3, fetch(100, requests.clone())).take(4).collect());
+            assert_eq!(items.len(), 4);
+            assert_eq!(*requests.lock().unwrap(), vec![(1, 3), (4, 3)]);
+        }
+
+        #[test]
+        fn stops_on_error() {
+            let items: Vec<Result<i32>> = block_on(
+                paginate(3, |from, _| {
+                    futures::future::ready(if from == 1 {
+                        Ok(vec![1, 2, 3])
+                    } else {
+                        Err(eyre!("Server returned status FAILED"))
+                    })
+                })
+                .collect(),
+            );
+            assert_eq!(items.len(), 4);
+            assert!(items[3].is_err());
+        }
+    }
+
     mod contest_list_test {
         use super::contest_list;
 
@@ -1,5 +1,8 @@
 pub mod error;
+pub mod languages;
+pub mod locator;
 pub mod methods;
 pub mod objects;
 pub mod parse;
+pub mod session;
 pub mod utils;
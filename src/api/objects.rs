@@ -4,7 +4,55 @@
 
 use std::fmt::Display;
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Declares an enum returned by the API.
+///
+/// Values unknown to cf-tool are kept as `Unknown` with the raw value instead of failing the
+/// whole response, as Codeforces adds new variants from time to time.
+macro_rules! api_enum {
+    ($(#[$meta:meta])* pub enum $name:ident { $($variant:ident,)* }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum $name {
+            $($variant,)*
+            /// A value unknown to cf-tool, kept as is.
+            Unknown(String),
+        }
+
+        impl $name {
+            /// Returns the raw value used by the API.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => stringify!($variant),)*
+                    $name::Unknown(value) => value,
+                }
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                match value {
+                    $(stringify!($variant) => $name::$variant,)*
+                    value => $name::Unknown(value.to_string()),
+                }
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = String::deserialize(deserializer)?;
+                Ok($name::from(value.as_str()))
+            }
+        }
+    };
+}
 
 /// Represents a Codeforces user.
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -125,22 +173,24 @@ pub struct RatingChange {
     pub newRating: i32,
 }
 
-/// Scoring system used for a [`Contest`].
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub enum ContestType {
-    CF,
-    IOI,
-    ICPC,
+api_enum! {
+    /// Scoring system used for a [`Contest`].
+    pub enum ContestType {
+        CF,
+        IOI,
+        ICPC,
+    }
 }
 
-/// The phase a [`Contest`] is in.
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub enum ContestPhase {
-    BEFORE,
-    CODING,
-    PENDING_SYSTEM_TEST,
-    SYSTEM_TEST,
-    FINISHED,
+api_enum! {
+    /// The phase a [`Contest`] is in.
+    pub enum ContestPhase {
+        BEFORE,
+        CODING,
+        PENDING_SYSTEM_TEST,
+        SYSTEM_TEST,
+        FINISHED,
+    }
 }
 
 /// Represents a contest on Codeforces.
@@ -182,14 +232,15 @@ pub struct Contest {
     pub season: Option<String>,
 }
 
-/// Type of participant in a [`Party`].
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub enum ParticipantType {
-    CONTESTANT,
-    PRACTICE,
-    VIRTUAL,
-    MANAGER,
-    OUT_OF_COMPETITION,
+api_enum! {
+    /// Type of participant in a [`Party`].
+    pub enum ParticipantType {
+        CONTESTANT,
+        PRACTICE,
+        VIRTUAL,
+        MANAGER,
+        OUT_OF_COMPETITION,
+    }
 }
 
 /// Represents a party, participating in a contest.
@@ -222,11 +273,12 @@ pub struct Member {
     pub name: Option<String>,
 }
 
-/// Type of a [`Problem`].
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub enum ProblemType {
-    PROGRAMMING,
-    QUESTION,
+api_enum! {
+    /// Type of a [`Problem`].
+    pub enum ProblemType {
+        PROGRAMMING,
+        QUESTION,
+    }
 }
 
 /// Represents a problem.
@@ -261,26 +313,27 @@ pub struct ProblemStatistics {
     pub solvedCount: i32,
 }
 
-/// Verdict of a [`Submission`].
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub enum SubmissionVerdict {
-    FAILED,
-    OK,
-    PARTIAL,
-    COMPILATION_ERROR,
-    RUNTIME_ERROR,
-    WRONG_ANSWER,
-    PRESENTATION_ERROR,
-    TIME_LIMIT_EXCEEDED,
-    MEMORY_LIMIT_EXCEEDED,
-    IDLENESS_LIMIT_EXCEEDED,
-    SECURITY_VIOLATED,
-    CRASHED,
-    INPUT_PREPARATION_CRASHED,
-    CHALLENGED,
-    SKIPPED,
-    TESTING,
-    REJECTED,
+api_enum! {
+    /// Verdict of a [`Submission`].
+    pub enum SubmissionVerdict {
+        FAILED,
+        OK,
+        PARTIAL,
+        COMPILATION_ERROR,
+        RUNTIME_ERROR,
+        WRONG_ANSWER,
+        PRESENTATION_ERROR,
+        TIME_LIMIT_EXCEEDED,
+        MEMORY_LIMIT_EXCEEDED,
+        IDLENESS_LIMIT_EXCEEDED,
+        SECURITY_VIOLATED,
+        CRASHED,
+        INPUT_PREPARATION_CRASHED,
+        CHALLENGED,
+        SKIPPED,
+        TESTING,
+        REJECTED,
+    }
 }
 
 impl Display for SubmissionVerdict {
@@ -306,28 +359,30 @@ impl Display for SubmissionVerdict {
                 SubmissionVerdict::SKIPPED => "Skipped",
                 SubmissionVerdict::TESTING => "Testing",
                 SubmissionVerdict::REJECTED => "Rejected",
+                SubmissionVerdict::Unknown(verdict) => verdict.as_str(),
             }
         )
     }
 }
 
-/// Testset used for judging a [`Submission`].
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub enum SubmissionTestset {
-    SAMPLES,
-    PRETESTS,
-    TESTS,
-    CHALLENGES,
-    TESTS1,
-    TESTS2,
-    TESTS3,
-    TESTS4,
-    TESTS5,
-    TESTS6,
-    TESTS7,
-    TESTS8,
-    TESTS9,
-    TESTS10,
+api_enum! {
+    /// Testset used for judging a [`Submission`].
+    pub enum SubmissionTestset {
+        SAMPLES,
+        PRETESTS,
+        TESTS,
+        CHALLENGES,
+        TESTS1,
+        TESTS2,
+        TESTS3,
+        TESTS4,
+        TESTS5,
+        TESTS6,
+        TESTS7,
+        TESTS8,
+        TESTS9,
+        TESTS10,
+    }
 }
 
 /// Represents a submission.
@@ -361,17 +416,18 @@ pub struct Submission {
     pub points: Option<f32>,
 }
 
-/// Verdict of a [`Hack`].
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub enum HackVerdict {
-    HACK_SUCCESSFUL,
-    HACK_UNSUCCESSFUL,
-    INVALID_INPUT,
-    GENERATOR_INCOMPILABLE,
-    GENERATOR_CRASHED,
-    IGNORED,
-    TESTING,
-    OTHER,
+api_enum! {
+    /// Verdict of a [`Hack`].
+    pub enum HackVerdict {
+        HACK_SUCCESSFUL,
+        HACK_UNSUCCESSFUL,
+        INVALID_INPUT,
+        GENERATOR_INCOMPILABLE,
+        GENERATOR_CRASHED,
+        IGNORED,
+        TESTING,
+        OTHER,
+    }
 }
 
 /// Judge protocol used for a [`Hack`].
@@ -427,12 +483,13 @@ pub struct RanklistRow {
     pub lastSubmissionTimeSeconds: Option<i64>,
 }
 
-/// Type of a [`ProblemResult`].
-/// If type is PRELIMINARY then points can decrease (if, for example, solution will fail during system test). Otherwise, party can only increase points for this problem by submitting better solutions.
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub enum ProblemResultType {
-    PRELIMINARY,
-    FINAL,
+api_enum! {
+    /// Type of a [`ProblemResult`].
+    /// If type is PRELIMINARY then points can decrease (if, for example, solution will fail during system test). Otherwise, party can only increase points for this problem by submitting better solutions.
+    pub enum ProblemResultType {
+        PRELIMINARY,
+        FINAL,
+    }
 }
 
 /// Represents a submissions results of a party for a problem.
@@ -462,3 +519,43 @@ pub struct ProblemSet {
     pub problems: Vec<Problem>,
     pub problemStatistics: Vec<ProblemStatistics>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_variant() {
+        let verdict: SubmissionVerdict = serde_json::from_str("\"WRONG_ANSWER\"").unwrap();
+        assert_eq!(verdict, SubmissionVerdict::WRONG_ANSWER);
+        assert_eq!(serde_json::to_string(&verdict).unwrap(), "\"WRONG_ANSWER\"");
+    }
+
+    #[test]
+    fn unknown_variant() {
+        let testset: SubmissionTestset = serde_json::from_str("\"TESTS11\"").unwrap();
+        assert_eq!(testset, SubmissionTestset::Unknown(String::from("TESTS11")));
+        assert_eq!(serde_json::to_string(&testset).unwrap(), "\"TESTS11\"");
+    }
+
+    #[test]
+    fn unknown_verdict_is_displayed_raw() {
+        let verdict: SubmissionVerdict = serde_json::from_str("\"WAITING\"").unwrap();
+        assert_eq!(verdict.to_string(), "WAITING");
+    }
+
+    #[test]
+    fn unknown_variant_in_object() {
+        let json = r#"{
+            "contestId": 1,
+            "members": [{"handle": "tourist"}],
+            "participantType": "OBSERVER",
+            "ghost": false
+        }"#;
+        let party: Party = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            party.participantType,
+            ParticipantType::Unknown(String::from("OBSERVER"))
+        );
+    }
+}
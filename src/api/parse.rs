@@ -1,15 +1,43 @@
-use color_eyre::{eyre::Context, Result};
-use nipper::Document;
+use std::collections::HashMap;
 
-use super::methods::CLIENT;
+use color_eyre::{
+    eyre::{bail, Context},
+    Result,
+};
+use nipper::{Document, Node, Selection};
 
-#[derive(Debug, Clone)]
+use super::{methods::CLIENT, utils::base_url};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct TestCase {
     pub input: String,
     pub answer: String,
+    /// The test each line of the input belongs to, when the sample marks them for highlighting.
+    pub input_groups: Vec<usize>,
 }
 
-pub async fn parse_testcase(url: String) -> Result<Vec<TestCase>> {
+/// A problem statement as shown on its Codeforces page.
+///
+/// Text sections are plain text with paragraphs separated by blank lines. Formulas are kept
+/// as LaTeX between `$` (`$$` for display formulas), including the ones Codeforces renders
+/// with `tex-span` markup, and images are referenced as `![](url)`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProblemStatement {
+    pub title: String,
+    pub time_limit: String,
+    pub memory_limit: String,
+    pub input_file: String,
+    pub output_file: String,
+    pub legend: String,
+    pub input_specification: String,
+    pub output_specification: String,
+    pub interaction: Option<String>,
+    pub note: Option<String>,
+    pub samples: Vec<TestCase>,
+    pub images: Vec<String>,
+}
+
+async fn get(url: String) -> Result<String> {
     let response = CLIENT
         .get(url)
         .send()
@@ -19,29 +47,687 @@ pub async fn parse_testcase(url: String) -> Result<Vec<TestCase>> {
     let text_error_message = format!(
         "Server returned status: {status_code}.\n\nFailed to parse\n{response:#?}\ninto text"
     );
-    let response = response.text().await.wrap_err(text_error_message)?;
+    response.text().await.wrap_err(text_error_message)
+}
 
-    let document = Document::from(&response);
-    let inputs: Vec<String> = document
-        .select("div.input")
+#[derive(Debug, Default)]
+struct SampleLine {
+    text: String,
+    group: Option<usize>,
+}
+
+fn is_line_div(node: &Node) -> bool {
+    node.node_name().as_deref() == Some("div") && node.has_class("test-example-line")
+}
+
+/// Splits the content of a sample `pre` into lines, whether they are separated by newlines,
+/// `<br>` or wrapped in `div.test-example-line`.
+fn collect_sample_lines(node: &Node, lines: &mut Vec<SampleLine>) {
+    let children = node.children();
+    let has_line_divs = children.iter().any(is_line_div);
+    for child in children {
+        if child.is_text() {
+            let text = child.text();
+            // Whitespace between line divs is only formatting of the page.
+            if has_line_divs && text.trim().is_empty() {
+                continue;
+            }
+            let text = text.replace("\r\n", "\n").replace('\r', "\n");
+            let mut parts = text.split('\n');
+            if let (Some(first), Some(line)) = (parts.next(), lines.last_mut()) {
+                line.text.push_str(first);
+            }
+            for part in parts {
+                lines.push(SampleLine {
+                    text: part.to_string(),
+                    group: None,
+                });
+            }
+        } else if is_line_div(&child) {
+            if lines.last().is_none_or(|line| !line.text.is_empty()) {
+                lines.push(SampleLine::default());
+            }
+            let group = child.attr("class").and_then(|class| {
+                class
+                    .split_whitespace()
+                    .filter_map(|class| class.strip_prefix("test-example-line-"))
+                    .find_map(|group| group.parse().ok())
+            });
+            if let Some(line) = lines.last_mut() {
+                line.group = group;
+            }
+            collect_sample_lines(&child, lines);
+            lines.push(SampleLine::default());
+        } else if child.node_name().as_deref() == Some("br") {
+            lines.push(SampleLine::default());
+        } else if child.is_element() {
+            collect_sample_lines(&child, lines);
+        }
+    }
+}
+
+fn sample_lines(pre: &Node) -> Vec<SampleLine> {
+    let mut lines = vec![SampleLine::default()];
+    collect_sample_lines(pre, &mut lines);
+    while lines.last().is_some_and(|line| line.text.trim().is_empty()) {
+        lines.pop();
+    }
+    for line in lines.iter_mut() {
+        line.text = line.text.replace('\u{a0}', " ");
+    }
+    lines
+}
+
+fn sample_text(lines: &[SampleLine]) -> String {
+    lines
+        .iter()
+        .map(|line| format!("{}\n", line.text))
+        .collect()
+}
+
+fn parse_samples(document: &Selection) -> Vec<TestCase> {
+    let inputs: Vec<Vec<SampleLine>> = document
+        .select("div.input pre")
+        .nodes()
         .iter()
-        .map(|input| input.select("pre"))
-        .map(|input| String::from(input.text()))
+        .map(sample_lines)
         .collect();
-    let outputs: Vec<String> = document
-        .select("div.output")
+    let outputs: Vec<Vec<SampleLine>> = document
+        .select("div.output pre")
+        .nodes()
         .iter()
-        .map(|output| output.select("pre"))
-        .map(|output| String::from(output.text()))
+        .map(sample_lines)
         .collect();
-    let test_cases: Vec<TestCase> = inputs
+    inputs
         .into_iter()
         .zip(outputs.into_iter())
-        .map(|(input, output)| TestCase {
-            input,
-            answer: output,
+        .map(|(input, output)| {
+            let input_groups = if input.iter().any(|line| line.group.is_some()) {
+                input
+                    .iter()
+                    .map(|line| line.group.unwrap_or_default())
+                    .collect()
+            } else {
+                vec![]
+            };
+            TestCase {
+                input: sample_text(&input),
+                answer: sample_text(&output),
+                input_groups,
+            }
         })
+        .collect()
+}
+
+/// Downloads a file referenced by a statement, like an image.
+pub async fn download(url: String) -> Result<Vec<u8>> {
+    let response = CLIENT
+        .get(&url)
+        .send()
+        .await
+        .wrap_err("Error occured when making a GET request")?
+        .error_for_status()
+        .wrap_err(format!("Failed to download {url}"))?;
+    Ok(response.bytes().await?.to_vec())
+}
+
+pub async fn parse_testcase(url: String) -> Result<Vec<TestCase>> {
+    let response = get(url).await?;
+    let document = Document::from(&response);
+    Ok(parse_samples(&document.select("html")))
+}
+
+pub async fn parse_statement(url: String) -> Result<ProblemStatement> {
+    let response = get(url).await?;
+    ProblemStatement::from_html(&response)
+}
+
+/// What is parsed out of a problem page.
+#[derive(Debug, Clone)]
+pub enum ProblemPage {
+    Statement(Box<ProblemStatement>),
+    /// The samples of a page without an HTML statement, like the problems only given as PDF.
+    Samples(Vec<TestCase>),
+}
+
+impl ProblemPage {
+    pub fn from_html(html: &str) -> Self {
+        match ProblemStatement::from_html(html) {
+            Ok(statement) => ProblemPage::Statement(Box::new(statement)),
+            Err(_) => ProblemPage::Samples(parse_samples(&Document::from(html).select("html"))),
+        }
+    }
+}
+
+/// Parses the statement of a problem page, or only its samples when it has no statement.
+pub async fn parse_problem_page(url: String) -> Result<ProblemPage> {
+    let response = get(url).await?;
+    Ok(ProblemPage::from_html(&response))
+}
+
+/// Parses the statements on the page of all problems of a contest, keyed by problem index.
+pub async fn parse_contest_statements(url: String) -> Result<HashMap<String, ProblemStatement>> {
+    let response = get(url).await?;
+    Ok(ProblemStatement::all_from_html(&response))
+}
+
+impl ProblemStatement {
+    /// Parses the statement out of a problem page.
+    pub fn from_html(html: &str) -> Result<Self> {
+        let document = Document::from(html);
+        let statement = document.select("div.problem-statement").first();
+        if !statement.exists() {
+            bail!("No problem statement found in the page.");
+        }
+        Ok(Self::from_selection(&statement))
+    }
+
+    /// Parses every statement out of the page of all problems of a contest, keyed by problem
+    /// index. Problems without an HTML statement, like the ones only given as PDF, are left out.
+    pub fn all_from_html(html: &str) -> HashMap<String, Self> {
+        let document = Document::from(html);
+        document
+            .select("div.problemindexholder")
+            .iter()
+            .filter_map(|holder| {
+                let index = holder.attr("problemindex")?.to_string();
+                let statement = holder.select("div.problem-statement").first();
+                if !statement.exists() {
+                    return None;
+                }
+                Some((index, Self::from_selection(&statement)))
+            })
+            .collect()
+    }
+
+    fn from_selection(statement: &Selection) -> Self {
+        let mut images = vec![];
+        let property = |class: &str| -> String {
+            statement
+                .select(&format!("div.header div.{class}"))
+                .iter()
+                .next()
+                .map(|property| {
+                    let title = property.select("div.property-title").text();
+                    property.text().replacen(&*title, "", 1)
+                })
+                .map(|text| normalize(&text))
+                .unwrap_or_default()
+        };
+        let title = property("title");
+        let time_limit = property("time-limit");
+        let memory_limit = property("memory-limit");
+        let input_file = property("input-file");
+        let output_file = property("output-file");
+
+        let mut section = |class: &str| -> Option<String> {
+            let section = statement.select(&format!("div.{class}"));
+            let node = section.nodes().first()?;
+            Some(render(node, &mut images))
+        };
+        let input_specification = section("input-specification").unwrap_or_default();
+        let output_specification = section("output-specification").unwrap_or_default();
+        let interaction = section("interaction");
+        let note = section("note");
+
+        // The legend is the only section without a class.
+        let legend = statement
+            .children()
+            .nodes()
+            .iter()
+            .find(|node| node.is_element() && node.attr("class").is_none())
+            .map(|node| render(node, &mut images))
+            .unwrap_or_default();
+
+        Self {
+            title,
+            time_limit,
+            memory_limit,
+            input_file,
+            output_file,
+            legend,
+            input_specification,
+            output_specification,
+            interaction,
+            note,
+            samples: parse_samples(statement),
+            images,
+        }
+    }
+
+    /// Formats the statement as Markdown, with the limits in a header and images referenced
+    /// by [`image_file_name`] so they can be saved next to it.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!(
+            "# {}\n\n\
+            - time limit per test: {}\n\
+            - memory limit per test: {}\n\
+            - input: {}\n\
+            - output: {}\n",
+            self.title, self.time_limit, self.memory_limit, self.input_file, self.output_file
+        );
+        let mut section = |title: Option<&str>, content: &str| {
+            if let Some(title) = title {
+                markdown.push_str(&format!("\n## {title}\n"));
+            }
+            if !content.is_empty() {
+                markdown.push_str(&format!("\n{content}\n"));
+            }
+        };
+        section(None, &self.legend);
+        section(Some("Input"), &self.input_specification);
+        section(Some("Output"), &self.output_specification);
+        if let Some(interaction) = &self.interaction {
+            section(Some("Interaction"), interaction);
+        }
+        if !self.samples.is_empty() {
+            let samples: Vec<String> = self
+                .samples
+                .iter()
+                .enumerate()
+                .map(|(i, sample)| {
+                    let id = i + 1;
+                    format!(
+                        "### Input #{id}\n\n```\n{}\n```\n\n### Output #{id}\n\n```\n{}\n```",
+                        sample.input.trim_end(),
+                        sample.answer.trim_end()
+                    )
+                })
+                .collect();
+            section(Some("Examples"), &samples.join("\n\n"));
+        }
+        if let Some(note) = &self.note {
+            section(Some("Note"), note);
+        }
+        for (index, url) in self.images.iter().enumerate() {
+            markdown = markdown.replace(
+                &format!("![]({url})"),
+                &format!("![]({})", image_file_name(index, url)),
+            );
+        }
+        markdown
+    }
+}
+
+/// Returns the name the image at `index` of a statement is saved as, which is the last segment
+/// of its URL after the index, as images from different paths can have the same name.
+pub fn image_file_name(index: usize, url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let name = path.rsplit('/').next().unwrap_or_default();
+    format!("{}-{name}", index + 1)
+}
+
+/// Renders the content of a statement section as text, collecting the images in it.
+fn render(node: &Node, images: &mut Vec<String>) -> String {
+    let mut rendered = Rendered {
+        images,
+        blocks: vec![],
+        text: String::new(),
+    };
+    for child in node.children() {
+        render_node(&child, &mut rendered);
+    }
+    rendered.finish()
+}
+
+/// A statement section being rendered. Preformatted text is kept in blocks of its own, apart
+/// from the text whose whitespace is collapsed.
+struct Rendered<'a> {
+    images: &'a mut Vec<String>,
+    blocks: Vec<String>,
+    text: String,
+}
+
+impl Rendered<'_> {
+    /// Ends the text rendered so far as a block.
+    fn flush(&mut self) {
+        let text = std::mem::take(&mut self.text)
+            .replace("$$$$$$", "$$")
+            .replace("$$$", "$");
+        let text = normalize(&text);
+        if !text.is_empty() {
+            self.blocks.push(text);
+        }
+    }
+
+    fn push_preformatted(&mut self, text: &str) {
+        self.flush();
+        let text = text.replace('\u{a0}', " ");
+        let text = text.trim_matches('\n');
+        if !text.is_empty() {
+            self.blocks.push(text.to_string());
+        }
+    }
+
+    fn finish(mut self) -> String {
+        self.flush();
+        self.blocks.join("\n\n")
+    }
+}
+
+fn render_node(node: &Node, rendered: &mut Rendered) {
+    if node.is_text() {
+        rendered.text.push_str(&node.text());
+        return;
+    }
+    if !node.is_element() {
+        return;
+    }
+    let children = |rendered: &mut Rendered| {
+        for child in node.children() {
+            render_node(&child, rendered);
+        }
+    };
+    let name = node.node_name().map(|name| name.to_string());
+    match name.as_deref() {
+        Some("div") if node.has_class("section-title") => {}
+        Some("span") if node.has_class("tex-span") => {
+            rendered.text.push('$');
+            children(rendered);
+            rendered.text.push('$');
+        }
+        Some("span") if node.has_class("tex-font-style-bf") => {
+            rendered.text.push_str("**");
+            children(rendered);
+            rendered.text.push_str("**");
+        }
+        Some("span") if node.has_class("tex-font-style-it") => {
+            rendered.text.push('*');
+            children(rendered);
+            rendered.text.push('*');
+        }
+        Some("span") if node.has_class("tex-font-style-tt") => {
+            rendered.text.push('`');
+            children(rendered);
+            rendered.text.push('`');
+        }
+        Some("sub") => {
+            rendered.text.push_str("_{");
+            children(rendered);
+            rendered.text.push('}');
+        }
+        Some("sup") => {
+            rendered.text.push_str("^{");
+            children(rendered);
+            rendered.text.push('}');
+        }
+        Some("br") => rendered.text.push('\n'),
+        Some("img") => {
+            if let Some(src) = node.attr("src") {
+                let url = absolute_url(&src);
+                rendered.text.push_str(&format!("![]({url})"));
+                if !rendered.images.contains(&url) {
+                    rendered.images.push(url);
+                }
+            }
+        }
+        Some("li") => {
+            rendered.text.push_str("\n- ");
+            children(rendered);
+        }
+        Some("pre") => rendered.push_preformatted(&node.text()),
+        Some("p" | "div" | "center" | "ul" | "ol" | "table" | "tr") => {
+            rendered.text.push_str("\n\n");
+            children(rendered);
+            rendered.text.push_str("\n\n");
+        }
+        _ => children(rendered),
+    }
+}
+
+fn absolute_url(src: &str) -> String {
+    if src.starts_with("http://") || src.starts_with("https://") {
+        src.to_string()
+    } else if let Some(src) = src.strip_prefix("//") {
+        format!("https://{src}")
+    } else {
+        format!("{}{}", base_url(), src.trim_start_matches('/'))
+    }
+}
+
+/// Collapses HTML whitespace, keeping line breaks and paragraphs separated by one blank line.
+fn normalize(text: &str) -> String {
+    let lines: Vec<String> = text
+        .replace('\u{a0}', " ")
+        .split('\n')
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
         .collect();
+    let mut paragraphs = vec![];
+    for paragraph in lines.split(|line| line.is_empty()) {
+        if !paragraph.is_empty() {
+            paragraphs.push(paragraph.join("\n"));
+        }
+    }
+    paragraphs.join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statement_with_tex_span() {
+        let statement =
+            ProblemStatement::from_html(include_str!("../mock/fixtures/problem/1A.html")).unwrap();
+        assert_eq!(statement.title, "A. Theatre Square");
+        assert_eq!(statement.time_limit, "1 second");
+        assert_eq!(statement.memory_limit, "256 megabytes");
+        assert_eq!(statement.input_file, "standard input");
+        assert_eq!(statement.output_file, "standard output");
+        assert!(statement
+            .legend
+            .starts_with("Theatre Square in the capital city"));
+        assert_eq!(statement.legend.split("\n\n").count(), 2);
+        assert!(statement.legend.contains("with the size $n × m$ meters"));
+        assert!(statement
+            .input_specification
+            .contains("($1 ≤ n, m, a ≤ 10^{9}$)"));
+        assert_eq!(
+            statement.output_specification,
+            "Write the needed number of flagstones."
+        );
+        assert_eq!(statement.interaction, None);
+        assert_eq!(statement.note, None);
+        assert_eq!(statement.samples.len(), 1);
+        assert!(statement.images.is_empty());
+    }
+
+    #[test]
+    fn statement_with_latex() {
+        let statement =
+            ProblemStatement::from_html(include_str!("../mock/fixtures/problem/1B.html")).unwrap();
+        assert_eq!(statement.title, "B. Spreadsheet");
+        assert!(statement
+            .input_specification
+            .contains("integer number $n$ ($1 \\le n \\le 10^5$)"));
+    }
+
+    #[test]
+    fn statement_with_image() {
+        let statement =
+            ProblemStatement::from_html(include_str!("../mock/fixtures/problem/1C.html")).unwrap();
+        let url = format!("{}predownloaded/1C.png", base_url());
+        assert_eq!(statement.images, vec![url.clone()]);
+        assert!(statement.legend.ends_with(&format!("![]({url})")));
+    }
+
+    #[test]
+    fn statement_with_interaction_and_note() {
+        let html = r#"<div class="problem-statement">
+            <div class="header"><div class="title">E. Guess</div></div>
+            <div><p>Guess $$$x$$$.</p><p>$$$$$$1 \le x \le n$$$$$$</p></div>
+            <div class="input-specification"><div class="section-title">Input</div>
+                <p>The only line contains <span class="tex-font-style-bf">n</span>.</p></div>
+            <div class="output-specification"><div class="section-title">Output</div></div>
+            <div class="interaction"><div class="section-title">Interaction</div>
+                <ul><li>Print <span class="tex-font-style-tt">? y</span>.</li><li>Flush.</li></ul></div>
+            <div class="note"><div class="section-title">Note</div>
+                <p>See <img src="/predownloaded/e.png" />.</p></div>
+        </div>"#;
+        let statement = ProblemStatement::from_html(html).unwrap();
+        assert_eq!(statement.legend, "Guess $x$.\n\n$$1 \\le x \\le n$$");
+        assert_eq!(
+            statement.input_specification,
+            "The only line contains **n**."
+        );
+        assert_eq!(statement.output_specification, "");
+        assert_eq!(
+            statement.interaction.as_deref(),
+            Some("- Print `? y`.\n- Flush.")
+        );
+        let url = format!("{}predownloaded/e.png", base_url());
+        assert_eq!(statement.note, Some(format!("See ![]({url}).")));
+        assert_eq!(statement.images, vec![url]);
+    }
+
+    #[test]
+    fn statement_with_preformatted_text() {
+        let html = r#"<div class="problem-statement">
+            <div class="header"><div class="title">F. Grid</div></div>
+            <div><p>The   grid is:</p><pre>
+#..#
+.  .
+
+#..#
+</pre><p>Count   it.</p></div>
+            <div class="input-specification"><div class="section-title">Input</div></div>
+            <div class="output-specification"><div class="section-title">Output</div></div>
+        </div>"#;
+        let statement = ProblemStatement::from_html(html).unwrap();
+        assert_eq!(
+            statement.legend,
+            "The grid is:\n\n#..#\n.  .\n\n#..#\n\nCount it."
+        );
+    }
+
+    #[test]
+    fn markdown() {
+        let statement =
+            ProblemStatement::from_html(include_str!("../mock/fixtures/problem/1C.html")).unwrap();
+        let markdown = statement.to_markdown();
+        assert!(markdown.starts_with(
+            "# C. Ancient Berland Circus\n\n\
+            - time limit per test: 2 seconds\n\
+            - memory limit per test: 64 megabytes\n\
+            - input: standard input\n\
+            - output: standard output\n\n\
+            Nowadays all circuses"
+        ));
+        assert!(markdown.contains("could have.\n\n![](1-1C.png)\n\n## Input\n\n"));
+        assert!(markdown.ends_with("### Output #1\n\n```\n1.00000000\n```\n"));
+
+        let statement =
+            ProblemStatement::from_html(include_str!("../mock/fixtures/problem/1B.html")).unwrap();
+        assert!(statement
+            .to_markdown()
+            .contains("integer number $n$ ($1 \\le n \\le 10^5$)"));
+    }
+
+    #[test]
+    fn page_without_statement() {
+        let html = r#"<div class="problemindexholder" problemindex="A">
+            <div class="input"><pre>1 2</pre></div><div class="output"><pre>3</pre></div>
+        </div>"#;
+        match ProblemPage::from_html(html) {
+            ProblemPage::Samples(samples) => assert_eq!(samples[0].answer, "3\n"),
+            ProblemPage::Statement(_) => panic!("There is no statement"),
+        }
+        assert!(matches!(
+            ProblemPage::from_html(include_str!("../mock/fixtures/problem/1A.html")),
+            ProblemPage::Statement(_)
+        ));
+    }
+
+    #[test]
+    fn image_file_names() {
+        assert_eq!(
+            image_file_name(0, "https://espresso.codeforces.com/1c9c1f.png?v=2"),
+            "1-1c9c1f.png"
+        );
+        assert_eq!(image_file_name(1, "/predownloaded/e.png"), "2-e.png");
+    }
+
+    #[test]
+    fn images_with_the_same_name() {
+        let html = r#"<div class="problem-statement">
+            <div class="header"><div class="title">G. Trees</div></div>
+            <div><p><img src="/a/tree.png" /> <img src="/b/tree.png" /> <img src="/a/tree.png" /></p></div>
+            <div class="input-specification"><div class="section-title">Input</div></div>
+            <div class="output-specification"><div class="section-title">Output</div></div>
+        </div>"#;
+        let statement = ProblemStatement::from_html(html).unwrap();
+        assert_eq!(statement.images.len(), 2);
+        assert!(statement
+            .to_markdown()
+            .contains("![](1-tree.png) ![](2-tree.png) ![](1-tree.png)"));
+    }
+
+    fn samples(html: &str) -> Vec<TestCase> {
+        parse_samples(&Document::from(html).select("html"))
+    }
+
+    #[test]
+    fn samples_with_br() {
+        let samples = samples(include_str!("fixtures/samples/br.html"));
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].input, "3\n1 2 3\n\n4 5\n");
+        assert_eq!(samples[0].answer, "6\n");
+        assert_eq!(samples[1].input, "0\n");
+        assert!(samples[0].input_groups.is_empty());
+    }
+
+    #[test]
+    fn samples_with_line_divs() {
+        let samples = samples(include_str!("fixtures/samples/lines.html"));
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].input, "2\n3\n1 2 3\n\n1\n5\n");
+        assert_eq!(samples[0].input_groups, vec![0, 1, 1, 1, 2, 2]);
+        assert_eq!(samples[0].answer, "6\n5\n");
+    }
+
+    #[test]
+    fn samples_with_crlf() {
+        let samples = samples(include_str!("fixtures/samples/crlf.html"));
+        assert_eq!(samples[0].input, "2\nab\n\ncd\n");
+        assert_eq!(samples[0].answer, "YES\nNO\n");
+    }
+
+    #[test]
+    fn samples_with_nbsp() {
+        let samples = samples(include_str!("fixtures/samples/nbsp.html"));
+        assert_eq!(samples[0].input, "1 2\n3  4\n");
+        assert_eq!(samples[0].answer, "3 7\n");
+    }
+
+    #[test]
+    fn samples_of_problem_pages() {
+        let statement =
+            ProblemStatement::from_html(include_str!("../mock/fixtures/problem/1A.html")).unwrap();
+        assert_eq!(statement.samples[0].input, "6 6 4\n");
+        let statement =
+            ProblemStatement::from_html(include_str!("../mock/fixtures/problem/1B.html")).unwrap();
+        assert_eq!(statement.samples[0].input, "2\nR23C55\nBC23\n");
+        assert_eq!(statement.samples[0].input_groups, vec![0, 1, 1]);
+        assert_eq!(statement.samples[0].answer, "BC23\nR23C55\n");
+    }
+
+    #[test]
+    fn statements_of_contest_page() {
+        let statements =
+            ProblemStatement::all_from_html(include_str!("../mock/fixtures/problems/1.html"));
+        let mut indexes: Vec<&str> = statements.keys().map(String::as_str).collect();
+        indexes.sort();
+        assert_eq!(indexes, vec!["A", "B", "C"]);
+        assert_eq!(
+            statements["A"],
+            ProblemStatement::from_html(include_str!("../mock/fixtures/problem/1A.html")).unwrap()
+        );
+        assert_eq!(statements["C"].title, "C. Ancient Berland Circus");
+        assert!(ProblemStatement::all_from_html("<html><body></body></html>").is_empty());
+    }
 
-    Ok(test_cases)
+    #[test]
+    fn no_statement() {
+        assert!(ProblemStatement::from_html("<html><body></body></html>").is_err());
+    }
 }
@@ -0,0 +1,472 @@
+//! The web session on Codeforces, for what the API does not offer, like submitting.
+//!
+//! Requests share the cookies of [`COOKIE_JAR`] through [`CLIENT`], and every cookie set by
+//! Codeforces is saved in `session.json` of the configuration directory, so the session
+//! outlives a run until it expires.
+
+use std::{
+    fs,
+    path::PathBuf,
+    sync::{Arc, Mutex, RwLock},
+};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use color_eyre::{
+    eyre::{bail, eyre, Context},
+    Result,
+};
+use lazy_static::lazy_static;
+use nipper::Document;
+use rand::Rng;
+use reqwest::{
+    cookie::{CookieStore, Jar},
+    header::HeaderValue,
+    Url,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::settings::{get_config_file_path, write_private_file};
+
+use super::{locator::ProblemLocator, methods::CLIENT, utils::base_url};
+
+/// The cookie Codeforces sets by logging in to remember the user, and never to a mere visitor.
+static LOGIN_COOKIE: &str = "X-User-Sha1";
+
+/// Codeforces computes `_tta` with a script on its pages, but accepts this value as well.
+static TTA: &str = "176";
+
+lazy_static! {
+    /// Cookies of the web session, shared by every request made with [`CLIENT`].
+    pub static ref COOKIE_JAR: Arc<SessionCookies> = Arc::new(SessionCookies::load());
+    /// Browser fingerprints posted along with forms, kept for the whole run like a browser does.
+    static ref FTAA: String = random_string(18, b"abcdefghijklmnopqrstuvwxyz0123456789");
+    static ref BFAA: String = random_string(32, b"0123456789abcdef");
+}
+
+/// A cookie as set by Codeforces, saved in `session.json` of the configuration directory.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+struct SavedCookie {
+    url: String,
+    set_cookie: String,
+    /// When the cookie was set, in seconds since the epoch, which `Max-Age` counts from.
+    received: i64,
+}
+
+impl SavedCookie {
+    fn name(&self) -> &str {
+        self.set_cookie
+            .split(['=', ';'])
+            .next()
+            .unwrap_or_default()
+            .trim()
+    }
+
+    fn attribute(&self, key: &str) -> Option<&str> {
+        self.set_cookie
+            .split(';')
+            .skip(1)
+            .filter_map(|attribute| attribute.split_once('='))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.trim())
+    }
+
+    /// Returns when the cookie expires, or `None` if it lasts for the session.
+    fn expires(&self) -> Option<i64> {
+        if let Some(max_age) = self.attribute("Max-Age") {
+            return max_age.parse::<i64>().ok().map(|age| self.received + age);
+        }
+        let expires = self.attribute("Expires")?;
+        DateTime::parse_from_rfc2822(expires)
+            .map(|time| time.timestamp())
+            .or_else(|_| {
+                NaiveDateTime::parse_from_str(expires, "%a, %d-%b-%Y %H:%M:%S GMT")
+                    .map(|time| time.timestamp())
+            })
+            .ok()
+    }
+
+    fn is_expired(&self, now: i64) -> bool {
+        self.expires().is_some_and(|expires| expires <= now)
+    }
+}
+
+/// The cookie store of [`CLIENT`], saving every cookie it is given for the next runs.
+pub struct SessionCookies {
+    jar: RwLock<Jar>,
+    saved: Mutex<Vec<SavedCookie>>,
+}
+
+fn random_string(len: usize, charset: &[u8]) -> String {
+    let mut rng = rand::thread_rng();
+    (0..len)
+        .map(|_| charset[rng.gen_range(0..charset.len())] as char)
+        .collect()
+}
+
+fn session_file_path() -> Result<PathBuf> {
+    Ok(get_config_file_path()?.with_file_name("session.json"))
+}
+
+impl SessionCookies {
+    /// Restores the cookies of the last session that have not expired yet.
+    fn load() -> Self {
+        let now = Utc::now().timestamp();
+        let saved: Vec<SavedCookie> = session_file_path()
+            .and_then(|path| Ok(fs::read_to_string(path)?))
+            .and_then(|content| Ok(serde_json::from_str(&content)?))
+            .unwrap_or_else(|_: color_eyre::Report| vec![]);
+        let saved: Vec<SavedCookie> = saved
+            .into_iter()
+            .filter(|cookie| !cookie.is_expired(now))
+            .collect();
+        let jar = Jar::default();
+        for cookie in &saved {
+            if let Ok(url) = Url::parse(&cookie.url) {
+                jar.add_cookie_str(&cookie.set_cookie, &url);
+            }
+        }
+        Self {
+            jar: RwLock::new(jar),
+            saved: Mutex::new(saved),
+        }
+    }
+
+    fn save(saved: &[SavedCookie]) -> Result<()> {
+        let path = session_file_path()?;
+        write_private_file(&path, &serde_json::to_string_pretty(saved)?)
+    }
+
+    /// Returns whether the cookies kept from a previous run were set by logging in.
+    pub fn has_logged_in(&self) -> bool {
+        self.saved
+            .lock()
+            .is_ok_and(|saved| saved.iter().any(|cookie| cookie.name() == LOGIN_COOKIE))
+    }
+
+    /// Forgets every cookie, here and in `session.json`.
+    pub fn clear(&self) -> Result<()> {
+        if let Ok(mut jar) = self.jar.write() {
+            *jar = Jar::default();
+        }
+        if let Ok(mut saved) = self.saved.lock() {
+            saved.clear();
+        }
+        let path = session_file_path()?;
+        if path.exists() {
+            fs::remove_file(&path).wrap_err(format!("Failed to remove {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Adds cookies given as `Set-Cookie` headers for `url`, e.g. from another tool, and saves them.
+    pub fn import(&self, url: &Url, set_cookies: &[String]) -> Result<()> {
+        let headers = set_cookies
+            .iter()
+            .map(|set_cookie| HeaderValue::from_str(set_cookie))
+            .collect::<Result<Vec<_>, _>>()
+            .wrap_err("Invalid cookie")?;
+        self.set_cookies(&mut headers.iter(), url);
+        Ok(())
+    }
+}
+
+impl CookieStore for SessionCookies {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        let headers: Vec<&HeaderValue> = cookie_headers.collect();
+        if let Ok(jar) = self.jar.read() {
+            jar.set_cookies(&mut headers.iter().copied(), url);
+        }
+        let now = Utc::now().timestamp();
+        if let Ok(mut saved) = self.saved.lock() {
+            for header in headers {
+                let cookie = match header.to_str() {
+                    Ok(set_cookie) => SavedCookie {
+                        url: url.to_string(),
+                        set_cookie: set_cookie.to_string(),
+                        received: now,
+                    },
+                    Err(_) => continue,
+                };
+                saved.retain(|saved| {
+                    saved.name() != cookie.name()
+                        || Url::parse(&saved.url).ok().as_ref().and_then(Url::host_str)
+                            != url.host_str()
+                });
+                if !cookie.is_expired(now) {
+                    saved.push(cookie);
+                }
+            }
+            if let Err(err) = Self::save(&saved) {
+                tracing::error!("Failed to save the cookies: {err:#}");
+            }
+        }
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        self.jar.read().ok()?.cookies(url)
+    }
+}
+
+async fn get_page(url: &str) -> Result<String> {
+    let response = CLIENT
+        .get(url)
+        .send()
+        .await
+        .wrap_err("Error occured when making a GET request")?
+        .error_for_status()
+        .wrap_err(format!("Failed to open {url}"))?;
+    Ok(response.text().await?)
+}
+
+async fn post_form(url: &str, form: &[(&str, &str)]) -> Result<String> {
+    let response = CLIENT
+        .post(url)
+        .form(form)
+        .send()
+        .await
+        .wrap_err("Error occured when making a POST request")?
+        .error_for_status()
+        .wrap_err(format!("Failed to post to {url}"))?;
+    Ok(response.text().await?)
+}
+
+/// Returns the token that every form posted from the page must carry.
+fn csrf_token(html: &str) -> Result<String> {
+    let document = Document::from(html);
+    let meta = document.select(r#"meta[name="X-Csrf-Token"]"#);
+    if let Some(token) = meta.attr("content") {
+        return Ok(token.to_string());
+    }
+    let span = document.select("span.csrf-token");
+    if let Some(token) = span.attr("data-csrf") {
+        return Ok(token.to_string());
+    }
+    bail!("Cannot find the CSRF token in the page.")
+}
+
+/// Returns the handle the page is shown to, if it is shown to a logged in user.
+pub fn logged_in_handle(html: &str) -> Option<String> {
+    let document = Document::from(html);
+    let links = document.select("div.lang-chooser a");
+    let links: Vec<_> = links.iter().collect();
+    let logged_in = links
+        .iter()
+        .any(|link| link.attr_or("href", "").ends_with("/logout"));
+    if !logged_in {
+        return None;
+    }
+    links
+        .iter()
+        .find(|link| link.attr_or("href", "").starts_with("/profile/"))
+        .map(|link| link.text().trim().to_string())
+}
+
+/// Returns the message of the first error shown next to a field of a posted form.
+fn form_error(html: &str) -> Option<String> {
+    let document = Document::from(html);
+    let errors = document.select("span.error");
+    let error = errors
+        .iter()
+        .map(|error| error.text().trim().to_string())
+        .find(|error| !error.is_empty());
+    error
+}
+
+/// Returns the newest submission listed in the page.
+fn latest_submission_id(html: &str) -> Option<i32> {
+    let document = Document::from(html);
+    let rows = document.select("[data-submission-id]");
+    rows.attr("data-submission-id")?.parse().ok()
+}
+
+/// Returns the link to log out of the session the page is shown to.
+fn logout_link(html: &str) -> Option<String> {
+    let document = Document::from(html);
+    let links = document.select("div.lang-chooser a");
+    let link = links
+        .iter()
+        .map(|link| link.attr_or("href", "").to_string())
+        .find(|href| href.ends_with("/logout"));
+    link
+}
+
+/// Explains why the web session is not logged in, telling an expired session from no session.
+fn not_logged_in() -> color_eyre::Report {
+    if COOKIE_JAR.has_logged_in() {
+        eyre!("The session has expired.\nPlease log in again with `cf-cli login`.")
+    } else {
+        eyre!("Not logged in.\nPlease log in with `cf-cli login`.")
+    }
+}
+
+/// Returns the handle logged in to the web session, if any.
+pub async fn current_handle() -> Result<Option<String>> {
+    let page = get_page(&base_url()).await?;
+    Ok(logged_in_handle(&page))
+}
+
+/// Returns the handle logged in to the web session, failing if the session has expired.
+pub async fn whoami() -> Result<String> {
+    current_handle().await?.ok_or_else(not_logged_in)
+}
+
+/// Logs out of the web session and forgets its cookies, returning the handle logged out from.
+pub async fn logout() -> Result<Option<String>> {
+    let page = get_page(&base_url()).await?;
+    let handle = logged_in_handle(&page);
+    if let Some(link) = logout_link(&page) {
+        let url = base_url();
+        let url = Url::parse(&url)?.join(&link)?;
+        get_page(url.as_str()).await?;
+    }
+    COOKIE_JAR.clear()?;
+    Ok(handle)
+}
+
+/// Logs in to the web session, returning the handle logged in as.
+pub async fn login(handle: &str, password: &str) -> Result<String> {
+    let url = format!("{}enter", base_url());
+    let page = get_page(&url).await?;
+    let csrf_token = csrf_token(&page)?;
+    let page = post_form(
+        &url,
+        &[
+            ("csrf_token", &csrf_token),
+            ("action", "enter"),
+            ("ftaa", &FTAA),
+            ("bfaa", &BFAA),
+            ("handleOrEmail", handle),
+            ("password", password),
+            ("_tta", TTA),
+            ("remember", "on"),
+        ],
+    )
+    .await?;
+    match logged_in_handle(&page) {
+        Some(handle) => Ok(handle),
+        None => bail!(
+            "Failed to log in as {handle}: {}",
+            form_error(&page).unwrap_or_else(|| String::from("Unknown error"))
+        ),
+    }
+}
+
+/// Makes sure the web session is logged in as `handle`, logging in with `password` if not.
+pub async fn ensure_login(handle: &str, password: Option<&str>) -> Result<String> {
+    if let Some(current) = current_handle().await? {
+        if current.eq_ignore_ascii_case(handle) {
+            return Ok(current);
+        }
+    }
+    let password = password.ok_or_else(|| {
+        not_logged_in().wrap_err(format!(
+            "Not logged in as {handle} and no password configured."
+        ))
+    })?;
+    login(handle, password).await
+}
+
+/// Submits `source` as `program_type_id` to the problem with the logged in web session,
+/// returning the id of the submission.
+pub async fn submit(locator: &ProblemLocator, program_type_id: u32, source: &str) -> Result<i32> {
+    let page = get_page(&locator.submit_url()).await?;
+    if logged_in_handle(&page).is_none() {
+        return Err(not_logged_in());
+    }
+    let csrf_token = csrf_token(&page)?;
+    let (problem_field, problem) = locator.submit_form_problem();
+    let url = format!("{}?csrf_token={csrf_token}", locator.source.submit_url());
+    let program_type_id = program_type_id.to_string();
+    let page = post_form(
+        &url,
+        &[
+            ("csrf_token", &csrf_token),
+            ("ftaa", &FTAA),
+            ("bfaa", &BFAA),
+            ("action", "submitSolutionFormSubmitted"),
+            (problem_field, &problem),
+            ("programTypeId", &program_type_id),
+            ("source", source),
+            ("tabSize", "4"),
+            ("sourceFile", ""),
+            ("_tta", TTA),
+        ],
+    )
+    .await?;
+    if let Some(error) = form_error(&page) {
+        bail!("Failed to submit Problem {}: {error}", locator.index);
+    }
+    // Codeforces redirects to the submissions of the user, with the new one on top.
+    let id = match latest_submission_id(&page) {
+        Some(id) => id,
+        None => {
+            let url = locator.source.my_submissions_url();
+            latest_submission_id(&get_page(&url).await?)
+                .ok_or(eyre!("Cannot find the submission in {url}"))?
+        }
+    };
+    Ok(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csrf_tokens() {
+        let html = r#"<html><head><meta name="X-Csrf-Token" content="abc"/></head></html>"#;
+        assert_eq!(csrf_token(html).unwrap(), "abc");
+        let html = r#"<html><body><span class="csrf-token" data-csrf="def"></span></body></html>"#;
+        assert_eq!(csrf_token(html).unwrap(), "def");
+        assert!(csrf_token("<html></html>").is_err());
+    }
+
+    #[test]
+    fn logged_in_handles() {
+        let html = r#"<div class="lang-chooser"><a href="/profile/tourist">tourist</a> | <a href="/abc/logout">Logout</a></div>"#;
+        assert_eq!(logged_in_handle(html), Some(String::from("tourist")));
+        assert_eq!(logout_link(html), Some(String::from("/abc/logout")));
+        let html = r#"<div class="lang-chooser"><a href="/enter">Enter</a> | <a href="/register">Register</a></div>"#;
+        assert_eq!(logged_in_handle(html), None);
+        assert_eq!(logout_link(html), None);
+    }
+
+    #[test]
+    fn cookie_expiry() {
+        let cookie = |set_cookie: &str| SavedCookie {
+            url: String::from("https://codeforces.com/"),
+            set_cookie: set_cookie.to_string(),
+            received: 1000,
+        };
+        let session = cookie("JSESSIONID=abc; Path=/; HttpOnly");
+        assert_eq!(session.name(), "JSESSIONID");
+        assert_eq!(session.expires(), None);
+        assert!(!session.is_expired(i64::MAX));
+
+        let aged = cookie("39ce7=xyz; Max-Age=100; Path=/");
+        assert_eq!(aged.name(), "39ce7");
+        assert_eq!(aged.expires(), Some(1100));
+        assert!(!aged.is_expired(1099));
+        assert!(aged.is_expired(1100));
+        assert!(cookie("JSESSIONID=; Max-Age=0").is_expired(1000));
+
+        let dated = cookie("X-User=1; expires=Thu, 01 Jan 1970 00:16:40 GMT; path=/");
+        assert_eq!(dated.expires(), Some(1000));
+        let dated = cookie("X-User=1; Expires=Thu, 01-Jan-1970 00:16:40 GMT");
+        assert_eq!(dated.expires(), Some(1000));
+    }
+
+    #[test]
+    fn submission_pages() {
+        let html = r#"<table><tr><th>#</th></tr><tr data-submission-id="42"></tr><tr data-submission-id="41"></tr></table>"#;
+        assert_eq!(latest_submission_id(html), Some(42));
+        let html = r#"<form><span class="error for__source">You have submitted exactly the same code before</span></form>"#;
+        assert_eq!(
+            form_error(html),
+            Some(String::from(
+                "You have submitted exactly the same code before"
+            ))
+        );
+        assert_eq!(form_error(r#"<span class="error"></span>"#), None);
+    }
+}
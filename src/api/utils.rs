@@ -1,12 +1,15 @@
 #![allow(non_snake_case)]
 #![allow(non_camel_case_types)]
-use std::time::SystemTime;
+use std::{env, sync::RwLock, time::SystemTime};
 
 use anyhow::Result;
+use lazy_static::lazy_static;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha512};
 
+use crate::settings::{CFHttp, SETTINGS};
+
 use super::error::NoAuthorizationError;
 
 fn rand() -> String {
@@ -36,10 +39,75 @@ pub(super) struct CFApiUrl {
 pub static BASEURL: &str = "https://codeforces.com/";
 pub static API_BASEURL: &str = "https://codeforces.com/api/";
 
+lazy_static! {
+    /// The website every request is pointed to instead of the configured one.
+    static ref BASEURL_OVERRIDE: RwLock<Option<String>> =
+        RwLock::new(match env::var("CF_BASE_URL") {
+            Ok(url) if !url.trim().is_empty() => Some(normalize_base_url(&url)),
+            _ => None,
+        });
+}
+
+fn normalize_base_url(url: &str) -> String {
+    let url = url.trim();
+    if url.ends_with('/') {
+        url.to_string()
+    } else {
+        format!("{url}/")
+    }
+}
+
+fn base_url_override() -> Option<String> {
+    BASEURL_OVERRIDE.read().ok()?.clone()
+}
+
+/// Returns the base URL of the website, e.g. `https://codeforces.com/`.
+///
+/// Defaults to `base_url` of the `http` settings, then [`BASEURL`], unless overridden by the
+/// `CF_BASE_URL` environment variable or [`set_base_url`].
+pub fn base_url() -> String {
+    resolve_base_url(base_url_override(), SETTINGS.get().http.as_ref())
+}
+
+fn resolve_base_url(url_override: Option<String>, http: Option<&CFHttp>) -> String {
+    if let Some(url) = url_override {
+        return url;
+    }
+    http.and_then(|http| http.base_url.as_deref())
+        .filter(|url| !url.trim().is_empty())
+        .map(normalize_base_url)
+        .unwrap_or_else(|| String::from(BASEURL))
+}
+
+/// Returns the base URL of the API, e.g. `https://codeforces.com/api/`.
+///
+/// Defaults to `api_base_url` of the `http` settings, then `api/` under [`base_url`], which
+/// is always used when the website is overridden.
+pub fn api_base_url() -> String {
+    resolve_api_base_url(base_url_override(), SETTINGS.get().http.as_ref())
+}
+
+fn resolve_api_base_url(url_override: Option<String>, http: Option<&CFHttp>) -> String {
+    let configured = http
+        .and_then(|http| http.api_base_url.as_deref())
+        .filter(|url| !url.trim().is_empty());
+    match (url_override, configured) {
+        (None, Some(url)) => normalize_base_url(url),
+        (url_override, _) => format!("{}api/", resolve_base_url(url_override, http)),
+    }
+}
+
+/// Points every following request to another website, e.g. a local `cf-mock` server.
+pub fn set_base_url(url: &str) {
+    if let Ok(mut current) = BASEURL_OVERRIDE.write() {
+        *current = Some(normalize_base_url(url));
+    }
+}
+
 impl CFApiUrl {
     pub fn new(endpoint: &str) -> Self {
         Self {
-            baseurl: String::from(API_BASEURL),
+            baseurl: api_base_url(),
             endpoint: String::from(endpoint),
             parameters: vec![],
         }
@@ -145,6 +213,43 @@ pub(super) fn get_authorize<'a>(
 mod tests {
     use super::*;
 
+    #[test]
+    fn base_urls() {
+        assert_eq!(resolve_base_url(None, None), BASEURL);
+        assert_eq!(resolve_api_base_url(None, None), API_BASEURL);
+
+        let mirror = CFHttp {
+            base_url: Some(String::from("https://codeforc.es")),
+            ..Default::default()
+        };
+        assert_eq!(
+            resolve_base_url(None, Some(&mirror)),
+            "https://codeforc.es/"
+        );
+        assert_eq!(
+            resolve_api_base_url(None, Some(&mirror)),
+            "https://codeforc.es/api/"
+        );
+
+        let api = CFHttp {
+            api_base_url: Some(String::from("https://mirror.example/api")),
+            ..mirror
+        };
+        assert_eq!(
+            resolve_api_base_url(None, Some(&api)),
+            "https://mirror.example/api/"
+        );
+        let local = Some(String::from("http://127.0.0.1:8080/"));
+        assert_eq!(
+            resolve_base_url(local.clone(), Some(&api)),
+            "http://127.0.0.1:8080/"
+        );
+        assert_eq!(
+            resolve_api_base_url(local, Some(&api)),
+            "http://127.0.0.1:8080/api/"
+        );
+    }
+
     mod rand_test {
         use super::rand;
         #[test]
@@ -1,4 +1,4 @@
-use clap::{arg, command, value_parser, Command};
+use clap::{arg, command, value_parser, ArgAction, Command};
 
 pub static CONFIG_HELP_MESSAGE: &str = "\
 File: cf-tool will save data in config_dir.
@@ -7,14 +7,36 @@ File: cf-tool will save data in config_dir.
     In config_dir there are following files:
     \"config_dir/cf.json\"   Your configuration file.
     \"config_dir/templates\" The folder to store templates. 
+    \"config_dir/session.json\" The cookies of the website, kept after logging in until the session expires or `cf-cli logout`.
+    \"config_dir/languages.json\" The languages refreshed by `cf-cli languages --refresh`.
+    \"config_dir/credentials.json\" Your API key, secret and password, saved by `cf-tui config login` and readable only by you.
+
+Layers: settings are read from the following sources, each overriding the ones before it:
+    1. config_dir/cf.json.
+    2. .cf.json in the current directory or the closest parent directory containing one. A relative home_dir in it is relative to its directory.
+    3. Environment variables CF_* where __ separates tables, e.g. CF_USERNAME or CF_HTTP__PROXY.
+    4. --set KEY=VALUE on the command line, e.g. --set http.proxy=socks5://127.0.0.1:1080.
+    Tables are merged item by item, while arrays such as templates are replaced as a whole.
+    Run `cf-cli config show --origin` to see where each setting comes from.
+
+Editing: `cf-tui config set KEY VALUE`, `cf-tui config unset KEY` and `cf-tui config get KEY` change and read one setting, e.g. `cf-tui config set http.read_timeout 30`.
+    VALUE is read as JSON when it is valid JSON, or else as a string. key, secret and password are kept in credentials.json.
+    `cf-tui config templates add|rm|edit` and `cf-tui config commands add|rm|edit` manage templates and commands without the configuration guide.
+
+Reloading: while cf-tui is running, changes to cf.json, credentials.json and .cf.json are used within a second. An invalid configuration is reported in the log and the last settings are kept.
+
+Version: `version` is the version of the configuration. An older configuration is upgraded when it is loaded, keeping the old file next to it, e.g. as cf.json.v0.bak.
 
 Configuration file consists of the following parts:
 
 username: Your username.
 key: Your API key.
 secret: Your API secret.
+password: Your password, used to log in to the website for submitting.
+key_command, secret_command, password_command: Commands printing your API key, secret and password, e.g. `pass show cf/secret`.
 
 API key and secret can be created in the part API in you account Setting.
+Each of key, secret and password is read from the first of the environment variables CF_KEY, CF_SECRET and CF_PASSWORD, the commands above, credentials.json and cf.json.
 
 templates:
     `templates` is an array of templates.
@@ -30,6 +52,7 @@ templates:
         \"path\":  The path to your code for this template. cf-tool will read from this directory when the path specified in templates in configuration file is not an absolute path.
                    For example, when path = /home/cf/dcode.cpp , cf-tool will read from /home/cf/dcode.cpp .
                                 when path = dcode.cpp, cf-tool will read from config_dir/templates/dcode.cpp;
+        \"program_type_id\": Optional. The id of the language codes generated from this template are submitted in, as listed by `cf-cli languages`.
     }
 
     You can insert some placeholders into your template code. 
@@ -75,6 +98,11 @@ templates:
         return 0;
     }
     
+languages:
+    `languages` maps extensions to the ids of the languages codes are submitted in, as listed by `cf-cli languages`.
+    For example: { \"cpp\": 89, \"py\": 70 }
+    Other extensions are submitted in the language of the first template with that extension, or else in a default language.
+
 commands: 
     cf-tool will run 3 scripts in sequence when you run press `t` on a problem:
     - before_script   (execute once)
@@ -95,22 +123,89 @@ commands:
     before_script: g++ <% full %> -o <% file %>,
     script:./<% file %> (or ./<% file %>.exe on Windows system)
 
+http:
+    `http` configures how cf-tool connects to Codeforces. Every item is optional:
+    proxy:           An HTTP, HTTPS or SOCKS5 proxy, e.g. socks5://127.0.0.1:1080. Defaults to HTTP_PROXY and HTTPS_PROXY.
+    connect_timeout: Seconds to wait for a connection, 10 by default.
+    read_timeout:    Seconds to wait for a whole response, 30 by default.
+    user_agent:      The user agent to send.
+    base_url:        The website to use instead of https://codeforces.com/, e.g. https://codeforc.es/.
+    api_base_url:    The API to use instead of api/ of base_url.
+    --base-url and CF_BASE_URL take precedence over base_url and api_base_url.
+
+keybindings:
+    `keybindings` changes the keys of cf-tui, e.g. {\"preset\": \"emacs\", \"keys\": {\"submit\": [\"s\", \"ctrl-s\"]}}.
+    preset: vim (the default) or emacs.
+    keys:   Keys of actions replacing those of the preset, like k, P, ctrl-s, shift-Tab, Enter or F5.
+    Press ? in cf-tui to see the actions of the current view and their keys, and : to search and run them. Press / on a list to filter its rows and S to sort them. A key bound to two actions available at the same time is refused.
+
+theme:
+    `theme` changes the colors of cf-tui, e.g. {\"preset\": \"light\", \"colors\": {\"accepted\": \"#00aa00\"}}.
+    preset: dark (the default), light, high-contrast, monochrome or a user theme in `themes`.
+    colors: text, muted, accent, title, frame, header, header_text, highlight, highlight_text,
+            accepted, rejected, pending, warning and error, as names like red or as #rrggbb.
+    `themes` holds user themes by name, each with its own preset and colors. NO_COLOR turns every color off.
+
 home_dir:
     This is the directory that stores all codes and samples generated by cf-tool and the directory to read from when testing or submitting.
 
     The directory looks like the following:
     .
-└── Contests
-    └── contest_id
-        └── problem_index
-            ├── problem_index.cpp
-            ├── ans1.txt
-            ├── ans2.txt
-            ├── in1.txt
-            └── in2.txt";
+├── Contests
+│   └── contest_id
+│       └── problem_index
+│           ├── problem_index.cpp
+│           ├── ans1.txt
+│           ├── ans2.txt
+│           ├── in1.txt
+│           └── in2.txt
+├── Gym
+│   └── contest_id
+│       └── problem_index
+├── Groups
+│   └── group_code
+│       └── contest_id
+│           └── problem_index
+└── acmsguru
+    └── problem_index
+
+    Problems from the problemset are kept with the contest they come from.";
+
+/// Adds the fields of a template, which `add` requires but `edit` does not.
+fn template_args(command: Command, required: bool) -> Command {
+    command
+        .arg(arg!(--alias <ALIAS> "The alias of the template.").required(true))
+        .arg(arg!(--lang <LANG> "The language of the template.").required(required))
+        .arg(arg!(--path <PATH> "The path to the template code, relative to config_dir/templates.").required(required))
+        .arg(
+            arg!(--"program-type-id" <ID> "The id of the language codes are submitted in, as listed by `cf-cli languages`.")
+                .value_parser(value_parser!(u32)),
+        )
+}
+
+/// Adds the scripts of commands, of which `add` requires `script` but `edit` does not.
+fn command_args(command: Command, required: bool) -> Command {
+    command
+        .arg(arg!(--ext <EXT> "The extension of the commands, e.g. cpp.").required(true))
+        .arg(arg!(--"before-script" <SCRIPT> "The script run once before testing."))
+        .arg(arg!(--script <SCRIPT> "The script run for each sample.").required(required))
+        .arg(arg!(--"after-script" <SCRIPT> "The script run once after testing."))
+        .arg(arg!(--"open-script" <SCRIPT> "The script opening codes."))
+}
 
 pub fn args() -> Command {
     command!()
+        .arg(
+            arg!(--"base-url" <URL> "The website to use instead of https://codeforces.com/, e.g. a local cf-mock server.\
+              Can also be set by the environment variable CF_BASE_URL.")
+            .global(true),
+        )
+        .arg(
+            arg!(--set <SETTING> "Override a setting as KEY=VALUE, e.g. http.proxy=socks5://127.0.0.1:1080.\
+              Tables and arrays are given as JSON.")
+            .action(ArgAction::Append)
+            .global(true),
+        )
         .subcommand(
             Command::new("config")
                 .about("Configure cf-tool")
@@ -118,25 +213,119 @@ pub fn args() -> Command {
                 .subcommand_required(true)
                 .subcommand(
                     Command::new("login").about(
-                        "Configure login related settings, including username, key and secret.",
+                        "Configure login related settings, including username, key, secret and password.",
                     ),
                 )
-                .subcommand(Command::new("templates").about("Configure templates."))
-                .subcommand(Command::new("commands").about("Configure commands."))
+                .subcommand(
+                    Command::new("get")
+                        .about("Print a setting, e.g. `username` or `http.proxy`")
+                        .arg(arg!(<KEY> "The dotted key of the setting.")),
+                )
+                .subcommand(
+                    Command::new("set")
+                        .about("Change a setting in cf.json, or in credentials.json for key, secret and password")
+                        .arg(arg!(<KEY> "The dotted key of the setting."))
+                        .arg(arg!(<VALUE> "The value, as JSON if it is not a plain string.")),
+                )
+                .subcommand(
+                    Command::new("unset")
+                        .about("Remove a setting")
+                        .arg(arg!(<KEY> "The dotted key of the setting.")),
+                )
+                .subcommand(
+                    Command::new("templates")
+                        .about("Configure templates. Asks what to do without a subcommand.")
+                        .subcommand(template_args(Command::new("add").about("Add a template"), true))
+                        .subcommand(
+                            Command::new("rm")
+                                .about("Remove a template")
+                                .arg(arg!(--alias <ALIAS> "The alias of the template.").required(true)),
+                        )
+                        .subcommand(
+                            template_args(Command::new("edit").about("Change a template"), false)
+                                .arg(arg!(--"new-alias" <ALIAS> "The alias to rename the template to.")),
+                        ),
+                )
+                .subcommand(
+                    Command::new("commands")
+                        .about("Configure commands. Asks what to do without a subcommand.")
+                        .subcommand(command_args(Command::new("add").about("Add the commands of an extension"), true))
+                        .subcommand(
+                            Command::new("rm")
+                                .about("Remove the commands of an extension")
+                                .arg(arg!(--ext <EXT> "The extension of the commands.").required(true)),
+                        )
+                        .subcommand(command_args(
+                            Command::new("edit").about("Change the commands of an extension"),
+                            false,
+                        )),
+                )
                 .subcommand(
                     Command::new("dir")
-                        .about("Configure directories related settings, including home_dir."),
+                        .about("Configure directories related settings, including home_dir.")
+                        .arg(arg!([HOME_DIR] "The directory to keep codes and samples in. Asked for if not given.")),
                 ),
         )
+        .subcommand(
+            Command::new("doctor")
+                .about("Check the configuration and the API credentials, suggesting fixes"),
+        )
         .subcommand(
             Command::new("race")
                 .arg(
-                    arg!(<CONTEST_ID> "The id of the contest.\
-                      It is not the round number.\
+                    arg!(<CONTEST> "The id or the URL of the contest.\
+                      The id is not the round number.\
                       It can be seen in contest URL.\
-                      For example: /contest/566/status")
-                    .value_parser(value_parser!(i32)),
+                      For example: 566, gym/102000 or group/xyz/contest/123"),
                 )
                 .about("Take part in a given contest"),
         )
 }
+
+pub fn cli_args() -> Command {
+    command!()
+        .arg(
+            arg!(--"base-url" <URL> "The website to use instead of https://codeforces.com/, e.g. a local cf-mock server.\
+              Can also be set by the environment variable CF_BASE_URL.")
+            .global(true),
+        )
+        .arg(
+            arg!(--set <SETTING> "Override a setting as KEY=VALUE, e.g. http.proxy=socks5://127.0.0.1:1080.\
+              Tables and arrays are given as JSON.")
+            .action(ArgAction::Append)
+            .global(true),
+        )
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("login")
+                .about("Log in to the website, keeping the session in config_dir/session.json")
+                .arg(arg!([HANDLE] "The handle to log in as. Defaults to the username configured.")),
+        )
+        .subcommand(Command::new("logout").about("Log out of the website"))
+        .subcommand(
+            Command::new("config")
+                .about("Show the configuration")
+                .after_help(CONFIG_HELP_MESSAGE)
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("show")
+                        .about("Show the configuration merged from every source")
+                        .arg(arg!(--origin "Show where each setting comes from.")),
+                )
+                .subcommand(
+                    Command::new("import")
+                        .about("Import the configuration and the session of cf-tool by xalanq")
+                        .arg(arg!([DIR] "The directory of cf-tool. Defaults to ~/.cf.")),
+                ),
+        )
+        .subcommand(
+            Command::new("doctor")
+                .about("Check the configuration and the API credentials, suggesting fixes"),
+        )
+        .subcommand(Command::new("whoami").about("Show the handle logged in to the website"))
+        .subcommand(
+            Command::new("languages")
+                .about("List the languages codes can be submitted in")
+                .arg(arg!(--refresh "Refresh the languages from the submit page of Codeforces.")),
+        )
+}
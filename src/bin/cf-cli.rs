@@ -1 +1,167 @@
-fn main() {}
+use std::{collections::BTreeMap, path::PathBuf};
+
+use cf::{
+    api::{
+        languages::{load_languages, preferred_language, refresh_languages},
+        methods::build_client,
+        session::{ensure_login, login, logout, whoami},
+        utils::set_base_url,
+    },
+    args::cli_args,
+    credentials::{load_credentials, print_permission_warnings, read_password, CREDENTIALS},
+    doctor::doctor,
+    import::{default_xalanq_dir, import_xalanq},
+    log::setup_logger,
+    settings::{init_settings, load_layered_settings, load_settings, set_overrides, SETTINGS},
+};
+use clap::ArgMatches;
+use color_eyre::{
+    eyre::{eyre, Context},
+    Result,
+};
+
+async fn handle_login(matches: &ArgMatches) -> Result<()> {
+    let handle = match matches.get_one::<String>("HANDLE") {
+        Some(handle) => handle.clone(),
+        None => SETTINGS.get().username.clone().ok_or(eyre!(
+            "No username configured.\nPlease give a handle or configure your username."
+        ))?,
+    };
+    let password = match CREDENTIALS.get().password.clone() {
+        Some(password) => password,
+        None => {
+            println!("Please input the password of {handle}:");
+            read_password()?
+        }
+    };
+    let handle = login(&handle, &password).await?;
+    println!("Logged in as {handle}.");
+    Ok(())
+}
+
+async fn handle_languages(matches: &ArgMatches) -> Result<()> {
+    let languages = if matches.get_flag("refresh") {
+        let username = SETTINGS.get().username.clone().ok_or(eyre!(
+            "No username configured.\nPlease configure your username."
+        ))?;
+        ensure_login(&username, CREDENTIALS.get().password.as_deref()).await?;
+        let languages = refresh_languages().await?;
+        println!("Refreshed {} languages.", languages.len());
+        languages
+    } else {
+        load_languages()
+    };
+
+    // Extensions each language is preferred for, to mark them in the list.
+    let mut preferred: BTreeMap<u32, Vec<String>> = BTreeMap::new();
+    let mut extensions: Vec<String> = languages
+        .iter()
+        .flat_map(|language| language.extensions.clone())
+        .chain(
+            SETTINGS
+                .get()
+                .languages
+                .iter()
+                .flat_map(|languages| languages.keys().cloned()),
+        )
+        .collect();
+    extensions.sort();
+    extensions.dedup();
+    for ext in extensions {
+        if let Ok(language) = preferred_language(format!("code.{ext}").as_ref(), None) {
+            preferred
+                .entry(language.id)
+                .or_default()
+                .push(format!(".{ext}"));
+        }
+    }
+
+    println!("{:>4}  {:<40}  Extensions", "ID", "Language");
+    for language in &languages {
+        let mut line = format!(
+            "{:>4}  {:<40}  {}",
+            language.id,
+            language.to_string(),
+            language.extensions.join(", ")
+        );
+        if let Some(exts) = preferred.get(&language.id) {
+            line.push_str(&format!("  (preferred for {})", exts.join(", ")));
+        }
+        println!("{}", line.trim_end());
+    }
+    Ok(())
+}
+
+fn handle_config(matches: &ArgMatches) -> Result<()> {
+    match matches.subcommand() {
+        Some(("show", sub_matches)) => {
+            let layered = load_layered_settings()?;
+            let mut value = layered.value;
+            if let Some(settings) = value.as_object_mut() {
+                settings.retain(|_, value| !value.is_null());
+                // Credentials are not to be shown on screen.
+                for key in ["key", "secret", "password"] {
+                    if let Some(value) = settings.get_mut(key) {
+                        *value = serde_json::Value::String(String::from("********"));
+                    }
+                }
+            }
+            if sub_matches.get_flag("origin") {
+                for (key, origin) in &layered.origins {
+                    if let Some(setting) = value.pointer(&format!("/{}", key.replace('.', "/"))) {
+                        println!("{key} = {setting}  ({origin})");
+                    }
+                }
+            } else {
+                println!("{}", serde_json::to_string_pretty(&value)?);
+            }
+        }
+        Some(("import", sub_matches)) => {
+            let dir = match sub_matches.get_one::<String>("DIR") {
+                Some(dir) => PathBuf::from(dir),
+                None => default_xalanq_dir().ok_or(eyre!("Home directory not defined"))?,
+            };
+            for note in import_xalanq(&dir)? {
+                println!("{note}");
+            }
+        }
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+async fn handle_doctor() -> Result<()> {
+    let settings = load_settings()?;
+    if !doctor(&settings, &load_credentials(&settings)).await {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    setup_logger()?;
+    let args = cli_args().get_matches();
+    if let Some(base_url) = args.get_one::<String>("base-url") {
+        set_base_url(base_url);
+    }
+    set_overrides(args.get_many::<String>("set").unwrap_or_default())?;
+    print_permission_warnings();
+    if !matches!(args.subcommand_name(), Some("config" | "doctor")) {
+        init_settings()?;
+        build_client(SETTINGS.get().http.as_ref()).wrap_err("Invalid http settings")?;
+    }
+    match args.subcommand() {
+        Some(("login", sub_matches)) => handle_login(sub_matches).await?,
+        Some(("logout", _)) => match logout().await? {
+            Some(handle) => println!("Logged out from {handle}."),
+            None => println!("Not logged in."),
+        },
+        Some(("config", sub_matches)) => handle_config(sub_matches)?,
+        Some(("doctor", _)) => handle_doctor().await?,
+        Some(("whoami", _)) => println!("{}", whoami().await?),
+        Some(("languages", sub_matches)) => handle_languages(sub_matches).await?,
+        _ => unreachable!(),
+    }
+    Ok(())
+}
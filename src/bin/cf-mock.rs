@@ -0,0 +1,42 @@
+use std::{net::SocketAddr, path::PathBuf};
+
+use cf::{
+    log::setup_logger,
+    mock::{serve, Fixtures, RequestCounter},
+};
+use clap::{arg, command, value_parser};
+use color_eyre::Result;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    setup_logger()?;
+    let args = command!()
+        .about("Serve recorded Codeforces fixtures on localhost")
+        .arg(
+            arg!(-p --port <PORT> "The port to listen on.")
+                .value_parser(value_parser!(u16))
+                .default_value("8080"),
+        )
+        .arg(
+            arg!(-f --fixtures <DIR> "A directory of fixtures overriding the built-in ones.")
+                .value_parser(value_parser!(PathBuf)),
+        )
+        .get_matches();
+    let port = *args.get_one::<u16>("port").unwrap_or(&8080);
+    let fixtures = match args.get_one::<PathBuf>("fixtures") {
+        Some(dir) => Fixtures::with_dir(dir),
+        None => Fixtures::new(),
+    };
+
+    let listener = std::net::TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], port)))?;
+    println!(
+        "Serving Codeforces fixtures at http://{}/\n\
+         Run `CF_BASE_URL=http://{}/ cf-tui` to use it.",
+        listener.local_addr()?,
+        listener.local_addr()?
+    );
+    serve(listener, fixtures, RequestCounter::default(), async {
+        tokio::signal::ctrl_c().await.ok();
+    })
+    .await
+}
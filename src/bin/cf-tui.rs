@@ -1,11 +1,24 @@
-use std::{collections::HashMap, fs::write, io, path::PathBuf};
+use std::{collections::HashMap, env, fs::DirBuilder, io, path::PathBuf, time::Duration};
 
 use cf::{
-    api::methods::contest_list,
+    api::{
+        locator::ProblemSource,
+        methods::{build_client, contest_standings},
+        utils::set_base_url,
+    },
     args::args,
-    display::tui::{app::App, msg::ViewConstructor},
+    credentials::{
+        load_credentials, print_permission_warnings, read_credentials_file, read_password,
+        write_credentials_file, CFCredentials,
+    },
+    display::tui::{app::App, keymap::Keymap, msg::ViewConstructor, theme::Theme},
+    doctor::doctor,
     log::setup_logger,
-    settings::{get_config_file_path, load_settings, CFCommand, CFScripts, CFSettings, CFTemplate},
+    settings::{
+        get_setting, init_settings, is_known_setting, load_layered_settings, load_settings,
+        read_global_config, set_overrides, set_setting, settings_from_value, unset_setting,
+        watch_settings, write_global_config, CFScripts, CFSettings, CFTemplate, SETTINGS,
+    },
 };
 use clap::ArgMatches;
 use color_eyre::{
@@ -13,21 +26,34 @@ use color_eyre::{
     Result,
 };
 
+/// Reads a line from stdin without surrounding whitespace.
+fn read_input() -> Result<String> {
+    let mut input = String::default();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim().to_string())
+}
+
 fn input_template() -> Result<CFTemplate> {
     println!("Please input alias of the template:");
-    let mut alias = String::default();
-    io::stdin().read_line(&mut alias)?;
+    let alias = read_input()?;
     println!("Please input language of the template:");
-    let mut lang = String::default();
-    io::stdin().read_line(&mut lang)?;
+    let lang = read_input()?;
     println!("Please input path to the template code:");
-    let mut path = String::default();
-    io::stdin().read_line(&mut path)?;
-    let path = PathBuf::from(path);
+    let path = PathBuf::from(read_input()?);
+    println!("Please input programTypeId to submit codes of the template in (see `cf-cli languages`, leave empty to decide by extension):");
+    let program_type_id = read_input()?;
+    let program_type_id = match program_type_id.trim() {
+        "" => None,
+        id => Some(
+            id.parse()
+                .wrap_err(format!("Invalid programTypeId: {id}"))?,
+        ),
+    };
     let template = CFTemplate {
         alias,
         lang,
         path: path.clone(),
+        program_type_id,
     };
     if !path.try_exists()? {
         bail!(
@@ -38,48 +64,61 @@ fn input_template() -> Result<CFTemplate> {
     Ok(template)
 }
 
-fn input_command() -> Result<CFCommand> {
+/// Reads the extension and the scripts of a command.
+fn input_command() -> Result<(String, CFScripts)> {
     println!("Please input extension of the template:");
-    let mut ext = String::default();
-    io::stdin().read_line(&mut ext)?;
+    let ext = read_input()?;
     println!("Please input before_script of the template:");
-    let mut before_script = String::default();
-    io::stdin().read_line(&mut before_script)?;
+    let before_script = read_input()?;
     println!("Please input script of the template:");
-    let mut script = String::default();
-    io::stdin().read_line(&mut script)?;
+    let script = read_input()?;
     println!("Please input after_script of the template:");
-    let mut after_script = String::default();
-    io::stdin().read_line(&mut after_script)?;
+    let after_script = read_input()?;
     println!("Please input open_script of the template:(if this is empty, the default \"vim <% full %>\")");
-    let mut open_script = String::default();
-    io::stdin().read_line(&mut open_script)?;
-    if open_script == "" {
+    let mut open_script = read_input()?;
+    if open_script.is_empty() {
         open_script = String::from("vim <% full %>");
     }
-    let command = CFCommand {
-        ext,
-        before_command: before_script,
-        command: script,
-        after_command: after_script,
-        open_command: open_script,
+    let scripts = CFScripts {
+        before_script: Some(before_script),
+        script,
+        after_script: Some(after_script),
+        open_script: Some(open_script),
     };
-    Ok(command)
+    Ok((ext, scripts))
 }
 
 fn config_login(settings: &mut CFSettings) -> Result<()> {
-    println!("Please input your username:");
-    let mut username = String::default();
-    io::stdin().read_line(&mut username)?;
-    println!("Please input your API key:");
-    let mut key = String::default();
-    io::stdin().read_line(&mut key)?;
-    println!("Please input your API secret:");
-    let mut secret = String::default();
-    io::stdin().read_line(&mut secret)?;
-    settings.username = Some(username);
-    settings.key = Some(key);
-    settings.secret = Some(secret);
+    println!("Please input your username (leave empty to keep it):");
+    let username = read_input()?;
+    println!("Please input your API key (leave empty to keep it):");
+    let key = read_input()?;
+    println!("Please input your API secret (leave empty to keep it):");
+    let secret = read_input()?;
+    println!("Please input your password (used for submitting, leave empty to keep it):");
+    let password = read_password()?;
+    if !username.is_empty() {
+        settings.username = Some(username);
+    }
+    // Credentials are kept out of cf.json, which is often shared with dotfiles.
+    let mut credentials = read_credentials_file()?;
+    credentials.key = match key.is_empty() {
+        true => credentials.key.or(settings.key.take()),
+        false => Some(key),
+    };
+    credentials.secret = match secret.is_empty() {
+        true => credentials.secret.or(settings.secret.take()),
+        false => Some(secret),
+    };
+    credentials.password = match password.is_empty() {
+        true => credentials.password.or(settings.password.take()),
+        false => Some(password),
+    };
+    settings.key = None;
+    settings.secret = None;
+    settings.password = None;
+    let path = write_credentials_file(&credentials)?;
+    println!("Saved your credentials to {}.", path.display());
     Ok(())
 }
 
@@ -90,8 +129,7 @@ fn config_templates(settings: &mut CFSettings) -> Result<()> {
         2. Delete a template.\n\
         3. Modify a template."
     );
-    let mut operation = String::default();
-    io::stdin().read_line(&mut operation)?;
+    let operation = read_input()?;
     let operation: usize = operation
         .trim()
         .parse()
@@ -113,8 +151,7 @@ fn config_templates(settings: &mut CFSettings) -> Result<()> {
                     serde_json::to_string_pretty(&templates)?
                 );
                 println!("Which template do you want to delete?");
-                let mut index = String::default();
-                io::stdin().read_line(&mut index)?;
+                let index = read_input()?;
                 let index: usize = index.trim().parse().wrap_err(format!(
                     "Please input an integer that is within 1 and {len}."
                 ))?;
@@ -137,8 +174,7 @@ fn config_templates(settings: &mut CFSettings) -> Result<()> {
                     serde_json::to_string_pretty(&templates)?
                 );
                 println!("Which template do you want to modify?");
-                let mut index = String::default();
-                io::stdin().read_line(&mut index)?;
+                let index = read_input()?;
                 let index: usize = index.trim().parse().wrap_err(format!(
                     "Please input an integer that is within 1 and {len}."
                 ))?;
@@ -170,107 +206,257 @@ fn config_commands(settings: &mut CFSettings) -> Result<()> {
         2. Delete a command.\n\
         3. Modify a command."
     );
-    let mut operation = String::default();
-    io::stdin().read_line(&mut operation)?;
+    let operation = read_input()?;
     let operation: usize = operation
         .trim()
         .parse()
         .wrap_err("Please input an integer that is within 1 and 3.")?;
     match operation {
         1 => {
-            let command = input_command()?;
+            let (ext, scripts) = input_command()?;
             match &mut settings.commands {
                 Some(commands) => {
-                    commands.insert(command.ext.clone(), CFScripts::from(command));
+                    commands.insert(ext, scripts);
                 }
                 None => {
                     let mut commands = HashMap::new();
-                    commands.insert(command.ext.clone(), CFScripts::from(command));
+                    commands.insert(ext, scripts);
                     settings.commands = Some(commands);
                 }
             };
         }
-        2 => match &mut settings.templates {
-            Some(templates) => {
-                let len = templates.len();
+        2 | 3 => match &mut settings.commands {
+            Some(commands) if !commands.is_empty() => {
                 println!(
-                    "Currently {} templates are configured:\n {}",
-                    len,
-                    serde_json::to_string_pretty(&templates)?
+                    "Currently commands of {} extensions are configured:\n {}",
+                    commands.len(),
+                    serde_json::to_string_pretty(&commands)?
                 );
-                println!("Which template do you want to delete?");
-                let mut index = String::default();
-                io::stdin().read_line(&mut index)?;
-                let index: usize = index.trim().parse().wrap_err(format!(
-                    "Please input an integer that is within 1 and {len}."
-                ))?;
-                if index < 1 || index > len {
-                    bail!("Please input an integer that is within 1 and {len}.");
+                match operation {
+                    2 => println!("Commands of which extension do you want to delete?"),
+                    _ => println!("Commands of which extension do you want to modify?"),
+                }
+                let ext = read_input()?;
+                if commands.remove(&ext).is_none() {
+                    bail!("No commands of {ext} are configured.");
+                }
+                if operation == 3 {
+                    let (ext, scripts) = input_command()?;
+                    commands.insert(ext, scripts);
                 }
-                templates.swap_remove(index - 1);
             }
-            None => {
-                println!("No template is configured!");
+            _ => {
+                println!("No command is configured!");
                 return Ok(());
             }
         },
-        3 => match &mut settings.templates {
-            Some(templates) => {
-                let len = templates.len();
-                println!(
-                    "Currently {} templates are configured:\n {}",
-                    len,
-                    serde_json::to_string_pretty(&templates)?
-                );
-                println!("Which template do you want to modify?");
-                let mut index = String::default();
-                io::stdin().read_line(&mut index)?;
-                let index: usize = index.trim().parse().wrap_err(format!(
-                    "Please input an integer that is within 1 and {len}."
-                ))?;
-                if index < 1 || index > len {
-                    bail!("Please input an integer that is within 1 and {len}.");
-                }
 
-                let template = input_template()?;
+        _ => bail!("Please input an integer that is within 1 and 3."),
+    }
+    Ok(())
+}
 
-                *templates
-                    .get_mut(index - 1)
-                    .wrap_err(format!("Template #{index} doesn't exist!"))? = template;
+fn config_dir(settings: &mut CFSettings, matches: &ArgMatches) -> Result<()> {
+    let home_dir = match matches.get_one::<String>("HOME_DIR") {
+        Some(home_dir) => home_dir.trim().to_string(),
+        None => {
+            println!("Please input home_dir, the directory to keep codes and samples in:");
+            read_input()?
+        }
+    };
+    if home_dir.is_empty() {
+        bail!("home_dir cannot be empty.");
+    }
+    let home_dir = env::current_dir()?.join(home_dir);
+    DirBuilder::new()
+        .recursive(true)
+        .create(&home_dir)
+        .wrap_err(format!("Failed to create {}", home_dir.display()))?;
+    settings.home_dir = Some(home_dir);
+    Ok(())
+}
+
+fn edit_templates(settings: &mut CFSettings, operation: &str, matches: &ArgMatches) -> Result<()> {
+    let alias = matches
+        .get_one::<String>("alias")
+        .ok_or(eyre!("No alias given"))?;
+    let templates = settings.templates.get_or_insert_with(Vec::new);
+    let index = templates
+        .iter()
+        .position(|template| &template.alias == alias);
+    match (operation, index) {
+        ("add", Some(_)) => bail!("Template {alias} already exists."),
+        ("add", None) => {
+            let template = CFTemplate {
+                alias: alias.clone(),
+                lang: matches
+                    .get_one::<String>("lang")
+                    .cloned()
+                    .unwrap_or_default(),
+                path: matches
+                    .get_one::<String>("path")
+                    .map(PathBuf::from)
+                    .unwrap_or_default(),
+                program_type_id: matches.get_one::<u32>("program-type-id").copied(),
+            };
+            let path = template.full_path()?;
+            if !path.try_exists()? {
+                bail!(
+                    "Template code doesn't exist or cf-tool has no permission to it: {}",
+                    path.display()
+                );
             }
-            None => {
-                println!("No template is configured!");
-                return Ok(());
+            templates.push(template);
+        }
+        ("rm", Some(index)) => {
+            templates.remove(index);
+        }
+        ("edit", Some(index)) => {
+            let template = &mut templates[index];
+            if let Some(alias) = matches.get_one::<String>("new-alias") {
+                template.alias = alias.clone();
             }
-        },
+            if let Some(lang) = matches.get_one::<String>("lang") {
+                template.lang = lang.clone();
+            }
+            if let Some(path) = matches.get_one::<String>("path") {
+                template.path = PathBuf::from(path);
+            }
+            if let Some(id) = matches.get_one::<u32>("program-type-id") {
+                template.program_type_id = Some(*id);
+            }
+        }
+        (_, None) => bail!("No template {alias} is configured."),
+        _ => unreachable!(),
+    }
+    Ok(())
+}
 
-        _ => bail!("Please input an integer that is within 1 and 3."),
+fn edit_commands(settings: &mut CFSettings, operation: &str, matches: &ArgMatches) -> Result<()> {
+    let ext = matches
+        .get_one::<String>("ext")
+        .ok_or(eyre!("No extension given"))?
+        .trim_start_matches('.');
+    let script = |name: &str| matches.get_one::<String>(name).cloned();
+    let commands = settings.commands.get_or_insert_with(HashMap::new);
+    match (operation, commands.get_mut(ext)) {
+        ("add", Some(_)) => bail!("Commands of {ext} already exist."),
+        ("add", None) => {
+            let scripts = CFScripts {
+                before_script: script("before-script"),
+                script: script("script").unwrap_or_default(),
+                after_script: script("after-script"),
+                open_script: script("open-script"),
+            };
+            commands.insert(ext.to_string(), scripts);
+        }
+        ("rm", Some(_)) => {
+            commands.remove(ext);
+        }
+        ("edit", Some(scripts)) => {
+            if let Some(script) = script("script") {
+                scripts.script = script;
+            }
+            for (name, value) in [
+                ("before-script", &mut scripts.before_script),
+                ("after-script", &mut scripts.after_script),
+                ("open-script", &mut scripts.open_script),
+            ] {
+                if let Some(script) = script(name) {
+                    *value = Some(script);
+                }
+            }
+        }
+        (_, None) => bail!("No commands of {ext} are configured."),
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+/// Prints the effective setting of `key`, from every source of settings.
+fn config_get(matches: &ArgMatches) -> Result<()> {
+    let key = matches
+        .get_one::<String>("KEY")
+        .ok_or(eyre!("No key given"))?;
+    if !is_known_setting(key) {
+        bail!("Unknown setting `{key}`");
+    }
+    let value = match CFCredentials::is_credential(key) {
+        true => load_credentials(&load_settings()?)
+            .get(key)
+            .cloned()
+            .map(serde_json::Value::String),
+        false => get_setting(&load_layered_settings()?.value, key).cloned(),
+    };
+    match value {
+        Some(serde_json::Value::String(value)) => println!("{value}"),
+        Some(value) => println!("{}", serde_json::to_string_pretty(&value)?),
+        None => bail!("`{key}` is not set"),
     }
     Ok(())
 }
 
 fn handle_config(matches: &ArgMatches) -> Result<()> {
-    let mut settings = load_settings()?;
+    let mut config = read_global_config()?;
     match matches.subcommand() {
-        Some(("login", _)) => {
-            config_login(&mut settings)?;
+        Some(("get", sub_matches)) => return config_get(sub_matches),
+        Some(("set", sub_matches)) => {
+            let key = sub_matches
+                .get_one::<String>("KEY")
+                .ok_or(eyre!("No key given"))?;
+            let text = sub_matches
+                .get_one::<String>("VALUE")
+                .ok_or(eyre!("No value given"))?;
+            if CFCredentials::is_credential(key) {
+                let mut credentials = read_credentials_file()?;
+                credentials.set(key, Some(text.trim().to_string()));
+                write_credentials_file(&credentials)?;
+                return Ok(());
+            }
+            let value = serde_json::from_str(text)
+                .unwrap_or_else(|_| serde_json::Value::String(text.clone()));
+            set_setting(&mut config, key, value)?;
         }
-        Some(("templates", _)) => {
-            config_templates(&mut settings)?;
+        Some(("unset", sub_matches)) => {
+            let key = sub_matches
+                .get_one::<String>("KEY")
+                .ok_or(eyre!("No key given"))?;
+            if CFCredentials::is_credential(key) {
+                let mut credentials = read_credentials_file()?;
+                if credentials.get(key).is_some() {
+                    credentials.set(key, None);
+                    write_credentials_file(&credentials)?;
+                }
+            }
+            unset_setting(&mut config, key);
         }
-        Some(("commands", _)) => {
-            config_commands(&mut settings)?;
+        Some((name, sub_matches)) => {
+            let mut settings = settings_from_value(&config)?;
+            match (name, sub_matches.subcommand()) {
+                ("login", _) => config_login(&mut settings)?,
+                ("templates", None) => config_templates(&mut settings)?,
+                ("templates", Some((operation, matches))) => {
+                    edit_templates(&mut settings, operation, matches)?
+                }
+                ("commands", None) => config_commands(&mut settings)?,
+                ("commands", Some((operation, matches))) => {
+                    edit_commands(&mut settings, operation, matches)?
+                }
+                ("dir", _) => config_dir(&mut settings, sub_matches)?,
+                _ => unreachable!(),
+            }
+            config = serde_json::to_value(settings)?;
         }
-        _ => unreachable!(),
+        None => unreachable!(),
     };
-    let config_file_path = get_config_file_path()?;
-    if !config_file_path.try_exists()? {
-        bail!(
-            "Config directory doesn't exist or cf-tool has no permission to it: {}",
-            config_file_path.display()
-        );
+    write_global_config(&config)
+}
+
+async fn handle_doctor() -> Result<()> {
+    let settings = load_settings()?;
+    if !doctor(&settings, &load_credentials(&settings)).await {
+        std::process::exit(1);
     }
-    write(config_file_path, serde_json::to_string_pretty(&settings)?)?;
     Ok(())
 }
 
@@ -278,25 +464,39 @@ fn handle_config(matches: &ArgMatches) -> Result<()> {
 async fn main() -> Result<()> {
     setup_logger()?;
     let args = args().get_matches();
+    if let Some(base_url) = args.get_one::<String>("base-url") {
+        set_base_url(base_url);
+    }
+    set_overrides(args.get_many::<String>("set").unwrap_or_default())?;
+    print_permission_warnings();
+    if !matches!(args.subcommand_name(), Some("config" | "doctor")) {
+        init_settings()?;
+        build_client(SETTINGS.get().http.as_ref()).wrap_err("Invalid http settings")?;
+        Keymap::new(SETTINGS.get().keybindings.as_ref()).wrap_err("Invalid keybindings")?;
+        Theme::new(&SETTINGS.get()).wrap_err("Invalid theme")?;
+        watch_settings(Duration::from_secs(1))?;
+    }
     match args.subcommand() {
         Some(("config", sub_matches)) => {
             handle_config(sub_matches)?;
         }
+        Some(("doctor", _)) => handle_doctor().await?,
         Some(("race", sub_matches)) => {
-            let contest_id = sub_matches
-                .get_one::<i32>("CONTEST_ID")
-                .ok_or(eyre!("Cannot find contest_id"))?;
-            let contest_list = contest_list(None).await?;
-            for contest in contest_list {
-                if contest.id == *contest_id {
-                    let mut app = App::new()?;
-                    app.enter_new_view(ViewConstructor::ContestBrowser(contest));
-                    if let Err(err) = app.run() {
-                        drop(app);
-                        eprintln!("{err:#}");
-                    }
-                    break;
-                }
+            let contest = sub_matches
+                .get_one::<String>("CONTEST")
+                .ok_or(eyre!("Cannot find contest"))?;
+            let source = ProblemSource::parse_contest(contest)?;
+            let contest_id = source
+                .contest_id()
+                .ok_or(eyre!("Cannot find the id of contest {contest}"))?;
+            let contest = contest_standings(contest_id, None, Some(1), None, None, None)
+                .await?
+                .contest;
+            let mut app = App::new()?;
+            app.enter_new_view(ViewConstructor::ContestBrowser(contest, source));
+            if let Err(err) = app.run() {
+                drop(app);
+                eprintln!("{err:#}");
             }
         }
         _ => {
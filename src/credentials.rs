@@ -0,0 +1,306 @@
+//! API key, secret and password, kept out of `cf.json` where possible.
+//!
+//! Each credential is taken from the first of:
+//! 1. the environment variables `CF_KEY`, `CF_SECRET` and `CF_PASSWORD`,
+//! 2. the output of `key_command`, `secret_command` and `password_command` in `cf.json`,
+//! 3. `credentials.json` next to `cf.json`, which only the current user can read,
+//! 4. `key`, `secret` and `password` in `cf.json`.
+
+use std::{
+    env, fs,
+    io::{self, IsTerminal},
+    path::PathBuf,
+    process::Command,
+};
+
+use color_eyre::{
+    eyre::{bail, Context},
+    Result,
+};
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    terminal,
+};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+use crate::settings::{
+    get_config_file_path, load_global_settings, write_private_file, CFSettings, Shared, SETTINGS,
+};
+
+lazy_static! {
+    pub static ref CREDENTIALS: Shared<CFCredentials> =
+        Shared::new(load_credentials(&SETTINGS.get()));
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CFCredentials {
+    pub key: Option<String>,
+    pub secret: Option<String>,
+    pub password: Option<String>,
+}
+
+impl CFCredentials {
+    /// Returns whether `key` names a credential rather than an ordinary setting.
+    pub fn is_credential(key: &str) -> bool {
+        matches!(key, "key" | "secret" | "password")
+    }
+
+    pub fn get(&self, key: &str) -> Option<&String> {
+        match key {
+            "key" => self.key.as_ref(),
+            "secret" => self.secret.as_ref(),
+            "password" => self.password.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn set(&mut self, key: &str, value: Option<String>) {
+        match key {
+            "key" => self.key = value,
+            "secret" => self.secret = value,
+            "password" => self.password = value,
+            _ => {}
+        }
+    }
+}
+
+pub fn get_credentials_file_path() -> Result<PathBuf> {
+    Ok(get_config_file_path()?.with_file_name("credentials.json"))
+}
+
+/// Reads `credentials.json`, returning no credentials if it does not exist.
+pub fn read_credentials_file() -> Result<CFCredentials> {
+    let path = get_credentials_file_path()?;
+    if !path.exists() {
+        return Ok(CFCredentials::default());
+    }
+    let content = fs::read_to_string(&path).wrap_err(format!(
+        "Error occured when reading from {}",
+        path.display()
+    ))?;
+    serde_json::from_str(&content).wrap_err(format!("Failed to parse {}", path.display()))
+}
+
+/// Saves `credentials` to `credentials.json`, readable only by the current user.
+pub fn write_credentials_file(credentials: &CFCredentials) -> Result<PathBuf> {
+    let path = get_credentials_file_path()?;
+    write_private_file(&path, &serde_json::to_string_pretty(credentials)?)?;
+    Ok(path)
+}
+
+/// Runs `command` with the shell, returning its output without the trailing newline.
+pub fn run_secret_command(command: &str) -> Result<String> {
+    let output = if cfg!(windows) {
+        Command::new("cmd").args(["/C", command]).output()
+    } else {
+        Command::new("sh").args(["-c", command]).output()
+    }
+    .wrap_err(format!("Failed to run `{command}`"))?;
+    if !output.status.success() {
+        bail!(
+            "`{command}` failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    // Only the first line counts, like `pass show` which may print more after the secret.
+    let stdout = String::from_utf8(output.stdout)
+        .wrap_err(format!("`{command}` printed non-unicode characters"))?;
+    Ok(stdout.lines().next().unwrap_or_default().trim().to_string())
+}
+
+/// Reads a password typed in the terminal without showing it, or a line of stdin if it is not
+/// a terminal.
+pub fn read_password() -> Result<String> {
+    if !io::stdin().is_terminal() {
+        let mut password = String::default();
+        io::stdin().read_line(&mut password)?;
+        return Ok(password.trim_end_matches(['\r', '\n']).to_string());
+    }
+    terminal::enable_raw_mode()?;
+    let password = read_hidden_line();
+    terminal::disable_raw_mode()?;
+    println!();
+    password
+}
+
+/// Reads keys up to Enter in raw mode, where they are not echoed.
+fn read_hidden_line() -> Result<String> {
+    let mut line = String::default();
+    loop {
+        if let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event::read()?
+        {
+            match code {
+                KeyCode::Enter => return Ok(line),
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    bail!("Interrupted")
+                }
+                KeyCode::Char(c) => line.push(c),
+                KeyCode::Backspace => {
+                    line.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn resolve(
+    var: &str,
+    command: Option<&String>,
+    file: Option<String>,
+    config: Option<&String>,
+) -> Option<String> {
+    if let Some(value) = non_empty(env::var(var).ok()) {
+        return Some(value);
+    }
+    if let Some(command) = command.filter(|command| !command.trim().is_empty()) {
+        match run_secret_command(command) {
+            Ok(value) if !value.is_empty() => return Some(value),
+            Ok(_) => tracing::warn!("`{command}` printed nothing"),
+            Err(err) => tracing::error!("{err:#}"),
+        }
+    }
+    non_empty(file).or_else(|| non_empty(config.cloned()))
+}
+
+/// Resolves every credential from the sources above.
+pub fn load_credentials(settings: &CFSettings) -> CFCredentials {
+    let file = read_credentials_file().unwrap_or_else(|err| {
+        tracing::error!("{err:#}");
+        CFCredentials::default()
+    });
+    CFCredentials {
+        key: resolve(
+            "CF_KEY",
+            settings.key_command.as_ref(),
+            file.key,
+            settings.key.as_ref(),
+        ),
+        secret: resolve(
+            "CF_SECRET",
+            settings.secret_command.as_ref(),
+            file.secret,
+            settings.secret.as_ref(),
+        ),
+        password: resolve(
+            "CF_PASSWORD",
+            settings.password_command.as_ref(),
+            file.password,
+            settings.password.as_ref(),
+        ),
+    }
+}
+
+/// Returns warnings about credentials that other users can read, given the settings of `cf.json`.
+pub fn permission_warnings(settings: &CFSettings) -> Vec<String> {
+    let mut warnings = vec![];
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = |path: &PathBuf| {
+            fs::metadata(path)
+                .map(|metadata| metadata.permissions().mode())
+                .ok()
+        };
+        let holds_credentials =
+            settings.key.is_some() || settings.secret.is_some() || settings.password.is_some();
+        if let Ok(path) = get_config_file_path() {
+            if mode(&path).is_some_and(|mode| mode & 0o004 != 0) {
+                warnings.push(if holds_credentials {
+                    format!(
+                        "{} is readable by every user but holds your credentials.\nRun `chmod 600 {}` or move them to {} with `cf-tui config login`.",
+                        path.display(),
+                        path.display(),
+                        get_credentials_file_path()
+                            .map(|path| path.display().to_string())
+                            .unwrap_or_default()
+                    )
+                } else {
+                    format!(
+                        "{} is readable by every user.\nRun `chmod 600 {}` before adding credentials to it.",
+                        path.display(),
+                        path.display()
+                    )
+                });
+            }
+        }
+        if let Ok(path) = get_credentials_file_path() {
+            if mode(&path).is_some_and(|mode| mode & 0o077 != 0) {
+                warnings.push(format!(
+                    "{} is readable by other users.\nRun `chmod 600 {}`.",
+                    path.display(),
+                    path.display()
+                ));
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = settings;
+    warnings
+}
+
+/// Prints [`permission_warnings`] of the configuration to stderr, if there is one.
+pub fn print_permission_warnings() {
+    if let Ok(settings) = load_global_settings() {
+        for warning in permission_warnings(&settings) {
+            eprintln!("Warning: {warning}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolution() {
+        let config = Some(String::from("from config"));
+        assert_eq!(
+            resolve("CF_TEST_UNSET", None, None, config.as_ref()),
+            Some(String::from("from config"))
+        );
+        assert_eq!(
+            resolve(
+                "CF_TEST_UNSET",
+                None,
+                Some(String::from("from file\n")),
+                config.as_ref()
+            ),
+            Some(String::from("from file"))
+        );
+        assert_eq!(
+            resolve("CF_TEST_UNSET", None, Some(String::from(" ")), None),
+            None
+        );
+        if cfg!(unix) {
+            let command = String::from("printf 'from command\\nmore'");
+            assert_eq!(
+                resolve("CF_TEST_UNSET", Some(&command), None, config.as_ref()),
+                Some(String::from("from command"))
+            );
+            let command = String::from("exit 1");
+            assert_eq!(
+                resolve("CF_TEST_UNSET", Some(&command), None, config.as_ref()),
+                Some(String::from("from config"))
+            );
+        }
+        env::set_var("CF_TEST_SET", "from env");
+        assert_eq!(
+            resolve("CF_TEST_SET", None, None, config.as_ref()),
+            Some(String::from("from env"))
+        );
+    }
+}
@@ -3,7 +3,7 @@ use std::io::{self, Stdout};
 
 use color_eyre::Result;
 use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture},
+    event::{DisableMouseCapture, EnableMouseCapture, KeyEvent},
     execute,
     terminal::{self, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -11,12 +11,25 @@ use crossterm::{
 use tuirealm::tui::{backend::CrosstermBackend, Terminal};
 
 use super::{
+    component::open_contest,
     event::{AppEvent, EventListener},
-    msg::{ChannelHandler, ViewConstructor, ViewMsg},
-    utils::is_terminate_key,
+    keymap::{keymap, Action},
+    msg::{ChannelHandler, Command, ViewConstructor, ViewMsg},
+    utils::{is_help_key, is_palette_key, is_terminate_key},
     view::View,
 };
 
+/// The actions of `view` and those available everywhere.
+fn actions(view: &dyn View) -> Vec<Action> {
+    let mut actions = view.actions();
+    for action in [Action::Help, Action::Palette, Action::Quit] {
+        if !actions.contains(&action) {
+            actions.push(action);
+        }
+    }
+    actions
+}
+
 pub struct App {
     running: bool,
     views: Vec<Box<dyn View>>,
@@ -92,6 +105,18 @@ impl App {
                     self.close();
                     break;
                 }
+                AppEvent::Key(evt)
+                    if is_help_key(evt) && view.is_fullscreen() && !view.is_editing() =>
+                {
+                    let actions = actions(view.as_ref());
+                    self.enter_new_view(ViewConstructor::Help(actions));
+                }
+                AppEvent::Key(evt)
+                    if is_palette_key(evt) && view.is_fullscreen() && !view.is_editing() =>
+                {
+                    let constructor = ViewConstructor::Palette(actions(view.as_ref()), view.tabs());
+                    self.enter_new_view(constructor);
+                }
                 event => {
                     if let Err(err) = view.handle_event(event) {
                         self.enter_new_view(ViewConstructor::ErrorPopup(
@@ -116,11 +141,48 @@ impl App {
                 }
                 ViewMsg::EnterNewView(constructor) => self.enter_new_view(constructor),
                 ViewMsg::ExitCurrentView => self.exit_current_view(),
+                ViewMsg::Run(command) => self.run_command(command),
                 ViewMsg::None => (),
             }
         }
     }
 
+    /// Runs a command of the palette on the current view.
+    fn run_command(&mut self, command: Command) {
+        let view = match self.views.last_mut() {
+            Some(view) => view,
+            None => return,
+        };
+        let result = match command {
+            Command::Action(Action::Help) => {
+                let actions = actions(view.as_ref());
+                self.enter_new_view(ViewConstructor::Help(actions));
+                Ok(())
+            }
+            Command::Action(Action::Quit) => {
+                self.close();
+                Ok(())
+            }
+            Command::Action(action) => match keymap().keys(action).first() {
+                Some(key) => {
+                    view.handle_event(&AppEvent::Key(KeyEvent::new(key.code, key.modifiers)))
+                }
+                None => Ok(()),
+            },
+            Command::Tab(index) => view.select_tab(index),
+            Command::OpenContest(source) => {
+                open_contest(self.msgs.sender.clone(), source);
+                Ok(())
+            }
+        };
+        if let Err(err) = result {
+            self.enter_new_view(ViewConstructor::ErrorPopup(
+                String::from("Error from View"),
+                format!("{err:#}"),
+            ))
+        }
+    }
+
     pub fn enter_new_view(&mut self, constructor: ViewConstructor) {
         let view = constructor.construct(&self.msgs.sender);
         self.views.push(view);
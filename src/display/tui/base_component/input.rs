@@ -0,0 +1,179 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use tuirealm::{
+    props::{BorderType, TextSpan},
+    tui::{
+        layout::Rect,
+        text::Spans,
+        widgets::{Block, Borders, Paragraph as TuiParagraph},
+    },
+    Frame,
+};
+
+use crate::display::tui::types::TextSpans;
+
+use super::BaseComponent;
+
+/// A line of text being edited, with a cursor moved by the arrow keys, Home and End.
+#[derive(Clone)]
+pub struct Input {
+    title: TextSpans,
+    text: Vec<char>,
+    cursor: usize,
+    /// Whether the text is shown as `*`, for passwords and keys.
+    secret: bool,
+    /// Whether the cursor is shown.
+    focused: bool,
+}
+
+impl BaseComponent for Input {
+    fn render(&mut self, frame: &mut Frame<'_>, area: Rect) {
+        let title = self.title.clone();
+        let text: Spans = self.spans().into();
+        let input = TuiParagraph::new(text).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .title(title),
+        );
+        frame.render_widget(input, area);
+    }
+}
+
+impl Input {
+    pub fn new(title: impl Into<TextSpans>, text: &str) -> Self {
+        let text: Vec<char> = text.chars().collect();
+        Self {
+            title: title.into(),
+            cursor: text.len(),
+            text,
+            secret: false,
+            focused: true,
+        }
+    }
+
+    pub fn set_title(&mut self, title: impl Into<TextSpans>) -> &mut Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn set_secret(&mut self, secret: bool) -> &mut Self {
+        self.secret = secret;
+        self
+    }
+
+    pub fn set_focused(&mut self, focused: bool) -> &mut Self {
+        self.focused = focused;
+        self
+    }
+
+    /// Inserts `text` at the cursor, dropping control characters like newlines.
+    pub fn insert(&mut self, text: &str) {
+        for c in text.chars().filter(|c| !c.is_control()) {
+            self.text.insert(self.cursor, c);
+            self.cursor += 1;
+        }
+    }
+
+    /// Handles a key editing the text, returning whether it is one.
+    pub fn on(&mut self, evt: &KeyEvent) -> bool {
+        match evt.code {
+            KeyCode::Char(c) if evt.modifiers - KeyModifiers::SHIFT == KeyModifiers::NONE => {
+                self.insert(&c.to_string())
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.text.remove(self.cursor);
+            }
+            KeyCode::Delete if self.cursor < self.text.len() => {
+                self.text.remove(self.cursor);
+            }
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.text.len()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.text.len(),
+            KeyCode::Backspace | KeyCode::Delete => (),
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn value(&self) -> String {
+        self.text.iter().collect()
+    }
+
+    /// The text with the cursor drawn reversed when focused, to be shown in another component.
+    pub fn spans(&self) -> TextSpans {
+        let shown = |chars: &[char]| match self.secret {
+            true => "*".repeat(chars.len()),
+            false => chars.iter().collect(),
+        };
+        if !self.focused {
+            return TextSpans::from(shown(&self.text));
+        }
+        let (before, rest) = self.text.split_at(self.cursor);
+        let (cursor, after) = match rest.split_first() {
+            Some((cursor, after)) => (shown(&[*cursor]), shown(after)),
+            None => (String::from(" "), String::new()),
+        };
+        TextSpans::from(vec![
+            TextSpan::new(shown(before)),
+            TextSpan::new(cursor).reversed(),
+            TextSpan::new(after),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(input: &mut Input, code: KeyCode) -> bool {
+        input.on(&KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn editing() {
+        let mut input = Input::new("", "ac");
+        press(&mut input, KeyCode::Left);
+        assert!(press(&mut input, KeyCode::Char('b')));
+        assert_eq!(input.value(), "abc");
+        assert!(input.on(&KeyEvent::new(KeyCode::Char('D'), KeyModifiers::SHIFT)));
+        assert_eq!(input.value(), "abDc");
+        assert!(!input.on(&KeyEvent::new(KeyCode::Char('g'), KeyModifiers::CONTROL)));
+        assert!(!press(&mut input, KeyCode::Enter));
+        assert_eq!(input.value(), "abDc");
+
+        // Backspace at the start and Delete at the end are handled, and change nothing.
+        press(&mut input, KeyCode::Home);
+        assert!(press(&mut input, KeyCode::Backspace));
+        assert_eq!(input.value(), "abDc");
+        assert!(press(&mut input, KeyCode::Delete));
+        assert_eq!(input.value(), "bDc");
+        press(&mut input, KeyCode::End);
+        assert!(press(&mut input, KeyCode::Delete));
+        assert_eq!(input.value(), "bDc");
+        assert!(press(&mut input, KeyCode::Backspace));
+        assert_eq!(input.value(), "bD");
+
+        input.insert("x\ny");
+        assert_eq!(input.value(), "bDxy");
+    }
+
+    #[test]
+    fn multibyte() {
+        let mut input = Input::new("", "数论");
+        press(&mut input, KeyCode::Left);
+        input.insert("é");
+        assert_eq!(input.value(), "数é论");
+        assert!(press(&mut input, KeyCode::Backspace));
+        assert!(press(&mut input, KeyCode::Backspace));
+        assert_eq!(input.value(), "论");
+        press(&mut input, KeyCode::Right);
+        press(&mut input, KeyCode::Right);
+        assert!(press(&mut input, KeyCode::Char('🎈')));
+        assert_eq!(input.value(), "论🎈");
+
+        input.set_secret(true);
+        assert_eq!(input.spans().to_string(), "** ");
+    }
+}
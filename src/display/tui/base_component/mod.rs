@@ -1,11 +1,13 @@
 use tuirealm::{tui::layout::Rect, Frame};
 
+mod input;
 mod paragraph;
 mod table;
 mod tabs;
 
+pub use input::Input;
 pub use paragraph::Paragraph;
-pub use table::Table;
+pub use table::{Comparator, Table};
 pub use tabs::Tabs;
 
 pub trait BaseComponent {
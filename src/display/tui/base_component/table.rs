@@ -1,49 +1,194 @@
-use lazy_static::lazy_static;
+use std::{cmp::Ordering, collections::HashMap};
+
+use chrono::NaiveDateTime;
+use crossterm::event::KeyEvent;
 use tuirealm::{
-    props::{BorderType, Color},
+    props::BorderType,
     tui::{
-        layout::{Constraint, Rect},
-        style::{Modifier, Style},
+        layout::{Constraint, Direction, Layout, Rect},
         widgets::{Block, Borders, Cell, Row, Table as TuiTable, TableState},
     },
     Frame,
 };
 
-use crate::display::tui::types::{Text, TextSpans};
+use crate::display::tui::{
+    event::AppEvent,
+    fuzzy::fuzzy_match,
+    keymap::{is_action, Action},
+    theme::theme,
+    types::{Text, TextSpans},
+    utils::{is_cancel_edit_key, is_confirm_edit_key, is_edit_down_key, is_edit_up_key},
+};
+
+use super::{BaseComponent, Input};
+
+/// Rows hidden unless they match what is typed, fuzzily across all the columns.
+struct Filter {
+    input: Input,
+    /// Whether the query is being typed, rather than kept while moving through the matches.
+    editing: bool,
+    /// The cells of the items matching, with the characters matched highlighted.
+    highlighted: HashMap<usize, Vec<Text>>,
+    /// The query the rows shown were matched against.
+    matched: String,
+}
+
+impl Filter {
+    /// Returns whether the item matches the query, highlighting the characters matched.
+    fn matches(&mut self, index: usize, item: &[Text], text: &str) -> bool {
+        let found = fuzzy_match(self.input.value().trim(), text);
+        match &found {
+            Some(found) if !found.positions.is_empty() => {
+                let cells: Vec<String> = item.iter().map(|text| text.to_string()).collect();
+                self.highlighted
+                    .insert(index, highlight_cells(item, &cells, &found.positions));
+            }
+            _ => {
+                self.highlighted.remove(&index);
+            }
+        }
+        found.is_some()
+    }
+}
+
+/// Returns whether the characters of `a` are found in `b` in order, so that whatever matches
+/// `b` matches `a` as well.
+fn is_subsequence(a: &str, b: &str) -> bool {
+    let mut chars = b.chars();
+    a.chars().all(|c| chars.any(|other| other == c))
+}
+
+/// How the cells of a column are compared when the table is sorted by it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparator {
+    /// By the first number, like `1800` of `1800A`, with sizes like `256 MB` in bytes.
+    Numeric,
+    /// By times like `2023-02-27 14:35:00`, unknown ones last.
+    Date,
+    /// By ratings, unrated ones last.
+    Rating,
+    /// Alphabetically, ignoring case.
+    String,
+}
+
+/// A cell as compared, the number found in it and its text.
+type SortKey = (Option<f64>, String);
+
+impl Comparator {
+    fn key(&self, text: &str) -> SortKey {
+        let number = match self {
+            Comparator::Numeric | Comparator::Rating => parse_number(text),
+            Comparator::Date => NaiveDateTime::parse_from_str(text.trim(), "%Y-%m-%d %H:%M:%S")
+                .ok()
+                .map(|time| time.timestamp() as f64),
+            Comparator::String => None,
+        };
+        (number, text.to_lowercase())
+    }
 
-use super::BaseComponent;
+    fn compare(&self, a: &SortKey, b: &SortKey, descending: bool) -> Ordering {
+        if matches!(self, Comparator::Date | Comparator::Rating) {
+            match (a.0, b.0) {
+                (None, Some(_)) => return Ordering::Greater,
+                (Some(_), None) => return Ordering::Less,
+                _ => (),
+            }
+        }
+        let ordering =
+            a.0.partial_cmp(&b.0)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| a.1.cmp(&b.1));
+        match descending {
+            true => ordering.reverse(),
+            false => ordering,
+        }
+    }
+}
+
+/// Finds the first number of `text` with its sign, in bytes when followed by a unit of size.
+fn parse_number(text: &str) -> Option<f64> {
+    let start = text.find(|c: char| c.is_ascii_digit())?;
+    let start = match text[..start].chars().last() {
+        Some('-') => start - 1,
+        _ => start,
+    };
+    let len = text[start + 1..]
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .map_or(text.len(), |len| start + 1 + len);
+    let number: f64 = text[start..len].parse().ok()?;
+    let unit = text[len..].split_whitespace().next().unwrap_or("");
+    let scale = match unit.to_uppercase().as_str() {
+        "KB" | "KIB" => 1e3,
+        "MB" | "MIB" => 1e6,
+        "GB" | "GIB" => 1e9,
+        _ => 1.0,
+    };
+    Some(number * scale)
+}
 
 pub struct Table {
+    /// The state of the rows shown, which are not the items when filtered.
     pub state: TableState,
-    pub items: Vec<Vec<Text>>,
+    items: Vec<Vec<Text>>,
     pub header: Vec<Text>,
     pub widths: Vec<Constraint>,
     pub title: TextSpans,
-}
-
-lazy_static! {
-    static ref HEADER_STYLE: Style = Style::default().fg(Color::Black);
-    static ref TABLE_HIGHLIGHT_STYLE: Style = Style::default().add_modifier(Modifier::REVERSED);
+    /// The indices of the items shown, in the order they are shown.
+    rows: Vec<usize>,
+    filter: Option<Filter>,
+    /// How each column is compared, `None` for columns that cannot be sorted by.
+    comparators: Vec<Option<Comparator>>,
+    /// The column sorted by and whether it is in descending order.
+    sort: Option<(usize, bool)>,
+    /// The cells of each item joined by newlines, which the filter is matched against.
+    texts: Vec<String>,
+    /// The key of each item in the column sorted by, empty when not sorted.
+    sort_keys: Vec<SortKey>,
+    /// Numbers the items are sorted by instead of the cells of a column, by column.
+    sort_numbers: HashMap<usize, Vec<f64>>,
 }
 
 impl BaseComponent for Table {
     fn render(&mut self, frame: &mut Frame<'_>, area: Rect) {
+        let area = match &mut self.filter {
+            Some(filter) => {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .margin(0)
+                    .constraints([Constraint::Min(0), Constraint::Length(3)].as_ref())
+                    .split(area);
+                filter.input.render(frame, chunks[1]);
+                chunks[0]
+            }
+            None => area,
+        };
+        let theme = theme();
         let header: Vec<Cell> = self
             .header
             .iter()
-            .map(|text| text.clone().bg(Color::Magenta).into())
+            .enumerate()
+            .map(|(column, text)| match self.sort {
+                Some((sorted, descending)) if sorted == column => {
+                    let arrow = if descending { "▼" } else { "▲" };
+                    Text::from(format!("{text} {arrow}")).into()
+                }
+                _ => text.clone().into(),
+            })
             .collect();
-        let header = Row::new(header)
-            .style(Style::default().bg(Color::Magenta))
-            .height(
-                self.header
-                    .iter()
-                    .map(|text| text.height().try_into().unwrap_or(u16::MAX))
-                    .max()
-                    .unwrap_or(1),
-            );
+        let header = Row::new(header).style(theme.header_style()).height(
+            self.header
+                .iter()
+                .map(|text| text.height().try_into().unwrap_or(u16::MAX))
+                .max()
+                .unwrap_or(1),
+        );
 
-        let rows = self.items.iter().map(|texts| {
+        let rows = self.rows.iter().map(|&index| {
+            let texts = self
+                .filter
+                .as_ref()
+                .and_then(|filter| filter.highlighted.get(&index))
+                .unwrap_or(&self.items[index]);
             let cells: Vec<Cell> = texts.iter().map(|text| text.clone().into()).collect();
             Row::new(cells).height(
                 texts
@@ -63,7 +208,7 @@ impl BaseComponent for Table {
                     .title(title),
             )
             .widths(&self.widths)
-            .highlight_style(TABLE_HIGHLIGHT_STYLE.clone());
+            .highlight_style(theme.highlight_style());
         frame.render_stateful_widget(table, area, &mut self.state);
     }
 }
@@ -82,28 +227,115 @@ impl Table {
             items: vec![],
             state: TableState::default(),
             widths,
+            rows: vec![],
+            filter: None,
+            comparators: vec![],
+            sort: None,
+            texts: vec![],
+            sort_keys: vec![],
+            sort_numbers: HashMap::new(),
         }
     }
 
+    /// The index of the item selected.
     pub fn selected(&self) -> usize {
         match self.state.selected() {
-            Some(i) => i,
+            Some(row) => self.rows.get(row).copied().unwrap_or(row),
             None => 0,
         }
     }
 
+    /// Selects the item at `index`, or one after it when it is filtered out.
     pub fn select(&mut self, index: usize) {
-        self.state.select(Some(index))
+        let row = self
+            .rows
+            .iter()
+            .position(|&i| i == index)
+            .or_else(|| self.rows.iter().position(|&i| i > index))
+            .unwrap_or(self.rows.len().saturating_sub(1));
+        self.state.select(Some(row))
+    }
+
+    /// The number of rows shown.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Whether fewer than `rows` rows are shown after the one selected, whatever the item
+    /// selected is.
+    pub fn is_near_end(&self, rows: usize) -> bool {
+        self.state.selected().unwrap_or(0) + rows >= self.rows.len()
     }
 
     pub fn set_items(&mut self, items: Vec<Vec<impl Into<Text>>>) -> &mut Self {
-        self.items = items
-            .into_iter()
-            .map(|texts| texts.into_iter().map(|text| text.into()).collect())
-            .collect();
+        self.items.clear();
+        self.texts.clear();
+        self.add_items(items);
+        self.update_sort_keys(0);
+        self.refresh();
         self
     }
 
+    /// Adds items after the others, only matching and sorting the new ones.
+    pub fn push_items(&mut self, items: Vec<Vec<impl Into<Text>>>) -> &mut Self {
+        let selected = self.state.selected().map(|_| self.selected());
+        let from = self.items.len();
+        self.add_items(items);
+        self.update_sort_keys(from);
+        let mut rows = self.matching(from..self.items.len());
+        self.sort_rows(&mut rows);
+        self.rows = match self.sort {
+            // Rows sorted the same stay in the order of the items, as with a stable sort.
+            Some((column, descending)) => {
+                let comparator = self.comparator(column).unwrap_or(Comparator::String);
+                let keys = &self.sort_keys;
+                let mut merged = Vec::with_capacity(self.rows.len() + rows.len());
+                let mut new_rows = rows.into_iter().peekable();
+                for row in self.rows.drain(..) {
+                    while let Some(&new_row) = new_rows.peek() {
+                        if comparator.compare(&keys[new_row], &keys[row], descending)
+                            != Ordering::Less
+                        {
+                            break;
+                        }
+                        merged.push(new_row);
+                        new_rows.next();
+                    }
+                    merged.push(row);
+                }
+                merged.extend(new_rows);
+                merged
+            }
+            None => {
+                let mut merged = std::mem::take(&mut self.rows);
+                merged.extend(rows);
+                merged
+            }
+        };
+        self.update_title();
+        if let Some(selected) = selected {
+            self.select(selected);
+        }
+        self
+    }
+
+    fn add_items(&mut self, items: Vec<Vec<impl Into<Text>>>) {
+        for item in items {
+            let item: Vec<Text> = item.into_iter().map(|text| text.into()).collect();
+            self.texts.push(
+                item.iter()
+                    .map(|text| text.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            );
+            self.items.push(item);
+        }
+    }
+
     pub fn set_header(&mut self, header: Vec<impl Into<Text>>) -> &mut Self {
         self.header = header.into_iter().map(|text| text.into()).collect();
         self
@@ -119,17 +351,58 @@ impl Table {
         self
     }
 
+    /// Sets how the columns are compared, keeping the order if the sorted column still can be
+    /// sorted by.
+    pub fn set_comparators(&mut self, comparators: Vec<Option<Comparator>>) -> &mut Self {
+        self.comparators = comparators;
+        if let Some((column, _)) = self.sort {
+            if self.comparator(column).is_none() {
+                self.sort = None;
+            }
+        }
+        self.update_sort_keys(0);
+        self.resort();
+        self
+    }
+
+    /// Sorts the items by `numbers` instead of the cells of `column`, e.g. durations by their
+    /// seconds rather than as shown.
+    pub fn set_sort_numbers(&mut self, column: usize, numbers: Vec<f64>) -> &mut Self {
+        self.sort_numbers.insert(column, numbers);
+        if self.sort.is_some_and(|(sorted, _)| sorted == column) {
+            self.update_sort_keys(0);
+            self.resort();
+        }
+        self
+    }
+
+    fn comparator(&self, column: usize) -> Option<Comparator> {
+        self.comparators.get(column).copied().flatten()
+    }
+
+    /// Sorts by the next column, going from ascending to descending order to the next column,
+    /// and back to the order of the items after the last column.
+    pub fn cycle_sort(&mut self) {
+        let next_column = |after: Option<usize>| {
+            (after.map_or(0, |column| column + 1)..self.header.len().max(self.comparators.len()))
+                .find(|&column| self.comparator(column).is_some())
+        };
+        self.sort = match self.sort {
+            None => next_column(None).map(|column| (column, false)),
+            Some((column, false)) => Some((column, true)),
+            Some((column, true)) => next_column(Some(column)).map(|column| (column, false)),
+        };
+        self.update_sort_keys(0);
+        self.resort();
+    }
+
     pub fn next(&mut self) {
         self.state.select(Some(match self.state.selected() {
             Some(i) => {
-                if i + 1 < self.items.len() {
+                if i + 1 < self.rows.len() {
                     i + 1
                 } else {
-                    if self.items.len() == 0 {
-                        0
-                    } else {
-                        self.items.len() - 1
-                    }
+                    self.rows.len().saturating_sub(1)
                 }
             }
             None => 0,
@@ -148,4 +421,405 @@ impl Table {
             None => 0,
         }))
     }
+
+    /// Whether the query of the filter is being typed.
+    pub fn is_editing(&self) -> bool {
+        self.filter.as_ref().is_some_and(|filter| filter.editing)
+    }
+
+    /// Handles the keys of the filter and of sorting, returning whether `event` is used.
+    ///
+    /// While the query is typed, Enter keeps the filter and Esc drops it. Otherwise, the filter
+    /// is opened again and the matches are gone through with wrapping.
+    pub fn on(&mut self, event: &AppEvent) -> bool {
+        match event {
+            AppEvent::Key(evt) if self.is_editing() => self.on_editing(evt),
+            AppEvent::Paste(text) if self.is_editing() => {
+                if let Some(filter) = &mut self.filter {
+                    filter.input.insert(text);
+                }
+                self.refilter();
+                true
+            }
+            AppEvent::Key(evt) if is_action(evt, Action::Filter) => {
+                let filter = self.filter.get_or_insert_with(|| Filter {
+                    input: Input::new("", ""),
+                    editing: true,
+                    highlighted: HashMap::new(),
+                    matched: String::new(),
+                });
+                filter.editing = true;
+                filter.input.set_focused(true);
+                self.refresh();
+                true
+            }
+            AppEvent::Key(evt) if self.filter.is_some() && is_action(evt, Action::NextMatch) => {
+                if !self.rows.is_empty() {
+                    let row = self.state.selected().map_or(0, |row| row + 1);
+                    self.state.select(Some(row % self.rows.len()));
+                }
+                true
+            }
+            AppEvent::Key(evt) if self.filter.is_some() && is_action(evt, Action::PrevMatch) => {
+                if !self.rows.is_empty() {
+                    let row = self.state.selected().unwrap_or(0);
+                    self.state
+                        .select(Some(row.checked_sub(1).unwrap_or(self.rows.len() - 1)));
+                }
+                true
+            }
+            AppEvent::Key(evt) if is_action(evt, Action::Sort) => {
+                self.cycle_sort();
+                self.comparators.iter().any(Option::is_some)
+            }
+            _ => false,
+        }
+    }
+
+    /// Edits the query, letting the arrow keys move through the rows.
+    fn on_editing(&mut self, evt: &KeyEvent) -> bool {
+        let filter = match &mut self.filter {
+            Some(filter) => filter,
+            None => return false,
+        };
+        if is_cancel_edit_key(evt) {
+            self.filter = None;
+            self.refresh();
+        } else if is_confirm_edit_key(evt) {
+            if filter.input.value().trim().is_empty() {
+                self.filter = None;
+                self.refresh();
+            } else {
+                filter.editing = false;
+                filter.input.set_focused(false);
+            }
+        } else if filter.input.on(evt) {
+            self.refilter();
+        } else {
+            return !is_edit_up_key(evt) && !is_edit_down_key(evt);
+        }
+        true
+    }
+
+    /// Works out the rows shown from the items and the filter, keeping the item selected.
+    fn refresh(&mut self) {
+        let selected = self.state.selected().map(|_| self.selected());
+        if let Some(filter) = &mut self.filter {
+            filter.highlighted.clear();
+        }
+        let mut rows = self.matching(0..self.items.len());
+        self.sort_rows(&mut rows);
+        self.rows = rows;
+        self.update_title();
+        if let Some(selected) = selected {
+            self.select(selected);
+        }
+    }
+
+    /// Matches the query just changed, against the rows shown only when it can only narrow
+    /// them, which keeps them in order.
+    fn refilter(&mut self) {
+        let narrowed = self
+            .filter
+            .as_ref()
+            .is_some_and(|filter| is_subsequence(&filter.matched, filter.input.value().trim()));
+        if !narrowed {
+            return self.refresh();
+        }
+        let selected = self.state.selected().map(|_| self.selected());
+        let rows = std::mem::take(&mut self.rows);
+        self.rows = self.matching(rows.into_iter());
+        self.update_title();
+        if let Some(selected) = selected {
+            self.select(selected);
+        }
+    }
+
+    /// Returns the items of `indices` matching the filter, all of them without one.
+    fn matching(&mut self, indices: impl Iterator<Item = usize>) -> Vec<usize> {
+        let filter = match &mut self.filter {
+            Some(filter) => filter,
+            None => return indices.collect(),
+        };
+        filter.matched = filter.input.value().trim().to_string();
+        indices
+            .filter(|&index| filter.matches(index, &self.items[index], &self.texts[index]))
+            .collect()
+    }
+
+    fn update_title(&mut self) {
+        if let Some(filter) = &mut self.filter {
+            filter
+                .input
+                .set_title(format!("Filter ({}/{})", self.rows.len(), self.items.len()));
+        }
+    }
+
+    /// Works out the keys of the items from `from` on in the column sorted by.
+    fn update_sort_keys(&mut self, from: usize) {
+        self.sort_keys.truncate(from);
+        let (column, _) = match self.sort {
+            Some(sort) => sort,
+            None => return self.sort_keys.clear(),
+        };
+        let comparator = self.comparator(column).unwrap_or(Comparator::String);
+        let numbers = self.sort_numbers.get(&column);
+        let keys: Vec<SortKey> = (self.sort_keys.len()..self.items.len())
+            .map(|index| {
+                let text = self.items[index]
+                    .get(column)
+                    .map(|text| text.to_string())
+                    .unwrap_or_default();
+                let mut key = comparator.key(&text);
+                if let Some(number) = numbers.and_then(|numbers| numbers.get(index)) {
+                    key.0 = Some(*number);
+                }
+                key
+            })
+            .collect();
+        self.sort_keys.extend(keys);
+    }
+
+    /// Sorts `rows` by the keys of the column sorted by, or in the order of the items.
+    fn sort_rows(&self, rows: &mut [usize]) {
+        match self.sort {
+            Some((column, descending)) => {
+                let comparator = self.comparator(column).unwrap_or(Comparator::String);
+                let keys = &self.sort_keys;
+                rows.sort_unstable_by(|&a, &b| {
+                    comparator
+                        .compare(&keys[a], &keys[b], descending)
+                        .then(a.cmp(&b))
+                });
+            }
+            None => rows.sort_unstable(),
+        }
+    }
+
+    /// Sorts the rows shown again, keeping the item selected.
+    fn resort(&mut self) {
+        let selected = self.state.selected().map(|_| self.selected());
+        let mut rows = std::mem::take(&mut self.rows);
+        self.sort_rows(&mut rows);
+        self.rows = rows;
+        if let Some(selected) = selected {
+            self.select(selected);
+        }
+    }
+}
+
+/// Highlights the characters at `positions` of the cells joined by newlines.
+fn highlight_cells(item: &[Text], cells: &[String], positions: &[usize]) -> Vec<Text> {
+    let mut start = 0;
+    item.iter()
+        .zip(cells)
+        .map(|(text, cell)| {
+            let len = cell.chars().count();
+            let cell_positions: Vec<usize> = positions
+                .iter()
+                .filter(|&&position| position >= start && position < start + len)
+                .map(|position| position - start)
+                .collect();
+            start += len + 1;
+            text.highlight(&cell_positions)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    use super::*;
+
+    fn key(code: KeyCode) -> AppEvent {
+        AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn filter() {
+        let mut table = Table::new(vec!["#", "Name"], vec![], "");
+        table.set_items(vec![
+            vec!["1800A", "Sum"],
+            vec!["1800B", "Count Binary Strings"],
+            vec!["1801A", "The Very Beautiful Blanket"],
+            vec!["1801B", "Buying gifts"],
+        ]);
+        table.select(3);
+        assert!(!table.on(&key(KeyCode::Char('b'))));
+
+        assert!(table.on(&key(KeyCode::Char('/'))));
+        assert!(table.is_editing());
+        for c in "1801b".chars() {
+            assert!(table.on(&key(KeyCode::Char(c))));
+        }
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.selected(), 3);
+        assert_eq!(
+            table.filter.as_ref().unwrap().highlighted[&3][0].to_string(),
+            "1801B"
+        );
+        assert!(!table.on(&key(KeyCode::Up)));
+
+        assert!(table.on(&key(KeyCode::Enter)));
+        assert!(!table.is_editing());
+        assert!(table.on(&key(KeyCode::Char('n'))));
+        assert_eq!(table.selected(), 2);
+        assert!(table.on(&key(KeyCode::Char('N'))));
+        assert_eq!(table.selected(), 3);
+
+        table.push_items(vec![vec!["1801C", "Bracket Walk"]]);
+        assert_eq!(table.len(), 3);
+
+        // Keys of actions typing a character are typed into the filter.
+        assert!(table.on(&key(KeyCode::Char('/'))));
+        for c in "qk".chars() {
+            assert!(table.on(&key(KeyCode::Char(c))));
+        }
+        assert!(table.is_editing());
+        assert_eq!(table.filter.as_ref().unwrap().input.value(), "1801bqk");
+        assert!(table.on(&key(KeyCode::Esc)));
+
+        assert!(table.on(&key(KeyCode::Char('/'))));
+        assert!(table.on(&AppEvent::Paste(String::from("zzz"))));
+        assert!(table.is_empty());
+        assert!(table.on(&key(KeyCode::Esc)));
+        assert_eq!(table.len(), 5);
+        assert!(!table.on(&key(KeyCode::Char('n'))));
+    }
+
+    #[test]
+    fn sort() {
+        assert_eq!(parse_number("1800A"), Some(1800.0));
+        assert_eq!(parse_number("-2"), Some(-2.0));
+        assert_eq!(parse_number("15ms"), Some(15.0));
+        assert_eq!(parse_number("1.5 MB"), Some(1.5e6));
+        assert_eq!(parse_number("Sum"), None);
+
+        let mut table = Table::new(vec!["#", "Name", "Rating", "Start"], vec![], "");
+        table.set_items(vec![
+            vec!["999A", "sum", "1500", "2018-06-01 10:00:00"],
+            vec!["1800B", "Count", "", ""],
+            vec!["1800A", "Beautiful", "800", "2023-02-27 14:35:00"],
+        ]);
+        assert!(!table.on(&key(KeyCode::Char('S'))));
+        table.set_comparators(vec![
+            Some(Comparator::Numeric),
+            Some(Comparator::String),
+            Some(Comparator::Rating),
+            Some(Comparator::Date),
+        ]);
+        table.select(1);
+
+        let order = |table: &Table| table.rows.clone();
+        assert!(table.on(&key(KeyCode::Char('S'))));
+        assert_eq!(order(&table), vec![0, 2, 1]);
+        assert_eq!(table.selected(), 1);
+        table.cycle_sort();
+        assert_eq!(order(&table), vec![1, 2, 0]);
+        table.cycle_sort();
+        assert_eq!(order(&table), vec![2, 1, 0]);
+        table.cycle_sort();
+        table.cycle_sort();
+        assert_eq!(order(&table), vec![2, 0, 1]);
+        table.cycle_sort();
+        assert_eq!(order(&table), vec![0, 2, 1]);
+        table.cycle_sort();
+        assert_eq!(order(&table), vec![0, 2, 1]);
+        table.cycle_sort();
+        assert_eq!(order(&table), vec![2, 0, 1]);
+        assert_eq!(table.sort, Some((3, true)));
+        table.cycle_sort();
+        assert_eq!(order(&table), vec![0, 1, 2]);
+        assert_eq!(table.selected(), 1);
+
+        table.set_comparators(vec![Some(Comparator::Numeric), None]);
+        table.cycle_sort();
+        table.set_comparators(vec![Some(Comparator::String)]);
+        assert_eq!(table.sort, Some((0, false)));
+        assert_eq!(order(&table), vec![2, 1, 0]);
+        table.select(0);
+        assert!(!table.is_near_end(0));
+        assert!(table.is_near_end(1));
+        table.select(2);
+        assert!(!table.is_near_end(2));
+        assert!(table.is_near_end(3));
+    }
+
+    #[test]
+    fn pushed_items() {
+        let items = vec![
+            vec!["1800A", "Sum", "800"],
+            vec!["1800B", "Count Binary Strings", "1000"],
+            vec!["1801A", "The Very Beautiful Blanket", "1600"],
+            vec!["1801B", "Buying gifts", "800"],
+            vec!["1801C", "Bracket Walk", "1000"],
+            vec!["1802A", "Likes", ""],
+        ];
+        let comparators = vec![
+            Some(Comparator::Numeric),
+            Some(Comparator::String),
+            Some(Comparator::Rating),
+        ];
+        let mut table = Table::new(vec!["#", "Name", "Rating"], vec![], "");
+        table.set_comparators(comparators.clone());
+        table.cycle_sort();
+        table.cycle_sort();
+        table.cycle_sort();
+        assert_eq!(table.sort, Some((1, false)));
+        table.on(&key(KeyCode::Char('/')));
+        table.on(&key(KeyCode::Char('b')));
+        table.set_items(items[..2].to_vec());
+        table.push_items(items[2..4].to_vec());
+        table.select(3);
+        table.push_items(items[4..].to_vec());
+        table.cycle_sort();
+        table.cycle_sort();
+        table.push_items(Vec::<Vec<&str>>::new());
+
+        // The same as with every item set at once.
+        let mut expected = Table::new(vec!["#", "Name", "Rating"], vec![], "");
+        expected.set_comparators(comparators);
+        expected.sort = table.sort;
+        expected.on(&key(KeyCode::Char('/')));
+        expected.on(&key(KeyCode::Char('b')));
+        expected.set_items(items);
+        assert_eq!(table.sort, Some((2, false)));
+        assert_eq!(table.rows, expected.rows);
+        assert_eq!(table.rows, vec![3, 1, 4, 2]);
+        assert_eq!(table.selected(), 3);
+
+        // A longer query only goes through the rows shown, a shorter one through every item.
+        table.on(&key(KeyCode::Char('r')));
+        assert_eq!(table.rows, vec![1, 4]);
+        table.on(&key(KeyCode::Char('a')));
+        assert_eq!(table.rows, vec![4]);
+        let narrowed = table.rows.clone();
+        table.refresh();
+        assert_eq!(table.rows, narrowed);
+        table.on(&key(KeyCode::Backspace));
+        table.on(&key(KeyCode::Backspace));
+        table.on(&key(KeyCode::Backspace));
+        assert_eq!(table.len(), 6);
+        assert_eq!(table.rows, vec![0, 3, 1, 4, 2, 5]);
+    }
+
+    #[test]
+    fn sort_numbers() {
+        let mut table = Table::new(vec!["Name", "Length"], vec![], "");
+        table.set_items(vec![
+            vec!["Div. 1", "2h 15m"],
+            vec!["Marathon", "10d"],
+            vec!["Div. 3", "2h"],
+        ]);
+        table.set_comparators(vec![None, Some(Comparator::Numeric)]);
+        table.set_sort_numbers(1, vec![8100.0, 864000.0, 7200.0]);
+        table.cycle_sort();
+        assert_eq!(table.rows, vec![2, 0, 1]);
+        table.push_items(vec![vec!["Educational", "2h"]]);
+        table.set_sort_numbers(1, vec![8100.0, 864000.0, 7200.0, 7200.0]);
+        assert_eq!(table.rows, vec![2, 3, 0, 1]);
+        table.cycle_sort();
+        assert_eq!(table.rows, vec![1, 0, 2, 3]);
+    }
 }
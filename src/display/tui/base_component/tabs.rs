@@ -1,16 +1,14 @@
 use color_eyre::{eyre::bail, Result};
-use lazy_static::lazy_static;
 use tuirealm::{
-    props::{BorderType, Color, Style},
+    props::{BorderType, Style},
     tui::{
         layout::Rect,
-        style::Modifier,
         widgets::{self, Block, Borders},
     },
     Frame,
 };
 
-use crate::display::tui::types::TextSpans;
+use crate::display::tui::{theme::theme, types::TextSpans};
 
 use super::BaseComponent;
 
@@ -19,13 +17,9 @@ pub struct Tabs {
     pub index: usize,
 }
 
-lazy_static! {
-    pub static ref TABS_STYLE: Style = Style::default().fg(Color::Cyan);
-    pub static ref TABS_HIGHLIGHT_STYLE: Style = Style::default().add_modifier(Modifier::REVERSED);
-}
-
 impl BaseComponent for Tabs {
     fn render(&mut self, frame: &mut Frame<'_>, area: Rect) {
+        let theme = theme();
         let titles = self.titles.iter().map(|text| text.into()).collect();
         let tabs = widgets::Tabs::new(titles)
             .block(
@@ -34,8 +28,8 @@ impl BaseComponent for Tabs {
                     .border_type(BorderType::Rounded),
             )
             .select(self.index)
-            .style(TABS_STYLE.clone())
-            .highlight_style(TABS_HIGHLIGHT_STYLE.clone());
+            .style(Style::default().fg(theme.accent))
+            .highlight_style(theme.highlight_style());
         frame.render_widget(tabs, area);
     }
 }
@@ -46,6 +40,10 @@ impl Tabs {
         Self { titles, index: 0 }
     }
 
+    pub fn titles(&self) -> Vec<String> {
+        self.titles.iter().map(|title| title.to_string()).collect()
+    }
+
     pub fn selected(&self) -> usize {
         self.index
     }
@@ -3,7 +3,10 @@ mod standings_list;
 mod submissions_list;
 mod tabs;
 
-pub use problems_list::ProblemsList;
+pub use problems_list::{
+    generate_view, open_problem, parse_view, submit_view, test_view, ProblemsList,
+    SCRIPT_PLACEHOLDERS, TEMPLATE_PLACEHOLDERS,
+};
 pub use standings_list::StandingsList;
 pub use submissions_list::SubmissionsList;
 pub use tabs::ContestBrowserTabs;
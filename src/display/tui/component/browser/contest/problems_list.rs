@@ -3,6 +3,7 @@ use std::{
     collections::HashMap,
     ffi::OsString,
     fs::{self, read_dir, read_to_string, write, DirBuilder},
+    io::ErrorKind,
     path::{Path, PathBuf},
     process::Stdio,
     time::Duration,
@@ -14,14 +15,14 @@ use color_eyre::{
     Report, Result,
 };
 
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::KeyEvent;
 
 use lazy_static::lazy_static;
 use std::sync::mpsc;
 use tokio::{io::AsyncWriteExt, process::Command, select, time::sleep};
 
 use tuirealm::{
-    props::{Alignment, BorderType, Color, TextSpan},
+    props::{Alignment, BorderType, TextSpan},
     tui::{
         layout::{Constraint, Rect},
         widgets::{Block, Borders, Paragraph},
@@ -31,21 +32,28 @@ use tuirealm::{
 
 use crate::{
     api::{
+        languages::{preferred_language, template_language},
+        locator::{ProblemLocator, ProblemSource},
         methods::{contest_standings, contest_status},
         objects::{Contest, Problem, SubmissionVerdict},
-        parse::{parse_testcase, TestCase},
-        utils::BASEURL,
+        parse::{
+            download, image_file_name, parse_contest_statements, parse_problem_page, ProblemPage,
+            ProblemStatement, TestCase,
+        },
+        session,
     },
+    credentials::CREDENTIALS,
     display::tui::{
         base_component::Table,
         component::{ComponentSender, ContentUpdateCmd, HandleSelectionFn, UpdateFn},
         error::NoConfigItemError,
         event::AppEvent,
+        keymap::{is_action, Action},
         msg::{ChannelHandler, ComponentMsg, ViewConstructor},
+        theme::theme,
         types::{TestCommands, TestResult, Text, TextSpans},
         utils::{
-            is_down_key, is_enter_key, is_key, is_refresh_key, is_scroll_down, is_scroll_up,
-            is_up_key,
+            is_down_key, is_enter_key, is_refresh_key, is_scroll_down, is_scroll_up, is_up_key,
         },
         view::get_chunk_with_ratio,
         BaseComponent, Component,
@@ -63,32 +71,37 @@ pub struct ProblemsList {
     sender: ComponentSender,
     handler: ChannelHandler<UpdateResult>,
     contest: Contest,
+    source: ProblemSource,
     component: Table,
     updating: u32,
     problems: Vec<Problem>,
 }
 
 fn is_generate_key(evt: &KeyEvent) -> bool {
-    is_key(evt, KeyCode::Char('g'), KeyModifiers::NONE)
+    is_action(evt, Action::Generate)
 }
 fn is_test_key(evt: &KeyEvent) -> bool {
-    is_key(evt, KeyCode::Char('t'), KeyModifiers::NONE)
+    is_action(evt, Action::Test)
 }
 
 fn is_parse_key(evt: &KeyEvent) -> bool {
-    is_key(evt, KeyCode::Char('p'), KeyModifiers::NONE)
+    is_action(evt, Action::Parse)
 }
 
 fn is_parse_all_key(evt: &KeyEvent) -> bool {
-    is_key(evt, KeyCode::Char('P'), KeyModifiers::SHIFT)
+    is_action(evt, Action::ParseAll)
 }
 
 fn is_submit_key(evt: &KeyEvent) -> bool {
-    is_key(evt, KeyCode::Char('s'), KeyModifiers::NONE)
+    is_action(evt, Action::Submit)
 }
 
 fn is_open_key(evt: &KeyEvent) -> bool {
-    is_key(evt, KeyCode::Char('o'), KeyModifiers::NONE)
+    is_action(evt, Action::Open)
+}
+
+fn is_browser_key(evt: &KeyEvent) -> bool {
+    is_action(evt, Action::Browser)
 }
 
 fn get_test_cases(path: &PathBuf) -> Vec<TestCase> {
@@ -103,7 +116,11 @@ fn get_test_cases(path: &PathBuf) -> Vec<TestCase> {
             Ok(answer) => answer,
             Err(_) => break,
         };
-        test_cases.push(TestCase { input, answer });
+        test_cases.push(TestCase {
+            input,
+            answer,
+            ..Default::default()
+        });
         i += 1;
     }
     return test_cases;
@@ -117,6 +134,7 @@ fn get_file_path_and_scripts(path: &PathBuf, file_name: &str) -> Result<(PathBuf
                 if let Some(file_ext) = file_path.extension() {
                     if let Some(file_ext) = file_ext.to_str() {
                         if let Some(scripts) = SETTINGS
+                            .get()
                             .commands
                             .clone()
                             .ok_or(NoConfigItemError {
@@ -154,7 +172,11 @@ async fn test(
         .stdin
         .take()
         .ok_or(eyre!("Failed to write to Stdin"))?;
-    stdin.write_all(test_case.input.as_bytes()).await?;
+    // A solution may exit without reading all of its input, which is judged by its output.
+    match stdin.write_all(test_case.input.as_bytes()).await {
+        Err(err) if err.kind() != ErrorKind::BrokenPipe => return Err(err.into()),
+        _ => drop(stdin),
+    }
     let delay = sleep(timeout);
     select! {
         _ = delay => {
@@ -163,7 +185,7 @@ async fn test(
         result = child.wait_with_output() => {
             match result {
                 Ok(output) => {
-                    let TestCase{input, answer} = test_case;
+                    let TestCase{input, answer, ..} = test_case;
                     let output = String::from_utf8_lossy(&output.stdout);
                     let output = output.trim().to_string();
                     let answer = answer.trim().to_string();
@@ -188,6 +210,29 @@ static FULL_PATH_PLACE_HOLDER: &str = "<% full %>";
 static PATH_PLACE_HOLDER: &str = "<% path %>";
 static FILE_PLACE_HOLDER: &str = "<% file %>";
 
+/// Placeholders replaced in scripts when testing or opening.
+pub static SCRIPT_PLACEHOLDERS: &[&str] =
+    &[PATH_PLACE_HOLDER, FULL_PATH_PLACE_HOLDER, FILE_PLACE_HOLDER];
+
+static USERNAME_PLACE_HOLDER: &str = "<% username %>";
+static YEAR_PLACE_HOLDER: &str = "<% year %>";
+static MONTH_PLACE_HOLDER: &str = "<% month %>";
+static DAY_PLACE_HOLDER: &str = "<% day %>";
+static HOUR_PLACE_HOLDER: &str = "<% hour %>";
+static MINUTE_PLACE_HOLDER: &str = "<% minute %>";
+static SECOND_PLACE_HOLDER: &str = "<% second %>";
+
+/// Placeholders replaced in template codes when generating.
+pub static TEMPLATE_PLACEHOLDERS: &[&str] = &[
+    USERNAME_PLACE_HOLDER,
+    YEAR_PLACE_HOLDER,
+    MONTH_PLACE_HOLDER,
+    DAY_PLACE_HOLDER,
+    HOUR_PLACE_HOLDER,
+    MINUTE_PLACE_HOLDER,
+    SECOND_PLACE_HOLDER,
+];
+
 fn get_command(full_path: &PathBuf, script: &str) -> Result<Command> {
     let full = full_path.display().to_string();
     let path = full_path
@@ -235,86 +280,152 @@ fn get_open_command(file_path: &PathBuf, scripts: CFScripts) -> Result<Option<Co
     Ok(open_command)
 }
 
-async fn parse(
-    sender: mpsc::Sender<ContentUpdateCmd>,
-    contest_id: i32,
-    problem_index: String,
-) -> Result<()> {
-    let home_dir = SETTINGS.home_dir.clone().ok_or(NoConfigItemError {
+/// The file of a problem directory keeping the template each code was generated from, by the
+/// name of the code.
+static GENERATED_FILE_NAME: &str = ".generated.json";
+
+fn read_generated(problem_dir: &Path) -> HashMap<String, String> {
+    read_to_string(problem_dir.join(GENERATED_FILE_NAME))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Keeps that the code at `path` is generated from the template `alias`.
+fn remember_template(path: &Path, alias: &str) -> Result<()> {
+    let problem_dir = path.parent().ok_or(eyre!("Code file has no parent!"))?;
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut generated = read_generated(problem_dir);
+    generated.insert(file_name, alias.to_string());
+    let generated_path = problem_dir.join(GENERATED_FILE_NAME);
+    write(&generated_path, serde_json::to_string_pretty(&generated)?).wrap_err(format!(
+        "Error occured when writing to {}",
+        generated_path.display()
+    ))
+}
+
+/// Returns the template the code at `path` is generated from, if it is still configured.
+fn generated_template(path: &Path) -> Option<CFTemplate> {
+    let file_name = path.file_name()?.to_string_lossy().to_string();
+    let alias = read_generated(path.parent()?).remove(&file_name)?;
+    SETTINGS
+        .get()
+        .templates
+        .iter()
+        .flatten()
+        .find(|template| template.alias == alias)
+        .cloned()
+}
+
+/// Returns the directory of the problem in `home_dir`, creating it if it does not exist.
+fn get_problem_dir(locator: &ProblemLocator) -> Result<PathBuf> {
+    let home_dir = SETTINGS.get().home_dir.clone().ok_or(NoConfigItemError {
         item: "home_dir".to_string(),
     })?;
-    let problem_dir = home_dir
-        .join("Contests")
-        .join(contest_id.to_string())
-        .join(&problem_index);
+    let problem_dir = locator.workspace_dir(&home_dir);
     let open_dir_err = eyre!(
-        "Failed to open directory when trying to parse problem: {}",
+        "Failed to open directory of problem: {}",
         problem_dir.display()
     );
     DirBuilder::new()
         .recursive(true)
         .create(&problem_dir)
         .wrap_err(open_dir_err)?;
+    Ok(problem_dir)
+}
 
-    let url = format!("{BASEURL}contest/{contest_id}/problem/{problem_index}");
-    let test_cases = parse_testcase(url).await?;
-    sender.send(ContentUpdateCmd::Set(
-        Text::from(format!(
-            "Parsed {} test cases for Problem {problem_index}",
-            test_cases.len()
-        ))
-        .fg(Color::Green),
-    ));
-    for (i, test_case) in test_cases.into_iter().enumerate() {
+/// Saves the samples into the problem directory.
+fn save_samples(problem_dir: &Path, samples: &[TestCase]) -> Result<()> {
+    for (i, test_case) in samples.iter().enumerate() {
         let id = i + 1;
         let input_path = problem_dir.join(format!("in{id}.txt"));
         let answer_path = problem_dir.join(format!("ans{id}.txt"));
-        fs::write(input_path, test_case.input)?;
-        fs::write(answer_path, test_case.answer)?;
+        fs::write(input_path, &test_case.input)?;
+        fs::write(answer_path, &test_case.answer)?;
+    }
+    Ok(())
+}
+
+/// Saves the samples, `statement.md` and the images of the statement into the problem
+/// directory, returning the number of samples and the images that could not be saved.
+async fn save_statement(
+    locator: &ProblemLocator,
+    statement: &ProblemStatement,
+) -> Result<(usize, Vec<String>)> {
+    let problem_dir = get_problem_dir(locator)?;
+    save_samples(&problem_dir, &statement.samples)?;
+    let statement_path = problem_dir.join("statement.md");
+    fs::write(&statement_path, statement.to_markdown()).wrap_err(format!(
+        "Error occured when writing to {}",
+        statement_path.display()
+    ))?;
+    let mut warnings = vec![];
+    for (index, url) in statement.images.iter().enumerate() {
+        let saved = match download(url.clone()).await {
+            Ok(image) => fs::write(problem_dir.join(image_file_name(index, url)), image)
+                .wrap_err(format!("Failed to save {url}")),
+            Err(err) => Err(err),
+        };
+        if let Err(err) = saved {
+            warnings.push(format!("{err:#}"));
+        }
     }
 
+    Ok((statement.samples.len(), warnings))
+}
+
+/// Fetches the problem page and saves it like [`save_statement`], or only its samples when it
+/// has no statement.
+async fn save_problem(locator: &ProblemLocator) -> Result<(usize, Vec<String>)> {
+    match parse_problem_page(locator.url()).await? {
+        ProblemPage::Statement(statement) => save_statement(locator, &statement).await,
+        ProblemPage::Samples(samples) => {
+            save_samples(&get_problem_dir(locator)?, &samples)?;
+            Ok((
+                samples.len(),
+                vec![String::from(
+                    "No statement found, only the samples are saved",
+                )],
+            ))
+        }
+    }
+}
+
+/// Reports the samples saved for a problem, with what went wrong besides.
+fn parsed_message(locator: &ProblemLocator, count: usize, warnings: &[String]) -> TextSpans {
+    let message = format!("Parsed {count} test cases for Problem {}", locator.index);
+    match warnings.is_empty() {
+        true => TextSpans::from(message).fg(theme().accepted),
+        false => TextSpans::from(format!("{message}. {}", warnings.join(". "))).fg(theme().warning),
+    }
+}
+
+async fn parse(sender: mpsc::Sender<ContentUpdateCmd>, locator: ProblemLocator) -> Result<()> {
+    let (count, warnings) = save_problem(&locator).await?;
+    sender.send(ContentUpdateCmd::Set(Text::from(vec![parsed_message(
+        &locator, count, &warnings,
+    )])));
+
     Ok(())
 }
 
 async fn parse_problem(
     sender: mpsc::Sender<ContentUpdateCmd>,
     index: usize,
-    contest_id: i32,
-    problem_index: String,
+    locator: ProblemLocator,
+    statement: Option<ProblemStatement>,
 ) -> Result<()> {
-    let home_dir = SETTINGS.home_dir.clone().ok_or(NoConfigItemError {
-        item: "home_dir".to_string(),
-    })?;
-    let problem_dir = home_dir
-        .join("Contests")
-        .join(contest_id.to_string())
-        .join(&problem_index);
-    let open_dir_err = eyre!(
-        "Failed to open directory when trying to parse problem: {}",
-        problem_dir.display()
-    );
-    DirBuilder::new()
-        .recursive(true)
-        .create(&problem_dir)
-        .wrap_err(open_dir_err)?;
-
-    let url = format!("{BASEURL}contest/{contest_id}/problem/{problem_index}");
-    let test_cases = parse_testcase(url).await?;
+    let (count, warnings) = match statement {
+        Some(statement) => save_statement(&locator, &statement).await?,
+        None => save_problem(&locator).await?,
+    };
     sender.send(ContentUpdateCmd::Change(
         index,
-        TextSpans::from(format!(
-            "Parsed {} test cases for Problem {problem_index}",
-            test_cases.len()
-        ))
-        .fg(Color::Green),
+        parsed_message(&locator, count, &warnings),
     ));
-    for (i, test_case) in test_cases.into_iter().enumerate() {
-        let id = i + 1;
-        let input_path = problem_dir.join(format!("in{id}.txt"));
-        let answer_path = problem_dir.join(format!("ans{id}.txt"));
-        fs::write(input_path, test_case.input)?;
-        fs::write(answer_path, test_case.answer)?;
-    }
 
     Ok(())
 }
@@ -343,6 +454,7 @@ impl Component for ProblemsList {
                 self.send(ComponentMsg::Update)?;
             }
             AppEvent::Key(evt) if is_enter_key(evt) => self.enter()?,
+            AppEvent::Key(evt) if is_browser_key(evt) => self.open_in_browser()?,
             AppEvent::Key(evt) if is_parse_key(evt) => self.parse()?,
             AppEvent::Key(evt) if is_parse_all_key(evt) => self.parse_all()?,
             AppEvent::Key(evt) if is_test_key(evt) => self.test()?,
@@ -361,6 +473,22 @@ impl Component for ProblemsList {
             self.render_loading(frame, area);
         }
     }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![
+            Action::Up,
+            Action::Down,
+            Action::Select,
+            Action::Refresh,
+            Action::Browser,
+            Action::Parse,
+            Action::ParseAll,
+            Action::Generate,
+            Action::Test,
+            Action::Open,
+            Action::Submit,
+        ]
+    }
 }
 
 lazy_static! {
@@ -391,7 +519,7 @@ async fn update(sender: mpsc::Sender<UpdateResult>, contest_id: i32) -> Result<(
             )
         })
         .collect();
-    if let Some(handle) = SETTINGS.username.clone() {
+    if let Some(handle) = SETTINGS.get().username.clone() {
         let submissions = contest_status(contest_id, Some(handle), None, None).await?;
         for submission in submissions {
             let index = submission.problem.index;
@@ -421,11 +549,11 @@ async fn update(sender: mpsc::Sender<UpdateResult>, contest_id: i32) -> Result<(
                 TextSpan::new(index),
                 TextSpan::new(name),
                 if status == "Accepted" {
-                    TextSpan::new("Accepted").fg(Color::Green)
+                    TextSpan::new("Accepted").fg(theme().accepted)
                 } else if status == "Rejected" {
-                    TextSpan::new("Rejected").fg(Color::Red)
+                    TextSpan::new("Rejected").fg(theme().rejected)
                 } else {
-                    TextSpan::new("Unrated").fg(Color::Gray)
+                    TextSpan::new("Unrated").fg(theme().muted)
                 },
             ]
             .into_iter()
@@ -443,8 +571,288 @@ async fn run_command(command: &mut Command) -> Result<()> {
     Ok(())
 }
 
+/// Builds the popup generating code from a template for the problem.
+pub fn generate_view(locator: &ProblemLocator) -> Result<ViewConstructor> {
+    let templates = SETTINGS.get().templates.clone().ok_or(eyre!("No templates available.\n Please configure templates in configuration file or run `cf-tui config`"))?;
+    let locator: &'static ProblemLocator = Box::leak(Box::new(locator.clone()));
+    let problem_index = &locator.index;
+
+    let title = TextSpans::from(format!("Generate for Problem {problem_index}"));
+    let header = vec![
+        Text::from("Name"),
+        Text::from("Lang"),
+        Text::from("Submitted in"),
+    ];
+    let widths = vec![
+        Constraint::Percentage(30),
+        Constraint::Percentage(20),
+        Constraint::Percentage(50),
+    ];
+    let items = templates
+        .iter()
+        .map(|template| {
+            vec![
+                Text::from(template.alias.clone()),
+                Text::from(template.lang.clone()),
+                Text::from(
+                    template_language(template)
+                        .map(|language| language.to_string())
+                        .unwrap_or_default(),
+                ),
+            ]
+        })
+        .collect();
+
+    let handle_selection: HandleSelectionFn = Box::new(|index| {
+        let templates = SETTINGS.get().templates.clone().ok_or(eyre!("No templates available.\n Please configure templates in configuration file or run `cf-tui config`"))?;
+        let problem_dir = get_problem_dir(locator)?;
+        let template: &CFTemplate = templates
+            .get(index)
+            .ok_or(eyre!(format!("No template #{index}.")))?;
+        let file_path = template.full_path()?;
+        let target_path = problem_dir.join(
+            Path::new(&locator.index).with_extension(file_path.extension().unwrap_or_default()),
+        );
+
+        let current_date = chrono::Local::now();
+        let content = read_to_string(file_path.clone())
+            .wrap_err(format!(
+                "Error occured when reading from {}",
+                file_path.display()
+            ))?
+            .replace(
+                USERNAME_PLACE_HOLDER,
+                &SETTINGS.get().username.clone().ok_or(NoConfigItemError {
+                    item: "username".to_string(),
+                })?,
+            )
+            .replace(YEAR_PLACE_HOLDER, &current_date.year().to_string())
+            .replace(MONTH_PLACE_HOLDER, &format!("{:02}", current_date.month()))
+            .replace(DAY_PLACE_HOLDER, &format!("{:02}", current_date.day()))
+            .replace(HOUR_PLACE_HOLDER, &format!("{:02}", current_date.hour()))
+            .replace(
+                MINUTE_PLACE_HOLDER,
+                &format!("{:02}", current_date.minute()),
+            )
+            .replace(
+                SECOND_PLACE_HOLDER,
+                &format!("{:02}", current_date.second()),
+            );
+        write(target_path.clone(), content).wrap_err(format!(
+            "Error occured when writing to {}",
+            target_path.display()
+        ))?;
+        // The code is submitted in the language of the template, not only of the extension.
+        remember_template(&target_path, &template.alias)?;
+        Ok(())
+    });
+    Ok(ViewConstructor::SelectPopup(
+        get_chunk_with_ratio((2, 1, 2), (1, 2, 1)),
+        handle_selection,
+        title,
+        header,
+        widths,
+        items,
+    ))
+}
+
+/// Builds the popup parsing the samples of the problem.
+pub fn parse_view(locator: &ProblemLocator, problem: &Problem) -> Result<ViewConstructor> {
+    if problem.tags.contains(&"interactive".to_string()) {
+        bail!("The problem is interactive. The traditional way of testing does not work.");
+    }
+    let locator = locator.clone();
+    let title = TextSpans::from(format!("Parse Problem {}", locator.index));
+    let text = Text::from("Parsing...");
+    let update: UpdateFn = Box::new(move |update_sender, popup_sender| {
+        tokio::spawn(async move {
+            if let Err(err) = parse(update_sender, locator).await {
+                popup_sender.send(ComponentMsg::EnterNewView(ViewConstructor::ErrorPopup(
+                    "Error from Parse".to_string(),
+                    format!("{err:?}"),
+                )));
+            }
+        });
+    });
+    Ok(ViewConstructor::UpdatablePopup(
+        get_chunk_with_ratio((1, 3, 1), (1, 3, 1)),
+        update,
+        title,
+        text,
+    ))
+}
+
+/// Builds the popup testing the code of the problem against the parsed samples.
+pub fn test_view(locator: &ProblemLocator) -> Result<ViewConstructor> {
+    let problem_index = locator.index.clone();
+    let problem_dir = get_problem_dir(locator)?;
+    let test_cases = get_test_cases(&problem_dir);
+    if test_cases.is_empty() {
+        bail!(
+            "Cannot find any test cases in {}.\n Maybe you should parse tests first?",
+            problem_dir.display()
+        );
+    }
+    let (file_path, scripts) = get_file_path_and_scripts(&problem_dir, &problem_index)?;
+    let texts: Text = (0..test_cases.len())
+        .enumerate()
+        .map(|(id, _)| TestResult::Testing.format(id + 1))
+        .collect::<Vec<Text>>()
+        .into();
+    let update: UpdateFn = Box::new(move |update_sender, popup_sender| {
+        tokio::spawn(async move {
+            let commands = match get_commands(&file_path, scripts.clone()) {
+                Ok(commands) => commands,
+                Err(err) => {
+                    popup_sender.send(ComponentMsg::EnterNewView(ViewConstructor::ErrorPopup(
+                        "Error from Test".to_string(),
+                        format!("{err:?}"),
+                    )));
+                    return;
+                }
+            };
+            if let Some(mut command) = commands.before_command {
+                if let Err(err) = run_command(&mut command).await {
+                    popup_sender.send(ComponentMsg::EnterNewView(ViewConstructor::ErrorPopup(
+                        "Error from Test: Before Command".to_string(),
+                        format!("{err:?}"),
+                    )));
+                    return;
+                }
+            }
+            for (i, test_case) in test_cases.into_iter().enumerate() {
+                let update_sender = update_sender.clone();
+                let commands = match get_commands(&file_path, scripts.clone()) {
+                    Ok(commands) => commands,
+                    Err(err) => {
+                        popup_sender.send(ComponentMsg::EnterNewView(ViewConstructor::ErrorPopup(
+                            "Error from Test".to_string(),
+                            format!("{err:?}"),
+                        )));
+                        continue;
+                    }
+                };
+                if let Err(err) = test(
+                    update_sender,
+                    i,
+                    Duration::from_millis(1000),
+                    test_case,
+                    commands,
+                )
+                .await
+                {
+                    popup_sender.send(ComponentMsg::EnterNewView(ViewConstructor::ErrorPopup(
+                        "Error from Test: Command".to_string(),
+                        format!("{err:?}"),
+                    )));
+                    continue;
+                }
+            }
+            if let Some(mut command) = commands.after_command {
+                if let Err(err) = run_command(&mut command).await {
+                    popup_sender.send(ComponentMsg::EnterNewView(ViewConstructor::ErrorPopup(
+                        "Error from Test: Before Command".to_string(),
+                        format!("{err:?}"),
+                    )));
+                }
+            }
+        });
+    });
+
+    Ok(ViewConstructor::UpdatablePopup(
+        get_chunk_with_ratio((1, 3, 1), (1, 3, 1)),
+        update,
+        TextSpans::from(format!("Test for Problem {problem_index}")),
+        texts,
+    ))
+}
+
+/// Opens the code of the problem with the `open_script` of its language.
+pub fn open_problem(popup_sender: ComponentSender, locator: &ProblemLocator) -> Result<()> {
+    let problem_index = &locator.index;
+    let problem_dir = get_problem_dir(locator)?;
+    let (file_path, scripts) = get_file_path_and_scripts(&problem_dir, problem_index)?;
+    futures::executor::block_on(async move {
+        let open_command = match get_open_command(&file_path, scripts.clone()) {
+            Ok(commands) => commands,
+            Err(err) => {
+                popup_sender.send(ComponentMsg::EnterNewView(ViewConstructor::ErrorPopup(
+                    "Error when opening file".to_string(),
+                    format!("{err:?}"),
+                )));
+                return;
+            }
+        };
+        if let Some(mut command) = open_command {
+            if let Err(err) = run_command(&mut command).await {
+                popup_sender.send(ComponentMsg::EnterNewView(ViewConstructor::ErrorPopup(
+                    "Error when opening file".to_string(),
+                    format!("{err:?}"),
+                )));
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Builds the popup submitting the code of the problem with the web session.
+///
+/// `notify` is told the id of the submission once it is accepted, so it can be tracked.
+pub fn submit_view(locator: &ProblemLocator, notify: ComponentSender) -> Result<ViewConstructor> {
+    let username = SETTINGS.get().username.clone().ok_or(NoConfigItemError {
+        item: "username".to_string(),
+    })?;
+    let problem_dir = get_problem_dir(locator)?;
+    let (file_path, _scripts) = get_file_path_and_scripts(&problem_dir, &locator.index)?;
+    let language = preferred_language(&file_path, generated_template(&file_path).as_ref())?;
+    let source = read_to_string(&file_path).wrap_err(format!(
+        "Error occured when reading from {}",
+        file_path.display()
+    ))?;
+    let file_name = file_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let locator = locator.clone();
+    let title = TextSpans::from(format!("Submit Problem {}", locator.index));
+    let text = Text::from("Submitting...");
+    let update: UpdateFn = Box::new(move |update_sender, popup_sender| {
+        tokio::spawn(async move {
+            let result = async {
+                session::ensure_login(&username, CREDENTIALS.get().password.as_deref()).await?;
+                session::submit(&locator, language.id, &source).await
+            };
+            match result.await {
+                Ok(id) => {
+                    update_sender.send(ContentUpdateCmd::Set(Text::from(
+                        TextSpan::new(format!("Submitted {file_name} in {language} as #{id}"))
+                            .fg(theme().accepted),
+                    )));
+                    notify.send(ComponentMsg::Submitted(id));
+                }
+                Err(err) => {
+                    update_sender.send(ContentUpdateCmd::Set(Text::from(
+                        TextSpan::new(format!("Failed to submit {file_name}")).fg(theme().error),
+                    )));
+                    popup_sender.send(ComponentMsg::EnterNewView(ViewConstructor::ErrorPopup(
+                        "Error from Submit".to_string(),
+                        format!("{err:?}"),
+                    )));
+                }
+            }
+        });
+    });
+    Ok(ViewConstructor::UpdatablePopup(
+        get_chunk_with_ratio((1, 3, 1), (1, 3, 1)),
+        update,
+        title,
+        text,
+    ))
+}
+
 impl ProblemsList {
-    pub fn new(sender: ComponentSender, contest: Contest) -> Self {
+    pub fn new(sender: ComponentSender, contest: Contest, source: ProblemSource) -> Self {
         let table = Table::new(
             DEFAULT_HEADER.clone(),
             DEFAULT_WIDTHS.clone(),
@@ -455,12 +863,21 @@ impl ProblemsList {
             sender,
             handler,
             contest,
+            source,
             component: table,
             updating: 0,
             problems: vec![],
         }
     }
 
+    pub fn problems(&self) -> &[Problem] {
+        &self.problems
+    }
+
+    pub fn is_updating(&self) -> bool {
+        self.updating != 0
+    }
+
     fn send(&mut self, msg: ComponentMsg) -> Result<()> {
         self.sender.send(msg)?;
         Ok(())
@@ -511,148 +928,44 @@ impl ProblemsList {
         frame.render_widget(loading, area);
     }
 
-    fn enter(&mut self) -> Result<()> {
+    fn selected_problem(&self) -> Result<&Problem> {
         let index = self.component.selected();
-        let index = self
-            .problems
-            .get(index)
-            .ok_or(eyre!(
-                "No such index: {index}\nCommonly this is a problem of the application."
-            ))?
-            .index
-            .clone();
-        let contest_id = self.contest.id;
-        let url = format!("{BASEURL}contest/{contest_id}/problem/{index}");
+        self.problems.get(index).ok_or(eyre!(
+            "No such index: {index}\nCommonly this is a problem of the application."
+        ))
+    }
+
+    fn selected_locator(&self) -> Result<ProblemLocator> {
+        let problem = self.selected_problem()?;
+        Ok(ProblemLocator::new(
+            self.source.clone(),
+            problem.index.clone(),
+        ))
+    }
+
+    fn enter(&mut self) -> Result<()> {
+        let problem = self.selected_problem()?.clone();
+        let locator = self.selected_locator()?;
+        self.send(ComponentMsg::EnterNewView(ViewConstructor::Statement(
+            locator, problem,
+        )))
+    }
+
+    fn open_in_browser(&mut self) -> Result<()> {
+        let url = self.selected_locator()?.url();
         webbrowser::open(&url)?;
         self.send(ComponentMsg::OpenedWebsite(url))?;
         Ok(())
     }
 
     fn generate(&mut self) -> Result<()> {
-        let templates = SETTINGS.templates.clone().ok_or(eyre!("No templates available.\n Please configure templates in configuration file or run `cf-tui config`"))?;
-        let index = self.component.selected();
-        let problem = self.problems.get(index).ok_or(eyre!(
-            "No such index: {index}\nCommonly this is a problem of the application."
-        ))?;
-        let contest_id: &'static i32 = Box::leak(Box::new(self.contest.id));
-        let problem_index: &'static String = Box::leak(Box::new(problem.index.clone()));
-
-        let title = TextSpans::from(format!("Generate for Problem {problem_index}"));
-        let header = vec![Text::from("Name"), Text::from("Lang")];
-        let widths = vec![Constraint::Percentage(50), Constraint::Percentage(50)];
-        let items = templates
-            .iter()
-            .map(|template| {
-                vec![
-                    Text::from(template.alias.clone()),
-                    Text::from(template.lang.clone()),
-                ]
-            })
-            .collect();
-
-        let handle_selection: HandleSelectionFn = Box::new(|index| {
-            let templates = SETTINGS.templates.clone().ok_or(eyre!("No templates available.\n Please configure templates in configuration file or run `cf-tui config`"))?;
-            let home_dir = SETTINGS.home_dir.clone().ok_or(NoConfigItemError {
-                item: "home_dir".to_string(),
-            })?;
-            let problem_dir = home_dir
-                .join("Contests")
-                .join(&*contest_id.to_string())
-                .join(&*problem_index);
-
-            let open_dir_err = eyre!(
-                "Failed to open directory when trying to test problem: {}",
-                problem_dir.display()
-            );
-            DirBuilder::new()
-                .recursive(true)
-                .create(&problem_dir)
-                .wrap_err(open_dir_err)?;
-            let template: &CFTemplate = templates
-                .get(index)
-                .ok_or(eyre!(format!("No template #{index}.")))?;
-            let template_dir = match dirs::config_dir() {
-                Some(config_dir) => {
-                    let template_dir = config_dir.join("cf").join("templates");
-                    DirBuilder::new().recursive(true).create(&template_dir)?;
-                    template_dir
-                }
-                None => bail!("Configuration directory not defined"),
-            };
-            let file_path = if !template.path.is_absolute() {
-                template_dir.join(&template.path)
-            } else {
-                template.path.clone()
-            };
-            let target_path = problem_dir.join(
-                Path::new(&*problem_index)
-                    .with_extension(file_path.extension().unwrap_or_default()),
-            );
-
-            let current_date = chrono::Local::now();
-            let content = read_to_string(file_path.clone())
-                .wrap_err(format!(
-                    "Error occured when reading from {}",
-                    file_path.display()
-                ))?
-                .replace(
-                    "<% username %>",
-                    &SETTINGS.username.clone().ok_or(NoConfigItemError {
-                        item: "username".to_string(),
-                    })?,
-                )
-                .replace("<% year %>", &current_date.year().to_string())
-                .replace("<% month %>", &format!("{:02}", current_date.month()))
-                .replace("<% day %>", &format!("{:02}", current_date.day()))
-                .replace("<% hour %>", &format!("{:02}", current_date.hour()))
-                .replace("<% minute %>", &format!("{:02}", current_date.minute()))
-                .replace("<% second %>", &format!("{:02}", current_date.second()));
-            write(target_path.clone(), content).wrap_err(format!(
-                "Error occured when writing to {}",
-                target_path.display()
-            ))?;
-            Ok(())
-        });
-        self.send(ComponentMsg::EnterNewView(ViewConstructor::SelectPopup(
-            get_chunk_with_ratio((2, 1, 2), (1, 2, 1)),
-            handle_selection,
-            title,
-            header,
-            widths,
-            items,
-        )))?;
-        Ok(())
+        let view = generate_view(&self.selected_locator()?)?;
+        self.send(ComponentMsg::EnterNewView(view))
     }
 
     fn parse(&mut self) -> Result<()> {
-        let index = self.component.selected();
-        let problem = self.problems.get(index).ok_or(eyre!(
-            "No such index: {index}\nCommonly this is a problem of the application."
-        ))?;
-        if problem.tags.contains(&"interactive".to_string()) {
-            bail!("The problem is interactive. The traditional way of testing does not work.");
-        }
-        let contest_id = self.contest.id;
-        let problem_index = problem.index.clone();
-        let title = TextSpans::from(format!("Parse Problem {problem_index}"));
-        let text = Text::from("Parsing...");
-        let update: UpdateFn = Box::new(move |update_sender, popup_sender| {
-            tokio::spawn(async move {
-                if let Err(err) = parse(update_sender, contest_id, problem_index).await {
-                    popup_sender.send(ComponentMsg::EnterNewView(ViewConstructor::ErrorPopup(
-                        "Error from Parse".to_string(),
-                        format!("{err:?}"),
-                    )));
-                }
-            });
-        });
-        self.send(ComponentMsg::EnterNewView(ViewConstructor::UpdatablePopup(
-            get_chunk_with_ratio((1, 3, 1), (1, 3, 1)),
-            update,
-            title,
-            text,
-        )))?;
-        Ok(())
+        let view = parse_view(&self.selected_locator()?, self.selected_problem()?)?;
+        self.send(ComponentMsg::EnterNewView(view))
     }
 
     fn parse_all(&mut self) -> Result<()> {
@@ -662,235 +975,60 @@ impl ProblemsList {
             .map(|_| "Parsing...")
             .collect::<Vec<&str>>()
             .into();
-        let contest_id = self.contest.id;
+        let source = self.source.clone();
         let update: UpdateFn = Box::new(move |update_sender, _| {
-            for (i, problem) in problems.into_iter().enumerate() {
-                if problem.tags.contains(&"interactive".to_string()) {
-                    update_sender.clone().send(ContentUpdateCmd::Change(i, TextSpans::from(format!("Problem {i} is interactive. The traditional way of testing does not work.")).fg(Color::Red)));
-                    continue;
-                }
-                let problem_index = problem.index.clone();
-                let update_sender = update_sender.clone();
-                let error_sender = update_sender.clone();
-                tokio::spawn(async move {
-                    if let Err(err) =
-                        parse_problem(update_sender, i, contest_id, problem_index.clone()).await
-                    {
-                        let err_msg = TextSpans::from(format!(
-                            "Failed to parse Problem {problem_index}: {err:?}"
-                        ))
-                        .fg(Color::Red);
-                        error_sender
-                            .clone()
-                            .send(ContentUpdateCmd::Change(i, err_msg));
-                    }
-                });
-            }
-        });
-        self.send(ComponentMsg::EnterNewView(ViewConstructor::UpdatablePopup(
-            get_chunk_with_ratio((1, 3, 1), (1, 3, 1)),
-            update,
-            title,
-            texts,
-        )))?;
-        Ok(())
-    }
-
-    fn test(&mut self) -> Result<()> {
-        let home_dir = SETTINGS.home_dir.clone().ok_or(NoConfigItemError {
-            item: "home_dir".to_string(),
-        })?;
-        let contest_id = self.contest.id;
-        let index = self.component.selected();
-        let problem = self.problems.get(index).ok_or(eyre!(
-            "No such index: {index}\nCommonly this is a problem of the application."
-        ))?;
-        let problem_index = problem.index.clone();
-        let problem_dir = home_dir
-            .join("Contests")
-            .join(contest_id.to_string())
-            .join(&problem_index);
-
-        let open_dir_err = eyre!(
-            "Failed to open directory when trying to test problem: {}",
-            problem_dir.display()
-        );
-        DirBuilder::new()
-            .recursive(true)
-            .create(&problem_dir)
-            .wrap_err(open_dir_err)?;
-        let test_cases = get_test_cases(&problem_dir);
-        if test_cases.is_empty() {
-            bail!(
-                "Cannot find any test cases in {}.\n Maybe you should parse tests first?",
-                problem_dir.display()
-            );
-        }
-        let (file_path, scripts) = get_file_path_and_scripts(&problem_dir, &problem_index)?;
-        let texts: Text = (0..test_cases.len())
-            .enumerate()
-            .map(|(id, _)| TestResult::Testing.format(id + 1))
-            .collect::<Vec<Text>>()
-            .into();
-        let update: UpdateFn = Box::new(move |update_sender, popup_sender| {
             tokio::spawn(async move {
-                let commands = match get_commands(&file_path, scripts.clone()) {
-                    Ok(commands) => commands,
-                    Err(err) => {
-                        popup_sender.send(ComponentMsg::EnterNewView(ViewConstructor::ErrorPopup(
-                            "Error from Test".to_string(),
-                            format!("{err:?}"),
-                        )));
-                        return;
-                    }
+                // Every statement is on the problems page, so a single request is enough unless
+                // it fails or misses some problems, which are then fetched one by one.
+                let mut statements = match source.problems_url() {
+                    Some(url) => parse_contest_statements(url).await.unwrap_or_default(),
+                    None => HashMap::new(),
                 };
-                if let Some(mut command) = commands.before_command {
-                    if let Err(err) = run_command(&mut command).await {
-                        popup_sender.send(ComponentMsg::EnterNewView(ViewConstructor::ErrorPopup(
-                            "Error from Test: Before Command".to_string(),
-                            format!("{err:?}"),
-                        )));
-                        return;
+                for (i, problem) in problems.into_iter().enumerate() {
+                    if problem.tags.contains(&"interactive".to_string()) {
+                        update_sender.clone().send(ContentUpdateCmd::Change(i, TextSpans::from(format!("Problem {i} is interactive. The traditional way of testing does not work.")).fg(theme().error)));
+                        continue;
                     }
-                }
-                for (i, test_case) in test_cases.into_iter().enumerate() {
+                    let problem_index = problem.index.clone();
+                    let locator = ProblemLocator::new(source.clone(), problem_index.clone());
+                    let statement = statements.remove(&problem_index);
                     let update_sender = update_sender.clone();
-                    let commands = match get_commands(&file_path, scripts.clone()) {
-                        Ok(commands) => commands,
-                        Err(err) => {
-                            popup_sender.send(ComponentMsg::EnterNewView(
-                                ViewConstructor::ErrorPopup(
-                                    "Error from Test".to_string(),
-                                    format!("{err:?}"),
-                                ),
-                            ));
-                            continue;
+                    let error_sender = update_sender.clone();
+                    tokio::spawn(async move {
+                        if let Err(err) = parse_problem(update_sender, i, locator, statement).await
+                        {
+                            let err_msg = TextSpans::from(format!(
+                                "Failed to parse Problem {problem_index}: {err:?}"
+                            ))
+                            .fg(theme().error);
+                            error_sender
+                                .clone()
+                                .send(ContentUpdateCmd::Change(i, err_msg));
                         }
-                    };
-                    if let Err(err) = test(
-                        update_sender,
-                        i,
-                        Duration::from_millis(1000),
-                        test_case,
-                        commands,
-                    )
-                    .await
-                    {
-                        popup_sender.send(ComponentMsg::EnterNewView(ViewConstructor::ErrorPopup(
-                            "Error from Test: Command".to_string(),
-                            format!("{err:?}"),
-                        )));
-                        continue;
-                    }
-                }
-                if let Some(mut command) = commands.after_command {
-                    if let Err(err) = run_command(&mut command).await {
-                        popup_sender.send(ComponentMsg::EnterNewView(ViewConstructor::ErrorPopup(
-                            "Error from Test: Before Command".to_string(),
-                            format!("{err:?}"),
-                        )));
-                        return;
-                    }
+                    });
                 }
             });
         });
-
         self.send(ComponentMsg::EnterNewView(ViewConstructor::UpdatablePopup(
             get_chunk_with_ratio((1, 3, 1), (1, 3, 1)),
             update,
-            TextSpans::from(format!("Test for Problem {problem_index}")),
+            title,
             texts,
         )))?;
         Ok(())
     }
 
-    fn open(&mut self) -> Result<()> {
-        let home_dir = SETTINGS.home_dir.clone().ok_or(NoConfigItemError {
-            item: "home_dir".to_string(),
-        })?;
-        let contest_id = self.contest.id;
-        let index = self.component.selected();
-        let problem = self.problems.get(index).ok_or(eyre!(
-            "No such index: {index}\nCommonly this is a problem of the application."
-        ))?;
-        let problem_index = problem.index.clone();
-        let problem_dir = home_dir
-            .join("Contests")
-            .join(contest_id.to_string())
-            .join(&problem_index);
-
-        let open_dir_err = eyre!(
-            "Failed to open directory when trying to test problem: {}",
-            problem_dir.display()
-        );
-        DirBuilder::new()
-            .recursive(true)
-            .create(&problem_dir)
-            .wrap_err(open_dir_err)?;
-
-        let (file_path, scripts) = get_file_path_and_scripts(&problem_dir, &problem_index)?;
-        println!("{:?}", file_path);
-
-        let popup_sender = self.sender.clone();
-        futures::executor::block_on(async move {
-            let open_command = match get_open_command(&file_path, scripts.clone()) {
-                Ok(commands) => commands,
-                Err(err) => {
-                    popup_sender.send(ComponentMsg::EnterNewView(ViewConstructor::ErrorPopup(
-                        "Error when opening file".to_string(),
-                        format!("{err:?}"),
-                    )));
-                    return;
-                }
-            };
-            if let Some(mut command) = open_command {
-                if let Err(err) = run_command(&mut command).await {
-                    popup_sender.send(ComponentMsg::EnterNewView(ViewConstructor::ErrorPopup(
-                        "Error when opening file".to_string(),
-                        format!("{err:?}"),
-                    )));
-                    return;
-                }
-            }
-        });
+    fn test(&mut self) -> Result<()> {
+        let view = test_view(&self.selected_locator()?)?;
+        self.send(ComponentMsg::EnterNewView(view))
+    }
 
-        Ok(())
+    fn open(&mut self) -> Result<()> {
+        open_problem(self.sender.clone(), &self.selected_locator()?)
     }
 
     fn submit(&mut self) -> Result<()> {
-        let home_dir = SETTINGS.home_dir.clone().ok_or(NoConfigItemError {
-            item: "home_dir".to_string(),
-        })?;
-        let contest_id = self.contest.id;
-        let index = self.component.selected();
-        let problem = self.problems.get(index).ok_or(eyre!(
-            "No such index: {index}\nCommonly this is a problem of the application."
-        ))?;
-        let problem_index = problem.index.clone();
-        let problem_dir = home_dir
-            .join("Contests")
-            .join(contest_id.to_string())
-            .join(&problem_index);
-
-        let open_dir_err = eyre!(
-            "Failed to open directory when trying to test problem: {}",
-            problem_dir.display()
-        );
-        DirBuilder::new()
-            .recursive(true)
-            .create(&problem_dir)
-            .wrap_err(open_dir_err)?;
-        let (file_path, _scripts) = get_file_path_and_scripts(&problem_dir, &problem_index)?;
-
-        let content = read_to_string(&file_path).wrap_err(format!(
-            "Error occured when reading from {}",
-            file_path.display()
-        ))?;
-        if let Err(err) = terminal_clipboard::set_string(content) {
-            bail!("Error occured when trying to copy code to clipboard: {err:?}");
-        }
-        let url = format!("{BASEURL}contest/{contest_id}/submit/{problem_index}");
-        webbrowser::open(&url);
-        Ok(())
+        let view = submit_view(&self.selected_locator()?, self.sender.clone())?;
+        self.send(ComponentMsg::EnterNewView(view))
     }
 }
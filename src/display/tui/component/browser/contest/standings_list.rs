@@ -18,9 +18,10 @@ use crate::{
         objects::{Contest, ProblemResult},
     },
     display::tui::{
-        base_component::Table,
+        base_component::{Comparator, Table},
         component::ComponentSender,
         event::AppEvent,
+        keymap::Action,
         msg::{ChannelHandler, ComponentMsg, ViewConstructor},
         types::Text,
         utils::{is_down_key, is_refresh_key, is_scroll_down, is_scroll_up, is_up_key},
@@ -33,6 +34,7 @@ struct UpdateResult {
     items: Vec<Vec<Text>>,
     header: Vec<String>,
     widths: Vec<Constraint>,
+    comparators: Vec<Option<Comparator>>,
 }
 
 pub struct StandingsList {
@@ -45,6 +47,10 @@ pub struct StandingsList {
 
 impl Component for StandingsList {
     fn on(&mut self, event: &AppEvent) -> Result<()> {
+        if self.component.on(event) {
+            self.send(ComponentMsg::ChangedTo(self.component.selected()))?;
+            return Ok(());
+        }
         match event {
             AppEvent::Key(evt) if is_up_key(evt) => {
                 self.component.next();
@@ -78,12 +84,30 @@ impl Component for StandingsList {
             self.render_loading(frame, area);
         }
     }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![
+            Action::Up,
+            Action::Down,
+            Action::Refresh,
+            Action::Filter,
+            Action::NextMatch,
+            Action::PrevMatch,
+            Action::Sort,
+        ]
+    }
+
+    fn is_editing(&self) -> bool {
+        self.component.is_editing()
+    }
 }
 
 lazy_static! {
     static ref DEFAULT_HEADER: Vec<String> = vec![String::from("#"), String::from("Name")];
     static ref DEFAULT_WIDTHS: Vec<Constraint> =
         vec![Constraint::Length(5), Constraint::Percentage(50),];
+    static ref DEFAULT_COMPARATORS: Vec<Option<Comparator>> =
+        vec![Some(Comparator::Numeric), Some(Comparator::String)];
 }
 
 async fn update(sender: mpsc::Sender<UpdateResult>, contest_id: i32) -> Result<()> {
@@ -96,8 +120,10 @@ async fn update(sender: mpsc::Sender<UpdateResult>, contest_id: i32) -> Result<(
         .collect();
     let mut header = DEFAULT_HEADER.clone();
     let mut widths = DEFAULT_WIDTHS.clone();
+    let mut comparators = DEFAULT_COMPARATORS.clone();
     for _ in 0..indexes.iter().count() {
         widths.push(Constraint::Min(4));
+        comparators.push(Some(Comparator::Numeric));
     }
     header.append(&mut indexes);
 
@@ -127,17 +153,19 @@ async fn update(sender: mpsc::Sender<UpdateResult>, contest_id: i32) -> Result<(
         items,
         header,
         widths,
+        comparators,
     });
     Ok(())
 }
 
 impl StandingsList {
     pub fn new(sender: ComponentSender, contest: Contest) -> Self {
-        let table = Table::new(
+        let mut table = Table::new(
             DEFAULT_HEADER.clone(),
             DEFAULT_WIDTHS.clone(),
             contest.name.clone(),
         );
+        table.set_comparators(DEFAULT_COMPARATORS.clone());
         let handler = ChannelHandler::new();
         Self {
             sender,
@@ -154,11 +182,13 @@ impl StandingsList {
                 items,
                 header,
                 widths,
+                comparators,
             } = result;
             self.component
                 .set_items(items)
                 .set_header(header)
-                .set_widths(widths);
+                .set_widths(widths)
+                .set_comparators(comparators);
             self.updating -= 1;
         }
     }
@@ -6,10 +6,15 @@ use color_eyre::{
     Result,
 };
 
+use futures::{pin_mut, StreamExt, TryStreamExt};
 use lazy_static::lazy_static;
-use std::sync::mpsc;
+use std::{
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 use tuirealm::{
-    props::{Alignment, BorderType, Color, TextSpan},
+    props::{Alignment, BorderType, TextSpan},
     tui::{
         layout::{Constraint, Rect},
         widgets::{Block, Borders, Paragraph},
@@ -19,15 +24,17 @@ use tuirealm::{
 
 use crate::{
     api::{
-        methods::contest_status,
+        methods::contest_status_stream,
         objects::{Contest, Submission, SubmissionVerdict},
-        utils::BASEURL,
+        utils::base_url,
     },
     display::tui::{
-        base_component::Table,
+        base_component::{Comparator, Table},
         component::ComponentSender,
         event::AppEvent,
+        keymap::Action,
         msg::{ChannelHandler, ComponentMsg, ViewConstructor},
+        theme::theme,
         types::Text,
         utils::{
             is_down_key, is_enter_key, is_refresh_key, is_scroll_down, is_scroll_up, is_up_key,
@@ -37,10 +44,22 @@ use crate::{
     settings::SETTINGS,
 };
 
+/// Number of submissions loaded at a time, unless set by [`SubmissionsList::set_page_size`].
+static PAGE_SIZE: usize = 50;
+/// More submissions are loaded once the selection gets this close to the last one.
+static LOAD_AHEAD: usize = 10;
+/// How often the loaded submissions are reloaded while a tracked submission is being judged.
+static TRACK_INTERVAL: Duration = Duration::from_secs(2);
+/// How long a tracked submission may be missing from the API before tracking gives up.
+static TRACK_MISSING_TIMEOUT: Duration = Duration::from_secs(60);
+
 #[derive(Debug, Default)]
 struct UpdateResult {
     items: Vec<Vec<Text>>,
     submissions: Vec<Submission>,
+    finished: bool,
+    /// The submissions could not be loaded, so those already loaded are kept.
+    failed: bool,
 }
 
 pub struct SubmissionsList {
@@ -50,10 +69,23 @@ pub struct SubmissionsList {
     component: Table,
     updating: u32,
     submissions: Vec<Submission>,
+    more_sender: Option<UnboundedSender<usize>>,
+    page_size: usize,
+    loading_more: bool,
+    finished: bool,
+    /// The loaded submissions are replaced by the next ones instead of being extended.
+    reloading: bool,
+    tracking: Option<i32>,
+    tracking_since: Instant,
+    last_update: Instant,
 }
 
 impl Component for SubmissionsList {
     fn on(&mut self, event: &AppEvent) -> Result<()> {
+        if self.component.on(event) {
+            self.send(ComponentMsg::ChangedTo(self.component.selected()))?;
+            return Ok(());
+        }
         match event {
             AppEvent::Key(evt) if is_up_key(evt) => {
                 self.component.prev();
@@ -65,17 +97,19 @@ impl Component for SubmissionsList {
             }
             AppEvent::Key(evt) if is_down_key(evt) => {
                 self.component.next();
+                self.load_more();
                 self.send(ComponentMsg::ChangedTo(self.component.selected()))?;
             }
             AppEvent::Mouse(evt) if is_scroll_down(evt) => {
                 self.component.next();
+                self.load_more();
                 self.send(ComponentMsg::ChangedTo(self.component.selected()))?;
             }
             AppEvent::Key(evt) if is_refresh_key(evt) => {
                 self.update();
                 self.send(ComponentMsg::Update)?;
             }
-            AppEvent::Key(evt) if is_enter_key(evt) => {
+            AppEvent::Key(evt) if is_enter_key(evt) && !self.component.is_empty() => {
                 let index = self.component.selected();
                 let id = self
                     .submissions
@@ -86,7 +120,7 @@ impl Component for SubmissionsList {
                     .id
                     .clone();
                 let contest_id = self.contest.id;
-                let url = format!("{BASEURL}contest/{contest_id}/submission/{id}");
+                let url = format!("{}contest/{contest_id}/submission/{id}", base_url());
                 webbrowser::open(url.as_str())?;
                 self.send(ComponentMsg::OpenedWebsite(url))?;
             }
@@ -102,6 +136,23 @@ impl Component for SubmissionsList {
             self.render_loading(frame, area);
         }
     }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![
+            Action::Up,
+            Action::Down,
+            Action::Select,
+            Action::Refresh,
+            Action::Filter,
+            Action::NextMatch,
+            Action::PrevMatch,
+            Action::Sort,
+        ]
+    }
+
+    fn is_editing(&self) -> bool {
+        self.component.is_editing()
+    }
 }
 
 lazy_static! {
@@ -119,77 +170,97 @@ lazy_static! {
         Constraint::Percentage(10),
         Constraint::Percentage(10),
     ];
+    pub static ref DEFAULT_COMPARATORS: Vec<Option<Comparator>> = vec![
+        Some(Comparator::Date),
+        Some(Comparator::String),
+        Some(Comparator::String),
+        Some(Comparator::Numeric),
+        Some(Comparator::Numeric),
+    ];
 }
 
-async fn update(sender: mpsc::Sender<UpdateResult>, contest_id: i32) -> Result<()> {
-    if let None = SETTINGS.username {
-        bail!(
-            "No username configured.\
-               Please configure your usename."
-        );
-    }
-    let submissions = contest_status(contest_id, SETTINGS.username.clone(), None, None).await?;
-
-    let items: Vec<Vec<Text>> = submissions
-        .iter()
-        .map(|submission| {
-            let naive = NaiveDateTime::from_timestamp_opt(submission.creationTimeSeconds, 0)
-                .unwrap_or(NaiveDateTime::default());
-            let datetime: DateTime<Utc> = DateTime::from_utc(naive, Utc);
-            let when = datetime.format("%Y-%m-%d %H:%M:%S").to_string();
-
-            let name = format!(
-                "{} - {}",
-                submission.problem.index.clone(),
-                submission.problem.name.clone()
-            );
-
-            let verdict = match submission.verdict.clone() {
-                Some(SubmissionVerdict::OK) => TextSpan::new("Accepted").fg(Color::Green),
-                Some(SubmissionVerdict::TESTING) => TextSpan::new(format!(
-                    "Testing on test {}",
-                    submission.passedTestCount + 1
-                ))
-                .fg(Color::Blue),
-                Some(verdict) => TextSpan::new(format!(
-                    "{} on test {}",
-                    verdict,
-                    submission.passedTestCount + 1
-                ))
-                .fg(Color::Red),
-                None => TextSpan::new("Failed").fg(Color::Red),
-            };
+fn format_item(submission: &Submission) -> Vec<Text> {
+    let naive =
+        NaiveDateTime::from_timestamp_opt(submission.creationTimeSeconds, 0).unwrap_or_default();
+    let datetime: DateTime<Utc> = DateTime::from_utc(naive, Utc);
+    let when = datetime.format("%Y-%m-%d %H:%M:%S").to_string();
+
+    let name = format!(
+        "{} - {}",
+        submission.problem.index.clone(),
+        submission.problem.name.clone()
+    );
 
-            let time_consumed = format!("{}ms", submission.timeConsumedMillis);
-            let memory_consumed = format!(
-                "{}",
-                ByteSize::b(submission.memoryConsumedBytes).to_string_as(false)
-            );
-
-            vec![
-                TextSpan::new(when),
-                TextSpan::new(name),
-                verdict,
-                TextSpan::new(time_consumed),
-                TextSpan::new(memory_consumed),
-            ]
-            .into_iter()
-            .map(|text| text.into())
-            .collect::<Vec<Text>>()
-        })
-        .collect();
-
-    sender.send(UpdateResult { items, submissions });
+    let verdict = match submission.verdict.clone() {
+        Some(SubmissionVerdict::OK) => TextSpan::new("Accepted").fg(theme().accepted),
+        Some(SubmissionVerdict::TESTING) => TextSpan::new(format!(
+            "Testing on test {}",
+            submission.passedTestCount + 1
+        ))
+        .fg(theme().pending),
+        Some(verdict) => TextSpan::new(format!(
+            "{} on test {}",
+            verdict,
+            submission.passedTestCount + 1
+        ))
+        .fg(theme().rejected),
+        None => TextSpan::new("Failed").fg(theme().rejected),
+    };
+
+    let time_consumed = format!("{}ms", submission.timeConsumedMillis);
+    let memory_consumed = ByteSize::b(submission.memoryConsumedBytes).to_string_as(false);
+
+    vec![
+        TextSpan::new(when),
+        TextSpan::new(name),
+        verdict,
+        TextSpan::new(time_consumed),
+        TextSpan::new(memory_consumed),
+    ]
+    .into_iter()
+    .map(|text| text.into())
+    .collect::<Vec<Text>>()
+}
+
+/// Loads submissions `page_size` at a time, each time a number of submissions is requested
+/// through `more_receiver`, until all submissions are loaded or the list is dropped.
+async fn update(
+    sender: mpsc::Sender<UpdateResult>,
+    mut more_receiver: UnboundedReceiver<usize>,
+    contest_id: i32,
+    page_size: usize,
+) -> Result<()> {
+    let Some(username) = SETTINGS.get().username.clone() else {
+        bail!("No username configured. Please configure your username.");
+    };
+    let submissions = contest_status_stream(contest_id, Some(username), page_size as i32);
+    pin_mut!(submissions);
+
+    while let Some(count) = more_receiver.recv().await {
+        let submissions: Vec<Submission> = submissions.as_mut().take(count).try_collect().await?;
+        let finished = submissions.len() < count;
+        let items = submissions.iter().map(format_item).collect();
+        let result = UpdateResult {
+            items,
+            submissions,
+            finished,
+            failed: false,
+        };
+        if sender.send(result).is_err() || finished {
+            break;
+        }
+    }
     Ok(())
 }
 
 impl SubmissionsList {
     pub fn new(sender: ComponentSender, contest: Contest) -> Self {
-        let table = Table::new(
+        let mut table = Table::new(
             DEFAULT_HEADER.clone(),
             DEFAULT_WIDTHS.clone(),
             contest.name.clone(),
         );
+        table.set_comparators(DEFAULT_COMPARATORS.clone());
         let handler = ChannelHandler::new();
         Self {
             sender,
@@ -198,16 +269,96 @@ impl SubmissionsList {
             component: table,
             updating: 0,
             submissions: vec![],
+            more_sender: None,
+            page_size: PAGE_SIZE,
+            loading_more: false,
+            finished: false,
+            reloading: false,
+            tracking: None,
+            tracking_since: Instant::now(),
+            last_update: Instant::now(),
         }
     }
 
     pub fn tick(&mut self) {
         while let Ok(result) = self.handler.try_next() {
-            let UpdateResult { items, submissions } = result;
-            self.component.set_items(items);
-            self.submissions = submissions;
-            self.updating -= 1;
+            let UpdateResult {
+                items,
+                submissions,
+                finished,
+                failed,
+            } = result;
+            if failed {
+                // Reloading again would only report the same error every time.
+                self.tracking = None;
+                self.reloading = false;
+            } else if self.reloading {
+                // The selection stays on the same submission, even if new ones come first.
+                let selected = self
+                    .submissions
+                    .get(self.component.selected())
+                    .map(|submission| submission.id);
+                self.component.set_items(items);
+                self.submissions = submissions;
+                if let Some(index) = selected.and_then(|id| {
+                    self.submissions
+                        .iter()
+                        .position(|submission| submission.id == id)
+                }) {
+                    self.component.select(index);
+                }
+                self.reloading = false;
+            } else {
+                self.component.push_items(items);
+                self.submissions.extend(submissions);
+            }
+            self.finished |= finished;
+            self.loading_more = false;
+            self.updating = 0;
         }
+        if let Some(id) = self.tracking {
+            let judging = match self
+                .submissions
+                .iter()
+                .find(|submission| submission.id == id)
+            {
+                Some(submission) => {
+                    matches!(submission.verdict, None | Some(SubmissionVerdict::TESTING))
+                }
+                // The submission may not be listed by the API right after it is made.
+                None => self.tracking_since.elapsed() < TRACK_MISSING_TIMEOUT,
+            };
+            if !judging {
+                self.tracking = None;
+            } else if !self.loading_more && self.last_update.elapsed() >= TRACK_INTERVAL {
+                self.reload();
+            }
+        }
+    }
+
+    /// Keeps reloading the submissions until the submission of `id` is judged.
+    pub fn track(&mut self, id: i32) {
+        self.tracking = Some(id);
+        self.tracking_since = Instant::now();
+        self.reload();
+    }
+
+    pub fn tracking(&self) -> Option<i32> {
+        self.tracking
+    }
+
+    pub fn submissions(&self) -> &[Submission] {
+        &self.submissions
+    }
+
+    /// Whether submissions are being loaded, or loaded again in the background.
+    pub fn is_updating(&self) -> bool {
+        self.updating != 0 || self.reloading
+    }
+
+    pub fn set_page_size(&mut self, page_size: usize) -> &mut Self {
+        self.page_size = page_size.max(1);
+        self
     }
 
     fn send(&mut self, msg: ComponentMsg) -> Result<()> {
@@ -216,15 +367,45 @@ impl SubmissionsList {
     }
 
     pub fn update(&mut self) -> &mut Self {
+        self.component.set_items(Vec::<Vec<Text>>::new());
+        self.component.select(0);
+        self.submissions.clear();
+        self.updating = 1;
+        self.reloading = false;
+        self.load(self.page_size)
+    }
+
+    /// Loads as many submissions as are loaded again, without showing the loading screen.
+    fn reload(&mut self) -> &mut Self {
+        self.reloading = true;
+        self.load(self.submissions.len().max(self.page_size))
+    }
+
+    /// Starts loading submissions from the first one, `count` of them at first.
+    fn load(&mut self, count: usize) -> &mut Self {
+        // Pages still coming from a previous update are dropped along with its channel.
+        self.handler = ChannelHandler::new();
+        self.finished = false;
+        self.last_update = Instant::now();
+
+        let (more_sender, more_receiver) = unbounded_channel();
+        more_sender.send(count);
+        self.more_sender = Some(more_sender);
+        self.loading_more = true;
+
         let update_sender = self.handler.sender.clone();
         let popup_sender = self.sender.clone();
         let error_sender = self.handler.sender.clone();
         let contest_id = self.contest.id;
-        self.updating += 1;
+        let page_size = self.page_size;
 
         tokio::spawn(async move {
-            if let Err(err) = update(update_sender, contest_id).await {
-                error_sender.send(UpdateResult::default());
+            if let Err(err) = update(update_sender, more_receiver, contest_id, page_size).await {
+                error_sender.send(UpdateResult {
+                    finished: true,
+                    failed: true,
+                    ..Default::default()
+                });
                 popup_sender.send(ComponentMsg::EnterNewView(ViewConstructor::ErrorPopup(
                     String::from("Error from Submission"),
                     format!("{err:#}"),
@@ -234,6 +415,18 @@ impl SubmissionsList {
         self
     }
 
+    /// Requests the next page once the selection gets close to the last loaded submission.
+    fn load_more(&mut self) {
+        if self.finished || self.loading_more || !self.component.is_near_end(LOAD_AHEAD) {
+            return;
+        }
+        if let Some(more_sender) = &self.more_sender {
+            if more_sender.send(self.page_size).is_ok() {
+                self.loading_more = true;
+            }
+        }
+    }
+
     fn render_loading(&self, frame: &mut Frame, area: Rect) {
         let loading_message = format!(
             "{}Loading...",
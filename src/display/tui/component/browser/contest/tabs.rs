@@ -1,16 +1,14 @@
 use color_eyre::Result;
 
-use tuirealm::{
-    props::TextSpan,
-    tui::{layout::Rect, style::Color},
-    Frame,
-};
+use tuirealm::{props::TextSpan, tui::layout::Rect, Frame};
 
 use crate::display::tui::{
     base_component::{BaseComponent, Tabs},
     component::ComponentSender,
     event::AppEvent,
+    keymap::Action,
     msg::ComponentMsg,
+    theme::theme,
     utils::{is_left_key, is_right_key},
     Component,
 };
@@ -39,16 +37,21 @@ impl Component for ContestBrowserTabs {
         };
         Ok(())
     }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![Action::PrevTab, Action::NextTab]
+    }
 }
 
 impl ContestBrowserTabs {
     pub fn new(sender: ComponentSender) -> Self {
+        let theme = theme();
         Self {
             sender,
             component: Tabs::new(vec![
-                TextSpan::new("Problems").fg(Color::White),
-                TextSpan::new("Standings").fg(Color::White),
-                TextSpan::new("Submissions").fg(Color::White),
+                TextSpan::new("Problems").fg(theme.text),
+                TextSpan::new("Standings").fg(theme.text),
+                TextSpan::new("Submissions").fg(theme.text),
             ]),
         }
     }
@@ -62,6 +65,10 @@ impl ContestBrowserTabs {
         self.component.index
     }
 
+    pub fn titles(&self) -> Vec<String> {
+        self.component.titles()
+    }
+
     pub fn select(&mut self, index: usize) -> Result<()> {
         self.component.select(index)
     }
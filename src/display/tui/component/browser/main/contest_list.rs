@@ -18,11 +18,12 @@ use tuirealm::{
 };
 
 use crate::{
-    api::{methods::contest_list, objects::Contest},
+    api::{locator::ProblemSource, methods::contest_list, objects::Contest},
     display::tui::{
-        base_component::Table,
+        base_component::{Comparator, Table},
         component::ComponentSender,
         event::AppEvent,
+        keymap::Action,
         msg::{ChannelHandler, ComponentMsg, ViewConstructor},
         utils::{
             is_down_key, is_enter_key, is_refresh_key, is_scroll_down, is_scroll_up, is_up_key,
@@ -47,6 +48,10 @@ pub struct ContestList {
 
 impl Component for ContestList {
     fn on(&mut self, event: &AppEvent) -> Result<()> {
+        if self.component.on(event) {
+            self.send(ComponentMsg::ChangedTo(self.component.selected()))?;
+            return Ok(());
+        }
         match event {
             AppEvent::Key(evt) if is_up_key(evt) => {
                 self.component.prev();
@@ -68,7 +73,7 @@ impl Component for ContestList {
                 self.update();
                 self.send(ComponentMsg::Update)?;
             }
-            AppEvent::Key(evt) if is_enter_key(evt) => {
+            AppEvent::Key(evt) if is_enter_key(evt) && !self.component.is_empty() => {
                 let index = self.component.selected();
                 let contest = self
                     .contests
@@ -77,8 +82,9 @@ impl Component for ContestList {
                         "No such index: {index}.\nCommonly this is a problem of the application."
                     ))?
                     .clone();
+                let source = ProblemSource::from(&contest);
                 self.send(ComponentMsg::EnterNewView(ViewConstructor::ContestBrowser(
-                    contest,
+                    contest, source,
                 )))?;
             }
             _ => (),
@@ -93,8 +99,28 @@ impl Component for ContestList {
             self.render_loading(frame, area);
         }
     }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![
+            Action::Up,
+            Action::Down,
+            Action::Select,
+            Action::Refresh,
+            Action::Filter,
+            Action::NextMatch,
+            Action::PrevMatch,
+            Action::Sort,
+        ]
+    }
+
+    fn is_editing(&self) -> bool {
+        self.component.is_editing()
+    }
 }
 
+/// The column of the lengths of the contests.
+static LENGTH_COLUMN: usize = 2;
+
 lazy_static! {
     pub static ref DEFAULT_HEADER: Vec<String> = vec![
         String::from("Name"),
@@ -106,6 +132,11 @@ lazy_static! {
         Constraint::Min(20),
         Constraint::Percentage(20),
     ];
+    pub static ref DEFAULT_COMPARATORS: Vec<Option<Comparator>> = vec![
+        Some(Comparator::String),
+        Some(Comparator::Date),
+        Some(Comparator::Numeric),
+    ];
 }
 
 async fn get_result() -> Result<Vec<Contest>> {
@@ -146,7 +177,8 @@ async fn update(sender: mpsc::Sender<UpdateResult>) -> Result<()> {
 
 impl ContestList {
     pub fn new(sender: ComponentSender) -> Self {
-        let table = Table::new(DEFAULT_HEADER.clone(), DEFAULT_WIDTHS.clone(), "");
+        let mut table = Table::new(DEFAULT_HEADER.clone(), DEFAULT_WIDTHS.clone(), "");
+        table.set_comparators(DEFAULT_COMPARATORS.clone());
         let handler = ChannelHandler::new();
         Self {
             sender,
@@ -160,12 +192,27 @@ impl ContestList {
     pub fn tick(&mut self) {
         while let Ok(result) = self.handler.try_next() {
             let UpdateResult { items, contests } = result;
-            self.component.set_items(items);
+            // Lengths are shown like `2h 15m`, so they are sorted by their seconds.
+            let lengths = contests
+                .iter()
+                .map(|contest| contest.durationSeconds as f64)
+                .collect();
+            self.component
+                .set_sort_numbers(LENGTH_COLUMN, lengths)
+                .set_items(items);
             self.contests = contests;
             self.updating -= 1;
         }
     }
 
+    pub fn contests(&self) -> &[Contest] {
+        &self.contests
+    }
+
+    pub fn is_updating(&self) -> bool {
+        self.updating != 0
+    }
+
     fn send(&mut self, msg: ComponentMsg) -> Result<()> {
         self.sender.send(msg)?;
         Ok(())
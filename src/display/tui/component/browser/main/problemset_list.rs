@@ -1,8 +1,9 @@
 #![allow(unused_must_use)]
 use color_eyre::{eyre::eyre, Result};
+use crossterm::event::KeyEvent;
 
 use lazy_static::lazy_static;
-use std::sync::mpsc;
+use std::{collections::HashMap, sync::mpsc};
 use tuirealm::{
     props::{Alignment, BorderType},
     tui::{
@@ -13,11 +14,12 @@ use tuirealm::{
 };
 
 use crate::{
-    api::{methods::problemset_problems, objects::Problem, utils::BASEURL},
+    api::{locator::ProblemLocator, methods::problemset_problems, objects::Problem},
     display::tui::{
-        base_component::Table,
+        base_component::{Comparator, Table},
         component::ComponentSender,
         event::AppEvent,
+        keymap::{is_action, Action},
         msg::{ChannelHandler, ComponentMsg, ViewConstructor},
         utils::{
             is_down_key, is_enter_key, is_refresh_key, is_scroll_down, is_scroll_up, is_up_key,
@@ -32,6 +34,10 @@ struct UpdateResult {
     items: Vec<Vec<String>>,
 }
 
+fn is_browser_key(evt: &KeyEvent) -> bool {
+    is_action(evt, Action::Browser)
+}
+
 pub struct ProblemsetList {
     sender: ComponentSender,
     handler: ChannelHandler<UpdateResult>,
@@ -42,6 +48,10 @@ pub struct ProblemsetList {
 
 impl Component for ProblemsetList {
     fn on(&mut self, event: &AppEvent) -> Result<()> {
+        if self.component.on(event) {
+            self.send(ComponentMsg::ChangedTo(self.component.selected()))?;
+            return Ok(());
+        }
         match event {
             AppEvent::Key(evt) if is_up_key(evt) => {
                 self.component.prev();
@@ -63,16 +73,15 @@ impl Component for ProblemsetList {
                 self.update();
                 self.send(ComponentMsg::Update)?;
             }
-            AppEvent::Key(evt) if is_enter_key(evt) => {
-                let index = self.component.selected();
-                let problem = self.problems.get(index).ok_or(eyre!(
-                    "No such index: {index}\nCommonly this is a problem of the application."
-                ))?;
-                let index = problem.index.clone();
-                let url = match problem.contestId {
-                    Some(contest_id) => format!("{BASEURL}problemset/problem/{contest_id}/{index}"),
-                    None => format!("{BASEURL}acmsguru/problem/99999/{index}"),
-                };
+            AppEvent::Key(evt) if is_enter_key(evt) && !self.component.is_empty() => {
+                let problem = self.selected_problem()?.clone();
+                let locator = ProblemLocator::from_problem(&problem);
+                self.send(ComponentMsg::EnterNewView(ViewConstructor::Statement(
+                    locator, problem,
+                )))?;
+            }
+            AppEvent::Key(evt) if is_browser_key(evt) && !self.component.is_empty() => {
+                let url = ProblemLocator::from_problem(self.selected_problem()?).url();
                 webbrowser::open(url.as_str())?;
                 self.send(ComponentMsg::OpenedWebsite(url))?;
             }
@@ -88,42 +97,96 @@ impl Component for ProblemsetList {
             self.render_loading(frame, area);
         }
     }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![
+            Action::Up,
+            Action::Down,
+            Action::Select,
+            Action::Refresh,
+            Action::Browser,
+            Action::Filter,
+            Action::NextMatch,
+            Action::PrevMatch,
+            Action::Sort,
+        ]
+    }
+
+    fn is_editing(&self) -> bool {
+        self.component.is_editing()
+    }
 }
 
 lazy_static! {
     static ref DEFAULT_HEADER: Vec<String> = vec![
         String::from("#"),
         String::from("Name"),
+        String::from("Rating"),
+        String::from("Solved"),
         String::from("Tags"),
     ];
     static ref DEFAULT_WIDTHS: Vec<Constraint> = vec![
         Constraint::Length(6),
-        Constraint::Percentage(50),
-        Constraint::Percentage(50),
+        Constraint::Percentage(45),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Percentage(45),
+    ];
+    static ref DEFAULT_COMPARATORS: Vec<Option<Comparator>> = vec![
+        Some(Comparator::Numeric),
+        Some(Comparator::String),
+        Some(Comparator::Rating),
+        Some(Comparator::Numeric),
+        None,
     ];
 }
 
-async fn get_result() -> Result<Vec<Problem>> {
-    let problems = problemset_problems(None, None).await?.problems;
-    Ok(problems)
+/// Returns the problems with the number of users who solved each.
+async fn get_result() -> Result<Vec<(Problem, Option<i32>)>> {
+    let problemset = problemset_problems(None, None).await?;
+    let solved: HashMap<_, _> = problemset
+        .problemStatistics
+        .into_iter()
+        .map(|statistics| {
+            (
+                (statistics.contestId, statistics.index),
+                statistics.solvedCount,
+            )
+        })
+        .collect();
+    Ok(problemset
+        .problems
+        .into_iter()
+        .map(|problem| {
+            let solved = solved
+                .get(&(problem.contestId, problem.index.clone()))
+                .copied();
+            (problem, solved)
+        })
+        .collect())
 }
 
-fn format_item(problem: Problem) -> Vec<String> {
+fn format_item(problem: Problem, solved: Option<i32>) -> Vec<String> {
     let index = match problem.contestId {
         Some(contest_id) => format!("{}{}", contest_id, problem.index),
         None => format!("{}", problem.index),
     };
     let name = problem.name;
+    let rating = problem
+        .rating
+        .map(|rating| rating.to_string())
+        .unwrap_or_default();
+    let solved = solved.map(|solved| solved.to_string()).unwrap_or_default();
     let tags = problem.tags.join(",");
-    vec![index.to_string(), name, tags]
+    vec![index.to_string(), name, rating, solved, tags]
 }
 
 async fn update(sender: mpsc::Sender<UpdateResult>) -> Result<()> {
     let results = get_result().await?;
-    let problems = results.clone();
+    let problems = results.iter().map(|(problem, _)| problem.clone()).collect();
     let items: Vec<Vec<String>> = results
         .into_iter()
-        .map(|problem| format_item(problem))
+        .map(|(problem, solved)| format_item(problem, solved))
         .collect();
     sender.send(UpdateResult { problems, items });
     Ok(())
@@ -131,7 +194,8 @@ async fn update(sender: mpsc::Sender<UpdateResult>) -> Result<()> {
 
 impl ProblemsetList {
     pub fn new(sender: ComponentSender) -> Self {
-        let table = Table::new(DEFAULT_HEADER.clone(), DEFAULT_WIDTHS.clone(), "");
+        let mut table = Table::new(DEFAULT_HEADER.clone(), DEFAULT_WIDTHS.clone(), "");
+        table.set_comparators(DEFAULT_COMPARATORS.clone());
         let handler = ChannelHandler::new();
         Self {
             sender,
@@ -151,11 +215,26 @@ impl ProblemsetList {
         }
     }
 
+    pub fn problems(&self) -> &[Problem] {
+        &self.problems
+    }
+
+    pub fn is_updating(&self) -> bool {
+        self.updating != 0
+    }
+
     fn send(&mut self, msg: ComponentMsg) -> Result<()> {
         self.sender.send(msg)?;
         Ok(())
     }
 
+    fn selected_problem(&self) -> Result<&Problem> {
+        let index = self.component.selected();
+        self.problems.get(index).ok_or(eyre!(
+            "No such index: {index}\nCommonly this is a problem of the application."
+        ))
+    }
+
     pub fn update(&mut self) -> &mut Self {
         let update_sender = self.handler.sender.clone();
         let popup_sender = self.sender.clone();
@@ -1,16 +1,14 @@
 use color_eyre::Result;
 
-use tuirealm::{
-    props::TextSpan,
-    tui::{layout::Rect, style::Color},
-    Frame,
-};
+use tuirealm::{props::TextSpan, tui::layout::Rect, Frame};
 
 use crate::display::tui::{
     base_component::{BaseComponent, Tabs},
     component::ComponentSender,
     event::AppEvent,
+    keymap::Action,
     msg::ComponentMsg,
+    theme::theme,
     utils::{is_left_key, is_right_key},
     Component,
 };
@@ -39,14 +37,19 @@ impl Component for MainBrowserTabs {
         };
         Ok(())
     }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![Action::PrevTab, Action::NextTab]
+    }
 }
 impl MainBrowserTabs {
     pub fn new(sender: ComponentSender) -> Self {
+        let theme = theme();
         Self {
             sender,
             component: Tabs::new(vec![
-                TextSpan::new("Contest").fg(Color::White),
-                TextSpan::new("ProblemSet").fg(Color::White),
+                TextSpan::new("Contest").fg(theme.text),
+                TextSpan::new("ProblemSet").fg(theme.text),
             ]),
         }
     }
@@ -60,6 +63,10 @@ impl MainBrowserTabs {
         self.component.index
     }
 
+    pub fn titles(&self) -> Vec<String> {
+        self.component.titles()
+    }
+
     pub fn select(&mut self, index: usize) -> Result<()> {
         self.component.select(index)
     }
@@ -1,5 +1,7 @@
 mod contest;
 mod main;
 
+pub use contest::{generate_view, open_problem, parse_view, submit_view, test_view};
 pub use contest::{ContestBrowserTabs, ProblemsList, StandingsList, SubmissionsList};
+pub use contest::{SCRIPT_PLACEHOLDERS, TEMPLATE_PLACEHOLDERS};
 pub use main::{ContestList, MainBrowserTabs, ProblemsetList};
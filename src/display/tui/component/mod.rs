@@ -3,21 +3,37 @@ use color_eyre::Result;
 use std::sync::mpsc;
 use tuirealm::{tui::layout::Rect, Frame};
 
-use super::{event::AppEvent, msg::ComponentMsg};
+use super::{event::AppEvent, keymap::Action, msg::ComponentMsg};
 
 mod browser;
+mod palette;
 mod popup;
+mod settings;
+mod statement;
 pub mod utils;
 
+pub use browser::{generate_view, open_problem, parse_view, submit_view, test_view};
 pub use browser::{ContestBrowserTabs, ProblemsList, StandingsList, SubmissionsList};
 pub use browser::{ContestList, MainBrowserTabs, ProblemsetList};
+pub use browser::{SCRIPT_PLACEHOLDERS, TEMPLATE_PLACEHOLDERS};
+pub use palette::{open_contest, CommandPalette};
 pub use popup::{
     ContentUpdateCmd, HandleSelectionFn, Popup, SelectPopup, UpdatablePopup, UpdateFn,
 };
+pub use settings::{CommandField, Field, SettingsDraft, SettingsForm, TemplateField};
+pub use statement::{format_statement, Statement};
 
 pub trait Component {
     fn on(&mut self, event: &AppEvent) -> Result<()>;
     fn render(&mut self, frame: &mut Frame<'_>, area: Rect);
+    /// The actions the component handles, listed by the help and the command palette.
+    fn actions(&self) -> Vec<Action> {
+        vec![]
+    }
+    /// Whether text is being typed into the component, so keys bound to actions are typed too.
+    fn is_editing(&self) -> bool {
+        false
+    }
 }
 
 pub type ComponentSender = mpsc::Sender<ComponentMsg>;
@@ -0,0 +1,246 @@
+#![allow(unused_must_use)]
+use color_eyre::{eyre::eyre, Result};
+use tuirealm::{
+    tui::layout::{Constraint, Direction, Layout, Rect},
+    Frame,
+};
+
+use crate::{
+    api::{locator::ProblemSource, methods::contest_standings},
+    display::tui::{
+        base_component::{Input, Table},
+        component::ComponentSender,
+        event::AppEvent,
+        fuzzy::{fuzzy_match, highlight},
+        keymap::{keymap, Action},
+        msg::{Command, ComponentMsg, ViewConstructor, ViewMsg},
+        types::{Text, TextSpans},
+        utils::{
+            is_confirm_edit_key, is_edit_down_key, is_edit_up_key, is_scroll_down, is_scroll_up,
+        },
+        view::ViewSender,
+        BaseComponent, Component,
+    },
+};
+
+/// Actions that make no sense to run once, or that open the palette itself.
+static HIDDEN_ACTIONS: &[Action] = &[
+    Action::Up,
+    Action::Down,
+    Action::PageUp,
+    Action::PageDown,
+    Action::Palette,
+];
+
+struct Entry {
+    label: String,
+    keys: String,
+    command: Command,
+}
+
+/// Opens the contest of `source` once its details are fetched.
+pub fn open_contest(sender: ViewSender, source: ProblemSource) {
+    tokio::spawn(async move {
+        let result = async {
+            let contest_id = source
+                .contest_id()
+                .ok_or(eyre!("Cannot find the id of contest {source}"))?;
+            contest_standings(contest_id, None, Some(1), None, None, None).await
+        };
+        match result.await {
+            Ok(standings) => sender.send(ViewMsg::EnterNewView(ViewConstructor::ContestBrowser(
+                standings.contest,
+                source,
+            ))),
+            Err(err) => sender.send(ViewMsg::EnterNewView(ViewConstructor::ErrorPopup(
+                String::from("Error from Contest"),
+                format!("{err:#}"),
+            ))),
+        };
+    });
+}
+
+/// Searches the actions and tabs of a view by what is typed, and runs the one selected.
+///
+/// A contest ID or URL typed is offered to be opened as well.
+pub struct CommandPalette {
+    sender: ComponentSender,
+    input: Input,
+    entries: Vec<Entry>,
+    /// The commands shown, in the order of the table.
+    shown: Vec<Command>,
+    table: Table,
+}
+
+impl Component for CommandPalette {
+    fn on(&mut self, event: &AppEvent) -> Result<()> {
+        match event {
+            AppEvent::Key(evt) if is_edit_up_key(evt) => self.table.prev(),
+            AppEvent::Mouse(evt) if is_scroll_up(evt) => self.table.prev(),
+            AppEvent::Key(evt) if is_edit_down_key(evt) => self.table.next(),
+            AppEvent::Mouse(evt) if is_scroll_down(evt) => self.table.next(),
+            AppEvent::Key(evt) if is_confirm_edit_key(evt) => {
+                if let Some(command) = self.shown.get(self.table.selected()).cloned() {
+                    self.send(ComponentMsg::ExitCurrentView)?;
+                    self.send(ComponentMsg::Run(command))?;
+                }
+            }
+            AppEvent::Key(evt) if self.input.on(evt) => self.filter(),
+            AppEvent::Paste(text) => {
+                self.input.insert(text);
+                self.filter();
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    fn render(&mut self, frame: &mut Frame<'_>, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(0)
+            .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+            .split(area);
+        self.input.render(frame, chunks[0]);
+        self.table.render(frame, chunks[1]);
+    }
+}
+
+impl CommandPalette {
+    pub fn new(sender: ComponentSender, actions: &[Action], tabs: &[String]) -> Self {
+        let keymap = keymap();
+        let mut entries: Vec<Entry> = vec![];
+        for action in actions {
+            let hidden = HIDDEN_ACTIONS.contains(action) || keymap.keys(*action).is_empty();
+            if hidden
+                || entries
+                    .iter()
+                    .any(|entry| entry.command == Command::Action(*action))
+            {
+                continue;
+            }
+            entries.push(Entry {
+                label: action.description().to_string(),
+                keys: keymap.key_names(*action),
+                command: Command::Action(*action),
+            });
+        }
+        for (index, tab) in tabs.iter().enumerate() {
+            entries.push(Entry {
+                label: format!("Switch to the {tab} tab"),
+                keys: String::new(),
+                command: Command::Tab(index),
+            });
+        }
+        let mut palette = Self {
+            sender,
+            input: Input::new(":", ""),
+            entries,
+            shown: vec![],
+            table: Table::new(
+                vec!["Command", "Keys"],
+                vec![Constraint::Percentage(70), Constraint::Percentage(30)],
+                "Type to search, or type a contest ID or URL to open it",
+            ),
+        };
+        palette.filter();
+        palette
+    }
+
+    fn send(&mut self, msg: ComponentMsg) -> Result<()> {
+        self.sender.send(msg)?;
+        Ok(())
+    }
+
+    /// Shows the entries matching the input, the best matches first.
+    fn filter(&mut self) {
+        let query = self.input.value();
+        let query = query.trim();
+        let mut matches: Vec<_> = self
+            .entries
+            .iter()
+            .filter_map(|entry| Some((fuzzy_match(query, &entry.label)?, entry)))
+            .collect();
+        matches.sort_by_key(|(found, _)| -found.score);
+
+        let mut items: Vec<Vec<TextSpans>> = vec![];
+        self.shown.clear();
+        let contest = ProblemSource::parse_contest(query)
+            .ok()
+            .filter(|source| source.contest_id().is_some());
+        if let Some(source) = contest {
+            items.push(vec![
+                TextSpans::from(format!("Open contest {source}")),
+                TextSpans::from(""),
+            ]);
+            self.shown.push(Command::OpenContest(source));
+        }
+        for (found, entry) in matches {
+            items.push(vec![
+                highlight(&entry.label, &found.positions),
+                TextSpans::from(entry.keys.as_str()),
+            ]);
+            self.shown.push(entry.command.clone());
+        }
+        self.table.set_items(
+            items
+                .into_iter()
+                .map(|row| {
+                    row.into_iter()
+                        .map(|spans| Text::from(vec![spans]))
+                        .collect()
+                })
+                .collect::<Vec<Vec<Text>>>(),
+        );
+        self.table.select(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use crate::display::tui::msg::ChannelHandler;
+
+    use super::*;
+
+    #[test]
+    fn palette() {
+        let handler = ChannelHandler::new();
+        let tabs = vec![String::from("Problems"), String::from("Standings")];
+        let mut palette = CommandPalette::new(
+            handler.sender.clone(),
+            &[Action::Up, Action::Submit, Action::Test, Action::Help],
+            &tabs,
+        );
+        assert_eq!(
+            palette.shown,
+            &[
+                Command::Action(Action::Submit),
+                Command::Action(Action::Test),
+                Command::Action(Action::Help),
+                Command::Tab(0),
+                Command::Tab(1),
+            ]
+        );
+
+        for c in "stand".chars() {
+            palette
+                .on(&AppEvent::Key(KeyEvent::new(
+                    KeyCode::Char(c),
+                    KeyModifiers::NONE,
+                )))
+                .unwrap();
+        }
+        assert_eq!(palette.shown[0], Command::Tab(1));
+
+        palette.on(&AppEvent::Paste(String::from("1800"))).unwrap();
+        assert!(palette.shown.is_empty());
+        palette.input = Input::new(":", "1800");
+        palette.filter();
+        assert_eq!(
+            palette.shown,
+            &[Command::OpenContest(ProblemSource::Contest(1800))]
+        );
+    }
+}
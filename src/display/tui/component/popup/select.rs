@@ -26,7 +26,7 @@ impl Component for SelectPopup {
     fn on(&mut self, event: &AppEvent) -> Result<()> {
         match event {
             AppEvent::Key(evt) if is_up_key(evt) => {
-                self.component.next();
+                self.component.prev();
                 self.send(ComponentMsg::ChangedTo(self.component.selected()))?;
             }
             AppEvent::Mouse(evt) if is_scroll_up(evt) => {
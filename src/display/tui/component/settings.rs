@@ -0,0 +1,983 @@
+#![allow(unused_must_use)]
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+    sync::mpsc,
+};
+
+use color_eyre::{
+    eyre::{bail, eyre},
+    Result,
+};
+use crossterm::event::KeyEvent;
+use tuirealm::{
+    props::TextSpan,
+    tui::layout::{Constraint, Direction, Layout, Rect},
+    Frame,
+};
+
+use crate::{
+    credentials::{read_credentials_file, write_credentials_file, CFCredentials},
+    display::tui::{
+        base_component::{Input, Paragraph, Table, Tabs},
+        component::{ComponentSender, HandleSelectionFn},
+        event::AppEvent,
+        keymap::{is_action, keymap, Action},
+        msg::{ChannelHandler, ComponentMsg, ViewConstructor},
+        theme::theme,
+        types::{Text, TextSpans},
+        utils::{
+            is_cancel_edit_key, is_confirm_edit_key, is_down_key, is_enter_key, is_exit_key,
+            is_left_key, is_right_key, is_scroll_down, is_scroll_up, is_up_key,
+        },
+        view::get_chunk_with_ratio,
+        BaseComponent, Component,
+    },
+    doctor::{report, validate, Diagnosis, Severity},
+    settings::{
+        client_needs_restart, get_config_file_path, read_global_config, reload_settings,
+        settings_from_value, write_global_config, CFScripts, CFSettings, CFTemplate,
+    },
+};
+
+fn is_edit_key(evt: &KeyEvent) -> bool {
+    is_enter_key(evt) || is_action(evt, Action::Edit)
+}
+
+fn is_pick_key(evt: &KeyEvent) -> bool {
+    is_action(evt, Action::Pick)
+}
+
+fn is_add_key(evt: &KeyEvent) -> bool {
+    is_action(evt, Action::Add)
+}
+
+fn is_delete_key(evt: &KeyEvent) -> bool {
+    is_action(evt, Action::Delete)
+}
+
+fn is_save_key(evt: &KeyEvent) -> bool {
+    is_action(evt, Action::Save)
+}
+
+static SECTIONS: &[&str] = &["Login", "Templates", "Commands", "Directories"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateField {
+    Alias,
+    Lang,
+    Path,
+    ProgramTypeId,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandField {
+    Ext,
+    BeforeScript,
+    Script,
+    AfterScript,
+    OpenScript,
+}
+
+/// An item of the configuration shown as a row of the form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Field {
+    Username,
+    Key,
+    Secret,
+    Password,
+    KeyCommand,
+    SecretCommand,
+    PasswordCommand,
+    Template(usize, TemplateField),
+    Command(String, CommandField),
+    HomeDir,
+    ConfigDir,
+    TemplatesDir,
+}
+
+impl Field {
+    fn is_secret(&self) -> bool {
+        matches!(self, Field::Key | Field::Secret | Field::Password)
+    }
+
+    fn is_read_only(&self) -> bool {
+        matches!(self, Field::ConfigDir | Field::TemplatesDir)
+    }
+
+    /// Whether the field is a path, chosen with a file picker, and whether it is a directory.
+    fn path_kind(&self) -> Option<bool> {
+        match self {
+            Field::Template(_, TemplateField::Path) => Some(false),
+            Field::HomeDir => Some(true),
+            _ => None,
+        }
+    }
+}
+
+fn templates_dir() -> Result<PathBuf> {
+    Ok(get_config_file_path()?.with_file_name("templates"))
+}
+
+fn non_empty(value: String) -> Option<String> {
+    match value.trim() {
+        "" => None,
+        value => Some(value.to_string()),
+    }
+}
+
+/// The global configuration and the credentials being edited, saved only when they are valid.
+#[derive(Debug, Default, Clone)]
+pub struct SettingsDraft {
+    pub settings: CFSettings,
+    pub credentials: CFCredentials,
+}
+
+impl SettingsDraft {
+    /// Reads `cf.json` and `credentials.json`, without the other sources of settings which are
+    /// not saved here.
+    pub fn load() -> Result<Self> {
+        let settings = settings_from_value(&read_global_config()?)?;
+        let file = read_credentials_file()?;
+        let credentials = CFCredentials {
+            key: file.key.or(settings.key.clone()),
+            secret: file.secret.or(settings.secret.clone()),
+            password: file.password.or(settings.password.clone()),
+        };
+        Ok(Self {
+            settings,
+            credentials,
+        })
+    }
+
+    fn commands(&self) -> Vec<String> {
+        let mut exts: Vec<String> = self
+            .settings
+            .commands
+            .iter()
+            .flatten()
+            .map(|(ext, _)| ext.clone())
+            .collect();
+        exts.sort();
+        exts
+    }
+
+    /// Returns the fields shown in the section numbered `section` of [`SECTIONS`].
+    pub fn fields(&self, section: usize) -> Vec<Field> {
+        match section {
+            0 => vec![
+                Field::Username,
+                Field::Key,
+                Field::Secret,
+                Field::Password,
+                Field::KeyCommand,
+                Field::SecretCommand,
+                Field::PasswordCommand,
+            ],
+            1 => (0..self.settings.templates.as_ref().map_or(0, Vec::len))
+                .flat_map(|index| {
+                    [
+                        TemplateField::Alias,
+                        TemplateField::Lang,
+                        TemplateField::Path,
+                        TemplateField::ProgramTypeId,
+                    ]
+                    .map(|field| Field::Template(index, field))
+                })
+                .collect(),
+            2 => self
+                .commands()
+                .into_iter()
+                .flat_map(|ext| {
+                    [
+                        CommandField::Ext,
+                        CommandField::BeforeScript,
+                        CommandField::Script,
+                        CommandField::AfterScript,
+                        CommandField::OpenScript,
+                    ]
+                    .map(|field| Field::Command(ext.clone(), field))
+                })
+                .collect(),
+            _ => vec![Field::HomeDir, Field::ConfigDir, Field::TemplatesDir],
+        }
+    }
+
+    fn template(&self, index: usize) -> Option<&CFTemplate> {
+        self.settings.templates.as_ref()?.get(index)
+    }
+
+    fn scripts(&self, ext: &str) -> Option<&CFScripts> {
+        self.settings.commands.as_ref()?.get(ext)
+    }
+
+    pub fn label(&self, field: &Field) -> String {
+        match field {
+            Field::Username => String::from("username"),
+            Field::Key => String::from("key"),
+            Field::Secret => String::from("secret"),
+            Field::Password => String::from("password"),
+            Field::KeyCommand => String::from("key_command"),
+            Field::SecretCommand => String::from("secret_command"),
+            Field::PasswordCommand => String::from("password_command"),
+            Field::Template(index, field) => {
+                let alias = self
+                    .template(*index)
+                    .map(|template| template.alias.clone())
+                    .unwrap_or_default();
+                let name = match field {
+                    TemplateField::Alias => "alias",
+                    TemplateField::Lang => "lang",
+                    TemplateField::Path => "path",
+                    TemplateField::ProgramTypeId => "program_type_id",
+                };
+                format!("{alias}: {name}")
+            }
+            Field::Command(ext, field) => {
+                let name = match field {
+                    CommandField::Ext => "extension",
+                    CommandField::BeforeScript => "before_script",
+                    CommandField::Script => "script",
+                    CommandField::AfterScript => "after_script",
+                    CommandField::OpenScript => "open_script",
+                };
+                format!("{ext}: {name}")
+            }
+            Field::HomeDir => String::from("home_dir"),
+            Field::ConfigDir => String::from("config_dir"),
+            Field::TemplatesDir => String::from("templates"),
+        }
+    }
+
+    pub fn value(&self, field: &Field) -> String {
+        let settings = &self.settings;
+        match field {
+            Field::Username => settings.username.clone(),
+            Field::Key => self.credentials.key.clone(),
+            Field::Secret => self.credentials.secret.clone(),
+            Field::Password => self.credentials.password.clone(),
+            Field::KeyCommand => settings.key_command.clone(),
+            Field::SecretCommand => settings.secret_command.clone(),
+            Field::PasswordCommand => settings.password_command.clone(),
+            Field::Template(index, field) => {
+                self.template(*index).and_then(|template| match field {
+                    TemplateField::Alias => Some(template.alias.clone()),
+                    TemplateField::Lang => Some(template.lang.clone()),
+                    TemplateField::Path => Some(template.path.display().to_string()),
+                    TemplateField::ProgramTypeId => {
+                        template.program_type_id.map(|id| id.to_string())
+                    }
+                })
+            }
+            Field::Command(ext, field) => self.scripts(ext).and_then(|scripts| match field {
+                CommandField::Ext => Some(ext.clone()),
+                CommandField::BeforeScript => scripts.before_script.clone(),
+                CommandField::Script => Some(scripts.script.clone()),
+                CommandField::AfterScript => scripts.after_script.clone(),
+                CommandField::OpenScript => scripts.open_script.clone(),
+            }),
+            Field::HomeDir => settings
+                .home_dir
+                .as_ref()
+                .map(|dir| dir.display().to_string()),
+            Field::ConfigDir => get_config_file_path()
+                .ok()
+                .and_then(|path| path.parent().map(|dir| dir.display().to_string())),
+            Field::TemplatesDir => templates_dir().ok().map(|dir| dir.display().to_string()),
+        }
+        .unwrap_or_default()
+    }
+
+    /// Sets `field` to `value`, an empty value unsetting optional fields.
+    pub fn set(&mut self, field: &Field, value: String) -> Result<()> {
+        let settings = &mut self.settings;
+        match field {
+            Field::Username => settings.username = non_empty(value),
+            Field::Key => self.credentials.key = non_empty(value),
+            Field::Secret => self.credentials.secret = non_empty(value),
+            Field::Password => self.credentials.password = non_empty(value),
+            Field::KeyCommand => settings.key_command = non_empty(value),
+            Field::SecretCommand => settings.secret_command = non_empty(value),
+            Field::PasswordCommand => settings.password_command = non_empty(value),
+            Field::Template(index, field) => {
+                let templates = settings.templates.get_or_insert_with(Vec::new);
+                if let TemplateField::Alias = field {
+                    let alias =
+                        non_empty(value.clone()).ok_or(eyre!("The alias cannot be empty."))?;
+                    if templates
+                        .iter()
+                        .enumerate()
+                        .any(|(i, template)| i != *index && template.alias == alias)
+                    {
+                        bail!("Template {alias} already exists.");
+                    }
+                }
+                let template = templates
+                    .get_mut(*index)
+                    .ok_or(eyre!("No template numbered {index}"))?;
+                match field {
+                    TemplateField::Alias => template.alias = value.trim().to_string(),
+                    TemplateField::Lang => template.lang = value.trim().to_string(),
+                    TemplateField::Path => template.path = PathBuf::from(value.trim()),
+                    TemplateField::ProgramTypeId => {
+                        template.program_type_id = match non_empty(value) {
+                            Some(id) => Some(
+                                id.parse()
+                                    .map_err(|_| eyre!("`{id}` is not a language code."))?,
+                            ),
+                            None => None,
+                        }
+                    }
+                }
+            }
+            Field::Command(ext, field) => {
+                let commands = settings.commands.get_or_insert_with(HashMap::new);
+                if let CommandField::Ext = field {
+                    let new_ext = non_empty(value)
+                        .ok_or(eyre!("The extension cannot be empty."))?
+                        .trim_start_matches('.')
+                        .to_string();
+                    if &new_ext != ext {
+                        if commands.contains_key(&new_ext) {
+                            bail!("Commands of {new_ext} already exist.");
+                        }
+                        let scripts = commands
+                            .remove(ext)
+                            .ok_or(eyre!("No commands of {ext} are configured."))?;
+                        commands.insert(new_ext, scripts);
+                    }
+                    return Ok(());
+                }
+                let scripts = commands
+                    .get_mut(ext)
+                    .ok_or(eyre!("No commands of {ext} are configured."))?;
+                match field {
+                    CommandField::Script => scripts.script = value.trim().to_string(),
+                    CommandField::BeforeScript => scripts.before_script = non_empty(value),
+                    CommandField::AfterScript => scripts.after_script = non_empty(value),
+                    CommandField::OpenScript => scripts.open_script = non_empty(value),
+                    CommandField::Ext => unreachable!(),
+                }
+            }
+            Field::HomeDir => {
+                settings.home_dir =
+                    non_empty(value).map(|dir| env::current_dir().unwrap_or_default().join(dir))
+            }
+            Field::ConfigDir | Field::TemplatesDir => {
+                bail!("{} cannot be changed here.", self.label(field))
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds a template or the commands of an extension in `section`, returning the first field
+    /// of it.
+    pub fn add(&mut self, section: usize) -> Option<Field> {
+        match section {
+            1 => {
+                let templates = self.settings.templates.get_or_insert_with(Vec::new);
+                let alias = (1..)
+                    .map(|i| format!("template{i}"))
+                    .find(|alias| templates.iter().all(|template| &template.alias != alias))?;
+                templates.push(CFTemplate {
+                    alias,
+                    lang: String::new(),
+                    path: PathBuf::new(),
+                    program_type_id: None,
+                });
+                Some(Field::Template(templates.len() - 1, TemplateField::Alias))
+            }
+            2 => {
+                let commands = self.settings.commands.get_or_insert_with(HashMap::new);
+                let ext = (1..)
+                    .map(|i| format!("ext{i}"))
+                    .find(|ext| !commands.contains_key(ext))?;
+                commands.insert(ext.clone(), CFScripts::default());
+                Some(Field::Command(ext, CommandField::Ext))
+            }
+            _ => None,
+        }
+    }
+
+    /// Removes the template or the commands `field` belongs to, returning whether there was one.
+    pub fn remove(&mut self, field: &Field) -> bool {
+        match field {
+            Field::Template(index, _) => match &mut self.settings.templates {
+                Some(templates) if *index < templates.len() => {
+                    templates.remove(*index);
+                    true
+                }
+                _ => false,
+            },
+            Field::Command(ext, _) => self
+                .settings
+                .commands
+                .as_mut()
+                .and_then(|commands| commands.remove(ext))
+                .is_some(),
+            _ => false,
+        }
+    }
+
+    /// Checks the draft as `cf-tui doctor` does, without making any request.
+    pub fn validate(&self) -> Vec<Diagnosis> {
+        validate(&self.settings, &self.credentials)
+    }
+
+    /// Saves the settings to `cf.json` and the credentials to `credentials.json`.
+    pub fn save(&self) -> Result<()> {
+        let mut settings = self.settings.clone();
+        settings.key = None;
+        settings.secret = None;
+        settings.password = None;
+        write_global_config(&serde_json::to_value(settings)?)?;
+        if self.credentials != CFCredentials::default()
+            || read_credentials_file()? != CFCredentials::default()
+        {
+            write_credentials_file(&self.credentials)?;
+        }
+        Ok(())
+    }
+}
+
+/// What is chosen in the file picker.
+#[derive(Debug, Clone)]
+enum PickerMsg {
+    Open(PathBuf),
+    Pick(PathBuf),
+}
+
+/// Builds the popup listing `dir`, where a directory is entered and a file is picked.
+fn picker_view(
+    dir: &Path,
+    directory: bool,
+    sender: mpsc::Sender<PickerMsg>,
+) -> Result<ViewConstructor> {
+    let mut entries = vec![];
+    if directory {
+        entries.push((
+            String::from("[Select this directory]"),
+            PickerMsg::Pick(dir.to_path_buf()),
+        ));
+    }
+    if let Some(parent) = dir.parent() {
+        entries.push((String::from("../"), PickerMsg::Open(parent.to_path_buf())));
+    }
+    let mut children: Vec<(bool, String, PathBuf)> = fs::read_dir(dir)?
+        .flatten()
+        .map(|entry| {
+            let path = entry.path();
+            (
+                !path.is_dir(),
+                entry.file_name().to_string_lossy().to_string(),
+                path,
+            )
+        })
+        .filter(|(is_file, _, _)| !directory || !is_file)
+        .collect();
+    children.sort();
+    for (is_file, name, path) in children {
+        entries.push(match is_file {
+            true => (name, PickerMsg::Pick(path)),
+            false => (format!("{name}/"), PickerMsg::Open(path)),
+        });
+    }
+    let items = entries
+        .iter()
+        .map(|(name, _)| vec![Text::from(name.clone())])
+        .collect();
+    let msgs: Vec<PickerMsg> = entries.into_iter().map(|(_, msg)| msg).collect();
+    let handle_selection: HandleSelectionFn = Box::new(move |index| {
+        if let Some(msg) = msgs.get(index) {
+            sender.send(msg.clone())?;
+        }
+        Ok(())
+    });
+    Ok(ViewConstructor::SelectPopup(
+        get_chunk_with_ratio((1, 3, 1), (1, 2, 1)),
+        handle_selection,
+        TextSpans::from(dir.display().to_string()),
+        vec![Text::from("Name")],
+        vec![Constraint::Percentage(100)],
+        items,
+    ))
+}
+
+pub struct SettingsForm {
+    sender: ComponentSender,
+    picker: ChannelHandler<PickerMsg>,
+    draft: SettingsDraft,
+    tabs: Tabs,
+    table: Table,
+    hint: Paragraph,
+    fields: Vec<Field>,
+    editing: Option<Input>,
+    picking: Option<Field>,
+    modified: bool,
+    discarding: bool,
+    status: Option<TextSpans>,
+}
+
+impl Component for SettingsForm {
+    fn on(&mut self, event: &AppEvent) -> Result<()> {
+        while let Ok(msg) = self.picker.try_next() {
+            self.handle_picker_msg(msg)?;
+        }
+        let evt = match event {
+            AppEvent::Key(evt) => evt,
+            AppEvent::Paste(text) => {
+                if let Some(input) = &mut self.editing {
+                    input.insert(text);
+                    self.refresh();
+                }
+                return Ok(());
+            }
+            AppEvent::Mouse(evt) if self.editing.is_none() && is_scroll_up(evt) => {
+                self.table.prev();
+                return Ok(());
+            }
+            AppEvent::Mouse(evt) if self.editing.is_none() && is_scroll_down(evt) => {
+                self.table.next();
+                return Ok(());
+            }
+            _ => return Ok(()),
+        };
+        if self.editing.is_some() {
+            return self.on_editing(evt);
+        }
+        let discarding = std::mem::take(&mut self.discarding);
+        let field = self.fields.get(self.table.selected()).cloned();
+        match evt {
+            evt if is_exit_key(evt) => {
+                if self.modified && !discarding {
+                    self.discarding = true;
+                    self.set_status(
+                        TextSpan::new(format!(
+                            "There are unsaved changes. Press {} again to discard them or {} to save them.",
+                            keymap().key_name(Action::Back),
+                            keymap().key_name(Action::Save)
+                        ))
+                        .fg(theme().warning),
+                    );
+                } else {
+                    self.send(ComponentMsg::ExitCurrentView)?;
+                }
+            }
+            evt if is_up_key(evt) => self.table.prev(),
+            evt if is_down_key(evt) => self.table.next(),
+            evt if is_right_key(evt) => {
+                self.tabs.next();
+                self.select_section(0);
+            }
+            evt if is_left_key(evt) => {
+                self.tabs.prev();
+                self.select_section(0);
+            }
+            evt if is_edit_key(evt) => match field {
+                Some(field) if field.is_read_only() => self.set_status(
+                    TextSpan::new(format!(
+                        "{} cannot be changed here.",
+                        self.draft.label(&field)
+                    ))
+                    .fg(theme().warning),
+                ),
+                Some(field) => {
+                    self.edit(&field);
+                    self.status = None;
+                    self.refresh();
+                }
+                None => (),
+            },
+            evt if is_pick_key(evt) => {
+                if let Some((field, directory)) =
+                    field.and_then(|field| field.path_kind().map(|kind| (field, kind)))
+                {
+                    let dir = self.picker_dir(&field)?;
+                    self.picking = Some(field);
+                    self.send(ComponentMsg::EnterNewView(picker_view(
+                        &dir,
+                        directory,
+                        self.picker.sender.clone(),
+                    )?))?;
+                }
+            }
+            evt if is_add_key(evt) => {
+                if let Some(field) = self.draft.add(self.tabs.selected()) {
+                    self.modified = true;
+                    self.refresh();
+                    self.select_field(&field);
+                    self.edit(&field);
+                    self.refresh();
+                }
+            }
+            evt if is_delete_key(evt) => {
+                if let Some(field) = field {
+                    if self.draft.remove(&field) {
+                        self.modified = true;
+                        self.refresh();
+                    }
+                }
+            }
+            evt if is_save_key(evt) => self.save()?,
+            _ => (),
+        }
+        Ok(())
+    }
+
+    fn render(&mut self, frame: &mut Frame<'_>, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(0)
+            .constraints(
+                [
+                    Constraint::Length(3),
+                    Constraint::Min(0),
+                    Constraint::Length(3),
+                ]
+                .as_ref(),
+            )
+            .split(area);
+        self.tabs.render(frame, chunks[0]);
+        self.table.render(frame, chunks[1]);
+        let hint = match (&self.status, &self.editing) {
+            (Some(status), _) => status.clone(),
+            (None, Some(_)) => TextSpans::from("Enter: confirm  Esc: cancel"),
+            (None, None) => {
+                let keymap = keymap();
+                let actions: &[(Action, &str)] = match self.tabs.selected() {
+                    1 | 2 => &[
+                        (Action::Edit, "edit"),
+                        (Action::Pick, "pick a file"),
+                        (Action::Add, "add"),
+                        (Action::Delete, "delete"),
+                        (Action::Save, "save"),
+                        (Action::Back, "back"),
+                    ],
+                    _ => &[
+                        (Action::Edit, "edit"),
+                        (Action::Pick, "pick a directory"),
+                        (Action::Save, "save"),
+                        (Action::Back, "back"),
+                    ],
+                };
+                TextSpans::from(
+                    actions
+                        .iter()
+                        .map(|(action, hint)| format!("{}: {hint}", keymap.key_name(*action)))
+                        .collect::<Vec<_>>()
+                        .join("  "),
+                )
+            }
+        };
+        self.hint.set_text(Text::from(vec![hint]));
+        self.hint.render(frame, chunks[2]);
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![
+            Action::Up,
+            Action::Down,
+            Action::PrevTab,
+            Action::NextTab,
+            Action::Select,
+            Action::Edit,
+            Action::Pick,
+            Action::Add,
+            Action::Delete,
+            Action::Save,
+            Action::Back,
+        ]
+    }
+
+    fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+}
+
+impl SettingsForm {
+    pub fn new(sender: ComponentSender) -> Result<Self> {
+        let mut form = Self {
+            sender,
+            picker: ChannelHandler::new(),
+            draft: SettingsDraft::load()?,
+            tabs: Tabs::new(
+                SECTIONS
+                    .iter()
+                    .map(|section| TextSpan::new(*section).fg(theme().text))
+                    .collect(),
+            ),
+            table: Table::new(
+                vec!["Setting", "Value"],
+                vec![Constraint::Percentage(35), Constraint::Percentage(65)],
+                "Settings",
+            ),
+            hint: Paragraph::new("", ""),
+            fields: vec![],
+            editing: None,
+            picking: None,
+            modified: false,
+            discarding: false,
+            status: None,
+        };
+        form.select_section(0);
+        Ok(form)
+    }
+
+    fn send(&mut self, msg: ComponentMsg) -> Result<()> {
+        self.sender.send(msg)?;
+        Ok(())
+    }
+
+    fn set_status(&mut self, status: impl Into<TextSpans>) {
+        self.status = Some(status.into());
+    }
+
+    /// Rebuilds the rows of the current section from the draft.
+    fn refresh(&mut self) {
+        self.fields = self.draft.fields(self.tabs.selected());
+        let selected = self.table.selected();
+        let items: Vec<Vec<Text>> = self
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let value = match &self.editing {
+                    Some(input) if index == selected => input.spans(),
+                    _ => match self.draft.value(field) {
+                        value if field.is_secret() && !value.is_empty() => {
+                            TextSpans::from("********")
+                        }
+                        value => TextSpans::from(value),
+                    },
+                };
+                let label = match field.is_read_only() {
+                    true => TextSpans::from(self.draft.label(field)).fg(theme().muted),
+                    false => TextSpans::from(self.draft.label(field)),
+                };
+                vec![Text::from(vec![label]), Text::from(vec![value])]
+            })
+            .collect();
+        self.table.set_items(items);
+        self.table.set_title(format!(
+            "Settings{}",
+            if self.modified { " (modified)" } else { "" }
+        ));
+        if self.fields.is_empty() {
+            self.table.state.select(None);
+        } else {
+            self.table.select(selected.min(self.fields.len() - 1));
+        }
+    }
+
+    pub fn sections(&self) -> Vec<String> {
+        self.tabs.titles()
+    }
+
+    pub fn select_tab(&mut self, index: usize) -> Result<()> {
+        self.tabs.select(index)?;
+        self.select_section(0);
+        Ok(())
+    }
+
+    fn select_section(&mut self, index: usize) {
+        self.editing = None;
+        self.table.select(index);
+        self.refresh();
+    }
+
+    fn select_field(&mut self, field: &Field) {
+        if let Some(index) = self.fields.iter().position(|f| f == field) {
+            self.table.select(index);
+        }
+    }
+
+    fn edit(&mut self, field: &Field) {
+        let mut input = Input::new("", &self.draft.value(field));
+        input.set_secret(field.is_secret());
+        self.editing = Some(input);
+    }
+
+    fn on_editing(&mut self, evt: &KeyEvent) -> Result<()> {
+        if is_cancel_edit_key(evt) {
+            self.editing = None;
+        } else if is_confirm_edit_key(evt) {
+            let value = self
+                .editing
+                .take()
+                .map(|input| input.value())
+                .unwrap_or_default();
+            if let Some(field) = self.fields.get(self.table.selected()).cloned() {
+                self.apply(&field, value);
+            }
+        } else if let Some(input) = &mut self.editing {
+            input.on(evt);
+        }
+        self.refresh();
+        Ok(())
+    }
+
+    /// Sets `field` in the draft, keeping it selected even if it is renamed.
+    fn apply(&mut self, field: &Field, value: String) {
+        let renamed = match field {
+            Field::Command(_, CommandField::Ext) => Some(Field::Command(
+                value.trim().trim_start_matches('.').to_string(),
+                CommandField::Ext,
+            )),
+            _ => None,
+        };
+        match self.draft.set(field, value) {
+            Ok(()) => {
+                self.modified = true;
+                self.refresh();
+                if let Some(field) = renamed {
+                    self.select_field(&field);
+                    self.refresh();
+                }
+            }
+            Err(err) => self.set_status(TextSpan::new(format!("{err:#}")).fg(theme().error)),
+        }
+    }
+
+    /// Returns the directory the file picker starts in for `field`.
+    fn picker_dir(&self, field: &Field) -> Result<PathBuf> {
+        let current = PathBuf::from(self.draft.value(field));
+        let dir = match field {
+            Field::Template(..) => match current.as_os_str().is_empty() {
+                true => templates_dir()?,
+                false => templates_dir()?
+                    .join(current)
+                    .parent()
+                    .map(Path::to_path_buf)
+                    .unwrap_or_default(),
+            },
+            _ => current,
+        };
+        Ok(match dir.is_dir() {
+            true => dir,
+            false => env::current_dir()?,
+        })
+    }
+
+    fn handle_picker_msg(&mut self, msg: PickerMsg) -> Result<()> {
+        let field = match &self.picking {
+            Some(field) => field.clone(),
+            None => return Ok(()),
+        };
+        match msg {
+            PickerMsg::Open(dir) => {
+                let directory = field.path_kind().unwrap_or_default();
+                self.send(ComponentMsg::EnterNewView(picker_view(
+                    &dir,
+                    directory,
+                    self.picker.sender.clone(),
+                )?))?;
+            }
+            PickerMsg::Pick(path) => {
+                self.picking = None;
+                // Templates in `config_dir/templates` are kept relative to it.
+                let path = match field {
+                    Field::Template(..) => match path.strip_prefix(templates_dir()?) {
+                        Ok(relative) => relative.to_path_buf(),
+                        Err(_) => path,
+                    },
+                    _ => path,
+                };
+                self.apply(&field, path.display().to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates the draft, then saves it and reloads the settings of the running app.
+    fn save(&mut self) -> Result<()> {
+        let problems: Vec<Diagnosis> = self
+            .draft
+            .validate()
+            .into_iter()
+            .filter(|diagnosis| diagnosis.severity != Severity::Ok)
+            .collect();
+        if problems
+            .iter()
+            .any(|diagnosis| diagnosis.severity == Severity::Error)
+        {
+            self.send(ComponentMsg::EnterNewView(ViewConstructor::ErrorPopup(
+                String::from("Settings are not saved"),
+                report(&problems, false),
+            )))?;
+            return Ok(());
+        }
+        self.draft.save()?;
+        reload_settings()?;
+        self.modified = false;
+        self.refresh();
+        self.set_status(match problems.len() {
+            0 if client_needs_restart() => {
+                TextSpan::new("Saved. Restart to apply the http settings.").fg(theme().warning)
+            }
+            0 => TextSpan::new("Saved.").fg(theme().accepted),
+            warnings => TextSpan::new(format!(
+                "Saved with {warnings} warning{}. Run `cf-tui doctor` to see them.",
+                if warnings == 1 { "" } else { "s" }
+            ))
+            .fg(theme().warning),
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draft() {
+        let mut draft = SettingsDraft::default();
+        assert_eq!(draft.fields(1), vec![]);
+        let alias = draft.add(1).unwrap();
+        assert_eq!(alias, Field::Template(0, TemplateField::Alias));
+        draft.set(&alias, String::from(" cpp ")).unwrap();
+        draft
+            .set(
+                &Field::Template(0, TemplateField::ProgramTypeId),
+                String::from("54"),
+            )
+            .unwrap();
+        assert!(draft
+            .set(
+                &Field::Template(0, TemplateField::ProgramTypeId),
+                String::from("C++")
+            )
+            .is_err());
+        assert_eq!(draft.label(&alias), "cpp: alias");
+        assert_eq!(draft.fields(1).len(), 4);
+        draft.add(1);
+        assert!(draft
+            .set(
+                &Field::Template(1, TemplateField::Alias),
+                String::from("cpp")
+            )
+            .is_err());
+
+        let ext = draft.add(2).unwrap();
+        draft.set(&ext, String::from(".py")).unwrap();
+        let script = Field::Command(String::from("py"), CommandField::Script);
+        draft
+            .set(&script, String::from("python3 <% full %>"))
+            .unwrap();
+        assert_eq!(draft.value(&script), "python3 <% full %>");
+        assert_eq!(
+            draft.fields(2)[0],
+            Field::Command(String::from("py"), CommandField::Ext)
+        );
+
+        draft.set(&Field::Secret, String::from(" ")).unwrap();
+        assert_eq!(draft.credentials.secret, None);
+        assert!(draft.remove(&script));
+        assert!(draft.remove(&alias));
+        assert_eq!(
+            draft.settings.templates.as_ref().unwrap()[0].alias,
+            "template1"
+        );
+    }
+}
@@ -0,0 +1,378 @@
+#![allow(unused_must_use)]
+use std::sync::mpsc;
+
+use color_eyre::{eyre::eyre, Result};
+use crossterm::event::KeyEvent;
+use tuirealm::{
+    props::{Alignment, BorderType, TextSpan},
+    tui::{
+        layout::Rect,
+        widgets::{Block, Borders, Paragraph as TuiParagraph},
+    },
+    Frame,
+};
+
+use crate::{
+    api::{
+        locator::ProblemLocator,
+        objects::Problem,
+        parse::{parse_statement, ProblemStatement, TestCase},
+    },
+    display::tui::{
+        base_component::Paragraph,
+        component::{
+            generate_view, open_problem, parse_view, submit_view, test_view, ComponentSender,
+        },
+        event::AppEvent,
+        keymap::{is_action, Action},
+        latex::render_latex,
+        msg::{ChannelHandler, ComponentMsg, ViewConstructor},
+        theme::theme,
+        types::{Text, TextSpans},
+        utils::{is_down_key, is_scroll_down, is_scroll_up, is_up_key},
+        BaseComponent, Component,
+    },
+};
+
+fn is_generate_key(evt: &KeyEvent) -> bool {
+    is_action(evt, Action::Generate)
+}
+
+fn is_test_key(evt: &KeyEvent) -> bool {
+    is_action(evt, Action::Test)
+}
+
+fn is_parse_key(evt: &KeyEvent) -> bool {
+    is_action(evt, Action::Parse)
+}
+
+fn is_submit_key(evt: &KeyEvent) -> bool {
+    is_action(evt, Action::Submit)
+}
+
+fn is_open_key(evt: &KeyEvent) -> bool {
+    is_action(evt, Action::Open)
+}
+
+fn is_browser_key(evt: &KeyEvent) -> bool {
+    is_action(evt, Action::Browser)
+}
+
+fn is_page_up_key(evt: &KeyEvent) -> bool {
+    is_action(evt, Action::PageUp)
+}
+
+fn is_page_down_key(evt: &KeyEvent) -> bool {
+    is_action(evt, Action::PageDown)
+}
+
+pub struct Statement {
+    sender: ComponentSender,
+    handler: ChannelHandler<Option<ProblemStatement>>,
+    locator: ProblemLocator,
+    problem: Problem,
+    component: Paragraph,
+    updating: u32,
+    statement: Option<ProblemStatement>,
+    text: Text,
+    max_scroll: u16,
+    page_height: u16,
+}
+
+async fn update(
+    sender: mpsc::Sender<Option<ProblemStatement>>,
+    locator: ProblemLocator,
+) -> Result<()> {
+    let statement = parse_statement(locator.url()).await?;
+    sender.send(Some(statement))?;
+    Ok(())
+}
+
+fn heading(title: &str) -> Text {
+    Text::from(vec![
+        TextSpans::from(""),
+        TextSpans::from(TextSpan::new(title).fg(theme().title).bold()),
+    ])
+}
+
+/// Renders a section, replacing formulas, emphasis and images with their terminal forms.
+fn section(content: &str) -> Text {
+    let content = render_latex(content)
+        .replace("**", "")
+        .replace('`', "")
+        .replace("![](", "[image: ");
+    let lines: Vec<TextSpans> = content
+        .split('\n')
+        .map(|line| {
+            if line.starts_with("[image: ") {
+                TextSpan::new(line.replacen(')', "]", 1))
+                    .fg(theme().accent)
+                    .into()
+            } else {
+                TextSpans::from(line)
+            }
+        })
+        .collect();
+    Text::from(lines)
+}
+
+/// Draws `content` in a box titled `title`, highlighting every other test of `groups`.
+fn sample_box(title: &str, content: &str, groups: &[usize]) -> Text {
+    let theme = theme();
+    let content = content.trim_end();
+    let width = content
+        .lines()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(title.chars().count() + 2);
+    let mut lines = vec![TextSpans::from(
+        TextSpan::new(format!(
+            "┌─ {title} {}┐",
+            "─".repeat(width - title.chars().count() - 1)
+        ))
+        .fg(theme.frame),
+    )];
+    for (i, line) in content.lines().enumerate() {
+        let line = TextSpan::new(format!("{line:width$}"));
+        let line = match groups.get(i) {
+            Some(group) if group % 2 == 1 => line.fg(theme.accent),
+            _ => line,
+        };
+        lines.push(TextSpans::from(vec![
+            TextSpan::new("│ ").fg(theme.frame),
+            line,
+            TextSpan::new(" │").fg(theme.frame),
+        ]));
+    }
+    lines.push(
+        TextSpan::new(format!("└{}┘", "─".repeat(width + 2)))
+            .fg(theme.frame)
+            .into(),
+    );
+    Text::from(lines)
+}
+
+fn samples(samples: &[TestCase]) -> Text {
+    let texts: Vec<Text> = samples
+        .iter()
+        .enumerate()
+        .map(|(i, sample)| {
+            let id = i + 1;
+            Text::from(vec![
+                sample_box(&format!("Input #{id}"), &sample.input, &sample.input_groups),
+                sample_box(&format!("Output #{id}"), &sample.answer, &[]),
+            ])
+        })
+        .collect();
+    Text::from(texts)
+}
+
+/// Lays out the whole statement as text.
+pub fn format_statement(statement: &ProblemStatement) -> Text {
+    let mut texts = vec![
+        Text::from(TextSpan::new(&statement.title).fg(theme().title).bold()),
+        Text::from(format!(
+            "time limit per test: {}    memory limit per test: {}",
+            statement.time_limit, statement.memory_limit
+        )),
+        Text::from(format!(
+            "input: {}    output: {}",
+            statement.input_file, statement.output_file
+        )),
+        Text::from(""),
+        section(&statement.legend),
+        heading("Input"),
+        section(&statement.input_specification),
+        heading("Output"),
+        section(&statement.output_specification),
+    ];
+    if let Some(interaction) = &statement.interaction {
+        texts.push(heading("Interaction"));
+        texts.push(section(interaction));
+    }
+    if !statement.samples.is_empty() {
+        texts.push(heading("Examples"));
+        texts.push(samples(&statement.samples));
+    }
+    if let Some(note) = &statement.note {
+        texts.push(heading("Note"));
+        texts.push(section(note));
+    }
+    Text::from(texts)
+}
+
+impl Component for Statement {
+    fn on(&mut self, event: &AppEvent) -> Result<()> {
+        match event {
+            AppEvent::Key(evt) if is_up_key(evt) => self.scroll_up(1),
+            AppEvent::Mouse(evt) if is_scroll_up(evt) => self.scroll_up(1),
+            AppEvent::Key(evt) if is_down_key(evt) => self.scroll_down(1),
+            AppEvent::Mouse(evt) if is_scroll_down(evt) => self.scroll_down(1),
+            AppEvent::Key(evt) if is_page_up_key(evt) => self.scroll_up(self.page_height),
+            AppEvent::Key(evt) if is_page_down_key(evt) => self.scroll_down(self.page_height),
+            AppEvent::Key(evt) if is_parse_key(evt) => {
+                let view = parse_view(&self.locator, &self.problem)?;
+                self.send(ComponentMsg::EnterNewView(view))?;
+            }
+            AppEvent::Key(evt) if is_generate_key(evt) => {
+                let view = generate_view(&self.locator)?;
+                self.send(ComponentMsg::EnterNewView(view))?;
+            }
+            AppEvent::Key(evt) if is_test_key(evt) => {
+                let view = test_view(&self.locator)?;
+                self.send(ComponentMsg::EnterNewView(view))?;
+            }
+            AppEvent::Key(evt) if is_open_key(evt) => {
+                open_problem(self.sender.clone(), &self.locator)?
+            }
+            AppEvent::Key(evt) if is_submit_key(evt) => {
+                let view = submit_view(&self.locator, self.sender.clone())?;
+                self.send(ComponentMsg::EnterNewView(view))?;
+            }
+            AppEvent::Key(evt) if is_browser_key(evt) => self.open_in_browser()?,
+            _ => (),
+        }
+        Ok(())
+    }
+
+    fn render(&mut self, frame: &mut Frame<'_>, area: Rect) {
+        if self.updating == 0 {
+            let inner_width = usize::from(area.width.saturating_sub(2)).max(1);
+            let inner_height = area.height.saturating_sub(2);
+            // Lines are wrapped, so the height is counted in rows rather than lines.
+            let rows: usize = self
+                .text
+                .lines
+                .iter()
+                .map(|line| line.to_string().chars().count().max(1))
+                .map(|width| width.div_ceil(inner_width))
+                .sum();
+            self.page_height = inner_height.max(1);
+            self.max_scroll = u16::try_from(rows)
+                .unwrap_or(u16::MAX)
+                .saturating_sub(inner_height);
+            self.component.scroll = self.component.scroll.min(self.max_scroll);
+            self.component.render(frame, area);
+        } else {
+            self.render_loading(frame, area);
+        }
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![
+            Action::Up,
+            Action::Down,
+            Action::PageUp,
+            Action::PageDown,
+            Action::Browser,
+            Action::Parse,
+            Action::Generate,
+            Action::Test,
+            Action::Open,
+            Action::Submit,
+        ]
+    }
+}
+
+impl Statement {
+    pub fn new(sender: ComponentSender, locator: ProblemLocator, problem: Problem) -> Self {
+        let title = TextSpans::from(format!(
+            "{locator} - {} [p]arse [g]enerate [t]est [o]pen [s]ubmit [b]rowser",
+            problem.name
+        ));
+        Self {
+            sender,
+            handler: ChannelHandler::new(),
+            locator,
+            problem,
+            component: Paragraph::new(title, ""),
+            updating: 0,
+            statement: None,
+            text: Text::default(),
+            max_scroll: 0,
+            page_height: 1,
+        }
+    }
+
+    pub fn statement(&self) -> Option<&ProblemStatement> {
+        self.statement.as_ref()
+    }
+
+    pub fn text(&self) -> &Text {
+        &self.text
+    }
+
+    pub fn is_updating(&self) -> bool {
+        self.updating != 0
+    }
+
+    fn send(&mut self, msg: ComponentMsg) -> Result<()> {
+        self.sender.send(msg)?;
+        Ok(())
+    }
+
+    pub fn tick(&mut self) {
+        while let Ok(statement) = self.handler.try_next() {
+            if let Some(statement) = statement {
+                self.text = format_statement(&statement);
+                self.component.set_text(self.text.clone());
+                self.component.scroll = 0;
+                self.statement = Some(statement);
+            }
+            self.updating -= 1;
+        }
+    }
+
+    pub fn update(&mut self) -> &mut Self {
+        self.updating += 1;
+        let update_sender = self.handler.sender.clone();
+        let popup_sender = self.sender.clone();
+        let error_sender = self.handler.sender.clone();
+        let locator = self.locator.clone();
+        tokio::spawn(async move {
+            if let Err(err) = update(update_sender, locator).await {
+                error_sender.send(None);
+                popup_sender.send(ComponentMsg::EnterNewView(ViewConstructor::ErrorPopup(
+                    String::from("Error from Statement"),
+                    format!("{err:#}"),
+                )));
+            }
+        });
+        self
+    }
+
+    fn scroll_up(&mut self, rows: u16) {
+        self.component.scroll = self.component.scroll.saturating_sub(rows);
+    }
+
+    fn scroll_down(&mut self, rows: u16) {
+        self.component.scroll = self
+            .component
+            .scroll
+            .saturating_add(rows)
+            .min(self.max_scroll);
+    }
+
+    fn open_in_browser(&mut self) -> Result<()> {
+        let url = self.locator.url();
+        webbrowser::open(&url).map_err(|err| eyre!("Failed to open {url}: {err}"))?;
+        self.send(ComponentMsg::OpenedWebsite(url))?;
+        Ok(())
+    }
+
+    fn render_loading(&self, frame: &mut Frame, area: Rect) {
+        let loading_message = format!(
+            "{}Loading...",
+            (0..(area.height - 1) / 2).map(|_| "\n").collect::<String>()
+        );
+        let loading = TuiParagraph::new(loading_message)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded),
+            )
+            .alignment(Alignment::Center);
+        frame.render_widget(loading, area);
+    }
+}
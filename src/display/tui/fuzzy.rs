@@ -0,0 +1,111 @@
+//! Fuzzy matching of what is typed against names, like `sbmt` against `Submit the code`.
+
+use super::types::TextSpans;
+
+/// Where the characters of a query are found in a text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// How well the text matches, higher for matches at the start of words and in a row.
+    pub score: i64,
+    /// The indices of the characters of the text matched.
+    pub positions: Vec<usize>,
+}
+
+/// Finds the characters of `query` in `text` in order, ignoring case, or `None` when some
+/// character is missing. An empty query matches everything.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<FuzzyMatch> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    let text: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = text
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let first = match query.first() {
+        Some(first) => *first,
+        None => {
+            return Some(FuzzyMatch {
+                score: 0,
+                positions: vec![],
+            })
+        }
+    };
+    // Every occurrence of the first character is tried, as the earliest one is not always the
+    // best, e.g. `test` in `latest test`.
+    (0..lower.len())
+        .filter(|&start| lower[start] == first)
+        .filter_map(|start| {
+            let mut positions = vec![start];
+            let mut next = start + 1;
+            for c in &query[1..] {
+                let found = (next..lower.len()).find(|&i| lower[i] == *c)?;
+                positions.push(found);
+                next = found + 1;
+            }
+            Some(FuzzyMatch {
+                score: score(&text, &positions),
+                positions,
+            })
+        })
+        .max_by_key(|found| (found.score, -(found.positions[0] as i64)))
+}
+
+fn score(text: &[char], positions: &[usize]) -> i64 {
+    let mut score = 0;
+    for (i, &position) in positions.iter().enumerate() {
+        score += 1;
+        let word_start = match position.checked_sub(1).map(|before| text[before]) {
+            None => true,
+            Some(before) => {
+                !before.is_alphanumeric()
+                    || (before.is_lowercase() && text[position].is_uppercase())
+            }
+        };
+        if word_start {
+            score += 8;
+        }
+        match i.checked_sub(1).map(|before| positions[before]) {
+            Some(before) if before + 1 == position => score += 5,
+            Some(before) => score -= (position - before - 1).min(5) as i64,
+            None => score -= position.min(5) as i64,
+        }
+    }
+    score
+}
+
+/// Shows `text` with the characters at `positions` highlighted.
+pub fn highlight(text: &str, positions: &[usize]) -> TextSpans {
+    TextSpans::from(text).highlight(positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches() {
+        assert_eq!(
+            fuzzy_match("", "Submit").unwrap().positions,
+            Vec::<usize>::new()
+        );
+        assert_eq!(
+            fuzzy_match("sbmt", "Submit the code").unwrap().positions,
+            vec![0, 2, 3, 5]
+        );
+        assert!(fuzzy_match("tset", "Test").is_none());
+        assert_eq!(
+            fuzzy_match("test", "latest test").unwrap().positions,
+            vec![7, 8, 9, 10]
+        );
+        assert_eq!(
+            fuzzy_match("pa", "Parse the samples of all problems")
+                .unwrap()
+                .positions,
+            vec![0, 1]
+        );
+
+        let score = |query, text| fuzzy_match(query, text).unwrap().score;
+        assert!(score("sub", "Submit the code") > score("sub", "Go to the Submissions tab of it"));
+        assert!(score("st", "Switch to Standings") < score("st", "Standings"));
+        assert_eq!(highlight("Test", &[0, 1]).to_string(), "Test");
+    }
+}
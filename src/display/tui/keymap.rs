@@ -0,0 +1,629 @@
+//! Keys of the TUI, mapping named actions to key chords.
+//!
+//! The keys start from a preset and are changed in `keybindings` of the configuration:
+//!
+//! ```json
+//! "keybindings": {
+//!     "preset": "emacs",
+//!     "keys": { "submit": ["s", "ctrl-s"], "help": ["?", "F1"] }
+//! }
+//! ```
+
+use std::{
+    fmt::Display,
+    str::FromStr,
+    sync::{Arc, RwLock},
+};
+
+use color_eyre::{
+    eyre::{bail, eyre},
+    Result,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use lazy_static::lazy_static;
+
+use crate::settings::{CFKeybindings, CFSettings, SETTINGS};
+
+/// Where an action is available. Actions of the same scope, or of [`Scope::Global`] and any
+/// other scope, cannot share keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    Global,
+    Problems,
+    Settings,
+}
+
+impl Scope {
+    pub fn title(&self) -> &'static str {
+        match self {
+            Scope::Global => "Everywhere",
+            Scope::Problems => "Contests, problems and statements",
+            Scope::Settings => "Settings",
+        }
+    }
+
+    fn overlaps(&self, other: &Scope) -> bool {
+        self == other || *self == Scope::Global || *other == Scope::Global
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Up,
+    Down,
+    PrevTab,
+    NextTab,
+    Select,
+    Back,
+    Quit,
+    Refresh,
+    Help,
+    Palette,
+    PageUp,
+    PageDown,
+    Browser,
+    Parse,
+    ParseAll,
+    Generate,
+    Test,
+    Open,
+    Submit,
+    Settings,
+    Edit,
+    Pick,
+    Add,
+    Delete,
+    Save,
+    Filter,
+    NextMatch,
+    PrevMatch,
+    Sort,
+}
+
+impl Action {
+    pub const ALL: &'static [Action] = &[
+        Action::Up,
+        Action::Down,
+        Action::PrevTab,
+        Action::NextTab,
+        Action::Select,
+        Action::Back,
+        Action::Quit,
+        Action::Refresh,
+        Action::Help,
+        Action::Palette,
+        Action::PageUp,
+        Action::PageDown,
+        Action::Browser,
+        Action::Parse,
+        Action::ParseAll,
+        Action::Generate,
+        Action::Test,
+        Action::Open,
+        Action::Submit,
+        Action::Settings,
+        Action::Edit,
+        Action::Pick,
+        Action::Add,
+        Action::Delete,
+        Action::Save,
+        Action::Filter,
+        Action::NextMatch,
+        Action::PrevMatch,
+        Action::Sort,
+    ];
+
+    /// The name of the action in `keybindings`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::PrevTab => "prev_tab",
+            Action::NextTab => "next_tab",
+            Action::Select => "select",
+            Action::Back => "back",
+            Action::Quit => "quit",
+            Action::Refresh => "refresh",
+            Action::Help => "help",
+            Action::Palette => "palette",
+            Action::PageUp => "page_up",
+            Action::PageDown => "page_down",
+            Action::Browser => "browser",
+            Action::Parse => "parse",
+            Action::ParseAll => "parse_all",
+            Action::Generate => "generate",
+            Action::Test => "test",
+            Action::Open => "open",
+            Action::Submit => "submit",
+            Action::Settings => "settings",
+            Action::Edit => "edit",
+            Action::Pick => "pick",
+            Action::Add => "add",
+            Action::Delete => "delete",
+            Action::Save => "save",
+            Action::Filter => "filter",
+            Action::NextMatch => "next_match",
+            Action::PrevMatch => "prev_match",
+            Action::Sort => "sort",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Up => "Move up",
+            Action::Down => "Move down",
+            Action::PrevTab => "Go to the previous tab",
+            Action::NextTab => "Go to the next tab",
+            Action::Select => "Enter the contest or problem selected",
+            Action::Back => "Exit the current view",
+            Action::Quit => "Quit cf-tui",
+            Action::Refresh => "Refresh the list",
+            Action::Help => "Show the keys",
+            Action::Palette => "Search and run a command",
+            Action::PageUp => "Scroll up by a page",
+            Action::PageDown => "Scroll down by a page",
+            Action::Browser => "Open the problem in the web browser",
+            Action::Parse => "Parse the samples of the problem",
+            Action::ParseAll => "Parse the samples of all problems",
+            Action::Generate => "Generate code from a template",
+            Action::Test => "Test the code against the samples",
+            Action::Open => "Open the code with open_script",
+            Action::Submit => "Submit the code",
+            Action::Settings => "Open the settings",
+            Action::Edit => "Edit the setting selected",
+            Action::Pick => "Pick a file or directory",
+            Action::Add => "Add a template or commands",
+            Action::Delete => "Delete the template or commands selected",
+            Action::Save => "Save the settings",
+            Action::Filter => "Filter the rows by what is typed",
+            Action::NextMatch => "Go to the next row matching the filter",
+            Action::PrevMatch => "Go to the previous row matching the filter",
+            Action::Sort => "Sort by the next column or reverse the order",
+        }
+    }
+
+    pub fn scope(&self) -> Scope {
+        match self {
+            Action::Up
+            | Action::Down
+            | Action::PrevTab
+            | Action::NextTab
+            | Action::Select
+            | Action::Back
+            | Action::Quit
+            | Action::Refresh
+            | Action::Help
+            | Action::Palette => Scope::Global,
+            Action::Edit | Action::Pick | Action::Add | Action::Delete | Action::Save => {
+                Scope::Settings
+            }
+            _ => Scope::Problems,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::ALL
+            .iter()
+            .find(|action| action.name() == name)
+            .copied()
+    }
+}
+
+/// A key pressed with modifiers, written like `k`, `P`, `ctrl-s`, `shift-tab` or `F5`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+
+    /// Returns whether `evt` is this chord. Shift is part of the character typed, and terminals
+    /// report Shift + Tab either as `BackTab` or as `Tab` with Shift.
+    pub fn matches(&self, evt: &KeyEvent) -> bool {
+        match (self.code, evt.code) {
+            (KeyCode::Char(a), KeyCode::Char(b)) => {
+                a == b
+                    && self.modifiers - KeyModifiers::SHIFT == evt.modifiers - KeyModifiers::SHIFT
+            }
+            (KeyCode::BackTab, KeyCode::BackTab) => {
+                self.modifiers == evt.modifiers - KeyModifiers::SHIFT
+            }
+            (KeyCode::BackTab, KeyCode::Tab) => {
+                evt.modifiers.contains(KeyModifiers::SHIFT)
+                    && self.modifiers == evt.modifiers - KeyModifiers::SHIFT
+            }
+            (a, b) => a == b && self.modifiers == evt.modifiers,
+        }
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = color_eyre::Report;
+
+    fn from_str(text: &str) -> Result<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = text.trim();
+        // `-` alone, or at the end as in `ctrl--`, is the key itself.
+        while let Some((modifier, key)) = rest.split_once('-').filter(|(_, key)| !key.is_empty()) {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "c" => KeyModifiers::CONTROL,
+                "alt" | "m" => KeyModifiers::ALT,
+                "shift" | "s" => KeyModifiers::SHIFT,
+                _ => bail!("Unknown modifier `{modifier}` in `{text}`"),
+            };
+            rest = key;
+        }
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_lowercase().as_str() {
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "tab" if modifiers.contains(KeyModifiers::SHIFT) => {
+                    modifiers -= KeyModifiers::SHIFT;
+                    KeyCode::BackTab
+                }
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "space" => KeyCode::Char(' '),
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                key => match key.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                    _ => bail!("Unknown key `{text}`"),
+                },
+            },
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl Display for KeyChord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "shift-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::BackTab => write!(f, "shift-Tab"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            code => write!(f, "{code:?}"),
+        }
+    }
+}
+
+fn chords(keys: &[&str]) -> Vec<KeyChord> {
+    keys.iter()
+        .map(|key| key.parse().expect("Keys of presets are valid"))
+        .collect()
+}
+
+/// The names of the presets, the first one being the default.
+pub static PRESETS: &[&str] = &["vim", "emacs"];
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<KeyChord>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::preset(PRESETS[0]).expect("The default preset exists")
+    }
+}
+
+impl Keymap {
+    pub fn preset(name: &str) -> Result<Self> {
+        let emacs = match name {
+            "vim" => false,
+            "emacs" => true,
+            _ => bail!(
+                "Unknown preset `{name}`, expected one of {}",
+                PRESETS.join(", ")
+            ),
+        };
+        let bindings = Action::ALL
+            .iter()
+            .map(|action| {
+                let keys: &[&str] = match (action, emacs) {
+                    (Action::Up, false) => &["k", "Up"],
+                    (Action::Up, true) => &["ctrl-p", "Up"],
+                    (Action::Down, false) => &["j", "Down"],
+                    (Action::Down, true) => &["ctrl-n", "Down"],
+                    (Action::PrevTab, false) => &["h", "Left", "shift-Tab"],
+                    (Action::PrevTab, true) => &["ctrl-b", "Left", "shift-Tab"],
+                    (Action::NextTab, false) => &["l", "Right", "Tab"],
+                    (Action::NextTab, true) => &["ctrl-f", "Right", "Tab"],
+                    (Action::Select, _) => &["Enter"],
+                    (Action::Back, false) => &["q", "Esc"],
+                    (Action::Back, true) => &["q", "Esc", "ctrl-g"],
+                    (Action::Quit, _) => &["ctrl-c"],
+                    (Action::Refresh, _) => &["F5"],
+                    (Action::Help, _) => &["?"],
+                    (Action::Palette, false) => &[":"],
+                    (Action::Palette, true) => &[":", "alt-x"],
+                    (Action::PageUp, false) => &["PageUp", "ctrl-u"],
+                    (Action::PageUp, true) => &["PageUp", "alt-v"],
+                    (Action::PageDown, false) => &["PageDown", "Space", "ctrl-d"],
+                    (Action::PageDown, true) => &["PageDown", "Space", "ctrl-v"],
+                    (Action::Browser, _) => &["b"],
+                    (Action::Parse, _) => &["p"],
+                    (Action::ParseAll, _) => &["P"],
+                    (Action::Generate, _) => &["g"],
+                    (Action::Test, _) => &["t"],
+                    (Action::Open, _) => &["o"],
+                    (Action::Submit, _) => &["s"],
+                    (Action::Settings, _) => &["c"],
+                    (Action::Edit, _) => &["e"],
+                    (Action::Pick, _) => &["f"],
+                    (Action::Add, _) => &["a"],
+                    (Action::Delete, _) => &["d"],
+                    (Action::Save, _) => &["w", "ctrl-s"],
+                    (Action::Filter, _) => &["/"],
+                    (Action::NextMatch, _) => &["n"],
+                    (Action::PrevMatch, _) => &["N"],
+                    (Action::Sort, _) => &["S"],
+                };
+                (*action, chords(keys))
+            })
+            .collect();
+        Ok(Self { bindings })
+    }
+
+    /// Builds the keys configured in `keybindings`, failing on unknown actions or keys and on
+    /// keys bound to more than one action.
+    pub fn new(config: Option<&CFKeybindings>) -> Result<Self> {
+        let mut keymap = Self::preset(
+            config
+                .and_then(|config| config.preset.as_deref())
+                .unwrap_or(PRESETS[0]),
+        )?;
+        let mut keys: Vec<_> = config
+            .and_then(|config| config.keys.as_ref())
+            .into_iter()
+            .flatten()
+            .collect();
+        keys.sort();
+        for (name, keys) in keys {
+            let action = Action::from_name(name).ok_or(eyre!(
+                "Unknown action `{name}`, expected one of {}",
+                Action::ALL
+                    .iter()
+                    .map(|action| action.name())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))?;
+            let keys = keys
+                .iter()
+                .map(|key| key.parse())
+                .collect::<Result<Vec<KeyChord>>>()?;
+            if let Some((_, chords)) = keymap.bindings.iter_mut().find(|(a, _)| *a == action) {
+                *chords = keys;
+            }
+        }
+        let conflicts = keymap.conflicts();
+        if !conflicts.is_empty() {
+            bail!("Conflicting keys:\n{}", conflicts.join("\n"));
+        }
+        Ok(keymap)
+    }
+
+    /// Returns every key bound to two actions available at the same time.
+    pub fn conflicts(&self) -> Vec<String> {
+        let mut conflicts = vec![];
+        for (i, (action, keys)) in self.bindings.iter().enumerate() {
+            for (other, other_keys) in &self.bindings[i + 1..] {
+                if !action.scope().overlaps(&other.scope()) {
+                    continue;
+                }
+                for key in keys.iter().filter(|key| other_keys.contains(key)) {
+                    conflicts.push(format!(
+                        "`{key}` is bound to both {} and {}",
+                        action.name(),
+                        other.name()
+                    ));
+                }
+            }
+        }
+        conflicts
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyChord] {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, keys)| keys.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Returns the first key of `action`, to show in hints.
+    pub fn key_name(&self, action: Action) -> String {
+        match self.keys(action).first() {
+            Some(key) => key.to_string(),
+            None => String::from("(none)"),
+        }
+    }
+
+    pub fn is(&self, action: Action, evt: &KeyEvent) -> bool {
+        self.keys(action).iter().any(|key| key.matches(evt))
+    }
+
+    /// Returns the keys of `action` joined by commas.
+    pub fn key_names(&self, action: Action) -> String {
+        match self.keys(action) {
+            [] => String::from("(none)"),
+            keys => keys
+                .iter()
+                .map(|key| key.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        }
+    }
+
+    /// Lists the keys of `actions`, by scope.
+    pub fn help(&self, actions: &[Action]) -> String {
+        let mut lines = vec![];
+        for scope in [Scope::Global, Scope::Problems, Scope::Settings] {
+            let bindings: Vec<_> = self
+                .bindings
+                .iter()
+                .filter(|(a, _)| a.scope() == scope && actions.contains(a))
+                .collect();
+            if bindings.is_empty() {
+                continue;
+            }
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines.push(format!("{}:", scope.title()));
+            for (action, _) in bindings {
+                let keys = self.key_names(*action);
+                lines.push(format!("  {keys:<24} {}", action.description()));
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+lazy_static! {
+    static ref KEYMAP: RwLock<Option<(Arc<CFSettings>, Arc<Keymap>)>> = RwLock::new(None);
+}
+
+/// Returns the keys of the current settings, built again whenever they are reloaded.
+///
+/// Invalid `keybindings` are reported in the log and the default keys are used instead.
+pub fn keymap() -> Arc<Keymap> {
+    let settings = SETTINGS.get();
+    if let Ok(cache) = KEYMAP.read() {
+        if let Some((cached, keymap)) = cache.as_ref() {
+            if Arc::ptr_eq(cached, &settings) {
+                return keymap.clone();
+            }
+        }
+    }
+    let keymap = Arc::new(
+        Keymap::new(settings.keybindings.as_ref()).unwrap_or_else(|err| {
+            tracing::error!("Using the default keys: {err:#}");
+            Keymap::default()
+        }),
+    );
+    if let Ok(mut cache) = KEYMAP.write() {
+        *cache = Some((settings, keymap.clone()));
+    }
+    keymap
+}
+
+/// Returns whether `evt` is one of the keys of `action`.
+pub fn is_action(evt: &KeyEvent, action: Action) -> bool {
+    keymap().is(action, evt)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn chords() {
+        let chord: KeyChord = "ctrl-s".parse().unwrap();
+        assert_eq!(
+            chord,
+            KeyChord::new(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        );
+        assert!(chord.matches(&KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL)));
+        assert!(!chord.matches(&KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE)));
+
+        let chord: KeyChord = "P".parse().unwrap();
+        assert!(chord.matches(&KeyEvent::new(KeyCode::Char('P'), KeyModifiers::SHIFT)));
+        assert!(chord.matches(&KeyEvent::new(KeyCode::Char('P'), KeyModifiers::NONE)));
+
+        let chord: KeyChord = "shift-Tab".parse().unwrap();
+        assert_eq!(chord.to_string(), "shift-Tab");
+        assert!(chord.matches(&KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT)));
+        assert!(chord.matches(&KeyEvent::new(KeyCode::Tab, KeyModifiers::SHIFT)));
+        assert!(!chord.matches(&KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE)));
+
+        assert_eq!("ctrl--".parse::<KeyChord>().unwrap().to_string(), "ctrl--");
+        assert_eq!("F5".parse::<KeyChord>().unwrap().to_string(), "F5");
+        assert_eq!("space".parse::<KeyChord>().unwrap().to_string(), "Space");
+        assert!("hyper-k".parse::<KeyChord>().is_err());
+        assert!("F13".parse::<KeyChord>().is_err());
+    }
+
+    #[test]
+    fn keymaps() {
+        for preset in PRESETS {
+            assert_eq!(
+                Keymap::preset(preset).unwrap().conflicts(),
+                Vec::<String>::new()
+            );
+        }
+        let keymap = Keymap::default();
+        assert!(keymap.is(
+            Action::NextTab,
+            &KeyEvent::new(KeyCode::Char('l'), KeyModifiers::NONE)
+        ));
+        assert!(keymap.is(
+            Action::PrevTab,
+            &KeyEvent::new(KeyCode::Char('h'), KeyModifiers::NONE)
+        ));
+
+        let config = |preset: &str, keys: &[(&str, &[&str])]| CFKeybindings {
+            preset: Some(preset.to_string()),
+            keys: Some(
+                keys.iter()
+                    .map(|(action, keys)| {
+                        (
+                            action.to_string(),
+                            keys.iter().map(|key| key.to_string()).collect(),
+                        )
+                    })
+                    .collect::<HashMap<_, _>>(),
+            ),
+        };
+        let keymap = Keymap::new(Some(&config("emacs", &[("submit", &["ctrl-x"])]))).unwrap();
+        assert!(keymap.is(
+            Action::Up,
+            &KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        ));
+        assert!(keymap.is(
+            Action::Submit,
+            &KeyEvent::new(KeyCode::Char('x'), KeyModifiers::CONTROL)
+        ));
+        assert!(!keymap.is(
+            Action::Submit,
+            &KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE)
+        ));
+
+        // Settings and problems are never shown together, so they can share keys.
+        assert!(Keymap::new(Some(&config("vim", &[("save", &["s"])]))).is_ok());
+        let err = Keymap::new(Some(&config("vim", &[("submit", &["q"])]))).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Conflicting keys:\n`q` is bound to both back and submit"
+        );
+        assert!(Keymap::new(Some(&config("vi", &[]))).is_err());
+        assert!(Keymap::new(Some(&config("vim", &[("jump", &["J"])]))).is_err());
+    }
+}
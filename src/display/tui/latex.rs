@@ -0,0 +1,389 @@
+//! A light LaTeX renderer for showing formulas of problem statements in the terminal.
+//!
+//! Only what commonly appears in statements is handled: Greek letters, relations and
+//! operators are replaced with their Unicode symbols, sub/superscripts use Unicode
+//! sub/superscript characters when possible, and unknown commands are kept as they are.
+
+static SYMBOLS: &[(&str, &str)] = &[
+    ("alpha", "α"),
+    ("beta", "β"),
+    ("gamma", "γ"),
+    ("delta", "δ"),
+    ("epsilon", "ϵ"),
+    ("varepsilon", "ε"),
+    ("zeta", "ζ"),
+    ("eta", "η"),
+    ("theta", "θ"),
+    ("iota", "ι"),
+    ("kappa", "κ"),
+    ("lambda", "λ"),
+    ("mu", "μ"),
+    ("nu", "ν"),
+    ("xi", "ξ"),
+    ("pi", "π"),
+    ("rho", "ρ"),
+    ("sigma", "σ"),
+    ("tau", "τ"),
+    ("upsilon", "υ"),
+    ("phi", "ϕ"),
+    ("varphi", "φ"),
+    ("chi", "χ"),
+    ("psi", "ψ"),
+    ("omega", "ω"),
+    ("Gamma", "Γ"),
+    ("Delta", "Δ"),
+    ("Theta", "Θ"),
+    ("Lambda", "Λ"),
+    ("Xi", "Ξ"),
+    ("Pi", "Π"),
+    ("Sigma", "Σ"),
+    ("Phi", "Φ"),
+    ("Psi", "Ψ"),
+    ("Omega", "Ω"),
+    ("le", "≤"),
+    ("leq", "≤"),
+    ("ge", "≥"),
+    ("geq", "≥"),
+    ("ne", "≠"),
+    ("neq", "≠"),
+    ("lt", "<"),
+    ("gt", ">"),
+    ("approx", "≈"),
+    ("equiv", "≡"),
+    ("cdot", "·"),
+    ("times", "×"),
+    ("div", "÷"),
+    ("pm", "±"),
+    ("dots", "…"),
+    ("ldots", "…"),
+    ("cdots", "⋯"),
+    ("to", "→"),
+    ("rightarrow", "→"),
+    ("leftarrow", "←"),
+    ("Rightarrow", "⇒"),
+    ("Leftrightarrow", "⇔"),
+    ("infty", "∞"),
+    ("sum", "∑"),
+    ("prod", "∏"),
+    ("in", "∈"),
+    ("notin", "∉"),
+    ("subset", "⊂"),
+    ("subseteq", "⊆"),
+    ("cup", "∪"),
+    ("cap", "∩"),
+    ("forall", "∀"),
+    ("exists", "∃"),
+    ("land", "∧"),
+    ("lor", "∨"),
+    ("oplus", "⊕"),
+    ("lfloor", "⌊"),
+    ("rfloor", "⌋"),
+    ("lceil", "⌈"),
+    ("rceil", "⌉"),
+    ("mid", "|"),
+    ("vert", "|"),
+    ("bmod", "mod"),
+    ("mod", "mod"),
+    ("quad", "  "),
+    ("qquad", "    "),
+];
+
+/// Commands whose argument is shown as it is.
+static TEXT_COMMANDS: &[&str] = &[
+    "text",
+    "textbf",
+    "textit",
+    "texttt",
+    "mathrm",
+    "mathbf",
+    "mathit",
+    "mathtt",
+    "operatorname",
+];
+
+/// Commands that are dropped, like sizing of delimiters.
+static IGNORED_COMMANDS: &[&str] = &["left", "right", "displaystyle", "limits"];
+
+static SUPERSCRIPTS: &[(char, char)] = &[
+    ('0', '⁰'),
+    ('1', '¹'),
+    ('2', '²'),
+    ('3', '³'),
+    ('4', '⁴'),
+    ('5', '⁵'),
+    ('6', '⁶'),
+    ('7', '⁷'),
+    ('8', '⁸'),
+    ('9', '⁹'),
+    ('+', '⁺'),
+    ('-', '⁻'),
+    ('=', '⁼'),
+    ('(', '⁽'),
+    (')', '⁾'),
+    ('a', 'ᵃ'),
+    ('b', 'ᵇ'),
+    ('c', 'ᶜ'),
+    ('d', 'ᵈ'),
+    ('e', 'ᵉ'),
+    ('f', 'ᶠ'),
+    ('g', 'ᵍ'),
+    ('h', 'ʰ'),
+    ('i', 'ⁱ'),
+    ('j', 'ʲ'),
+    ('k', 'ᵏ'),
+    ('l', 'ˡ'),
+    ('m', 'ᵐ'),
+    ('n', 'ⁿ'),
+    ('o', 'ᵒ'),
+    ('p', 'ᵖ'),
+    ('r', 'ʳ'),
+    ('s', 'ˢ'),
+    ('t', 'ᵗ'),
+    ('u', 'ᵘ'),
+    ('v', 'ᵛ'),
+    ('w', 'ʷ'),
+    ('x', 'ˣ'),
+    ('y', 'ʸ'),
+    ('z', 'ᶻ'),
+];
+
+static SUBSCRIPTS: &[(char, char)] = &[
+    ('0', '₀'),
+    ('1', '₁'),
+    ('2', '₂'),
+    ('3', '₃'),
+    ('4', '₄'),
+    ('5', '₅'),
+    ('6', '₆'),
+    ('7', '₇'),
+    ('8', '₈'),
+    ('9', '₉'),
+    ('+', '₊'),
+    ('-', '₋'),
+    ('=', '₌'),
+    ('(', '₍'),
+    (')', '₎'),
+    ('a', 'ₐ'),
+    ('e', 'ₑ'),
+    ('h', 'ₕ'),
+    ('i', 'ᵢ'),
+    ('j', 'ⱼ'),
+    ('k', 'ₖ'),
+    ('l', 'ₗ'),
+    ('m', 'ₘ'),
+    ('n', 'ₙ'),
+    ('o', 'ₒ'),
+    ('p', 'ₚ'),
+    ('r', 'ᵣ'),
+    ('s', 'ₛ'),
+    ('t', 'ₜ'),
+    ('u', 'ᵤ'),
+    ('v', 'ᵥ'),
+    ('x', 'ₓ'),
+];
+
+/// Renders the formulas between `$` or `$$` in `text`, leaving the rest untouched.
+pub fn render_latex(text: &str) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('$') {
+        let delimiter = if rest[start..].starts_with("$$") {
+            "$$"
+        } else {
+            "$"
+        };
+        let formula_start = start + delimiter.len();
+        let formula_end = match rest[formula_start..].find(delimiter) {
+            Some(end) => formula_start + end,
+            None => break,
+        };
+        result.push_str(&rest[..start]);
+        result.push_str(&render_formula(&rest[formula_start..formula_end]));
+        rest = &rest[formula_end + delimiter.len()..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Renders a formula without its `$` delimiters.
+pub fn render_formula(formula: &str) -> String {
+    let chars: Vec<char> = formula.chars().collect();
+    let mut result = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => {
+                let (name, next) = read_command(&chars, i);
+                i = next;
+                render_command(&name, &chars, &mut i, &mut result);
+            }
+            operator @ ('^' | '_') => {
+                let (argument, next) = read_argument(&chars, i + 1);
+                i = next;
+                let table = if operator == '^' {
+                    SUPERSCRIPTS
+                } else {
+                    SUBSCRIPTS
+                };
+                result.push_str(&script(operator, &render_formula(&argument), table));
+            }
+            '{' => {
+                let (group, next) = read_group(&chars, i);
+                i = next;
+                result.push_str(&render_formula(&group));
+            }
+            '}' => i += 1,
+            '~' => {
+                result.push(' ');
+                i += 1;
+            }
+            c => {
+                result.push(c);
+                i += 1;
+            }
+        }
+    }
+    result
+}
+
+fn render_command(name: &str, chars: &[char], i: &mut usize, result: &mut String) {
+    if let Some((_, symbol)) = SYMBOLS.iter().find(|(command, _)| *command == name) {
+        result.push_str(symbol);
+    } else if TEXT_COMMANDS.contains(&name) {
+        let (argument, next) = read_argument(chars, *i);
+        *i = next;
+        result.push_str(&argument);
+    } else if IGNORED_COMMANDS.contains(&name) {
+    } else if name == "frac" {
+        let (numerator, next) = read_argument(chars, *i);
+        let (denominator, next) = read_argument(chars, next);
+        *i = next;
+        result.push_str(&format!(
+            "{}/{}",
+            parenthesize(&render_formula(&numerator)),
+            parenthesize(&render_formula(&denominator))
+        ));
+    } else if name == "sqrt" {
+        let (argument, next) = read_argument(chars, *i);
+        *i = next;
+        result.push_str(&format!("√{}", parenthesize(&render_formula(&argument))));
+    } else if name.chars().all(|c| c.is_ascii_alphabetic()) {
+        result.push('\\');
+        result.push_str(name);
+    } else {
+        // Escaped characters and spacing like `\{` or `\,`.
+        match name {
+            "," | ";" | ":" | " " => result.push(' '),
+            "!" => {}
+            "\\" => result.push('\n'),
+            _ => result.push_str(name),
+        }
+    }
+}
+
+/// Reads the command starting at the backslash at `start`, returning its name and the position
+/// after it.
+fn read_command(chars: &[char], start: usize) -> (String, usize) {
+    let mut end = start + 1;
+    while end < chars.len() && chars[end].is_ascii_alphabetic() {
+        end += 1;
+    }
+    if end == start + 1 && end < chars.len() {
+        end += 1;
+    }
+    (chars[start + 1..end].iter().collect(), end)
+}
+
+/// Reads the group starting at the brace at `start`, returning its content and the position
+/// after the closing brace.
+fn read_group(chars: &[char], start: usize) -> (String, usize) {
+    let mut depth = 0;
+    for (i, c) in chars.iter().enumerate().skip(start) {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return (chars[start + 1..i].iter().collect(), i + 1);
+                }
+            }
+            _ => (),
+        }
+    }
+    (chars[start + 1..].iter().collect(), chars.len())
+}
+
+/// Reads the argument of a command or script at `start`: a group, a command or a character.
+fn read_argument(chars: &[char], start: usize) -> (String, usize) {
+    let mut start = start;
+    while start < chars.len() && chars[start] == ' ' {
+        start += 1;
+    }
+    match chars.get(start) {
+        Some('{') => read_group(chars, start),
+        Some('\\') => {
+            let (_, end) = read_command(chars, start);
+            (chars[start..end].iter().collect(), end)
+        }
+        Some(c) => (c.to_string(), start + 1),
+        None => (String::new(), start),
+    }
+}
+
+fn script(operator: char, argument: &str, table: &[(char, char)]) -> String {
+    let scripted: Option<String> = argument
+        .chars()
+        .map(|c| table.iter().find(|(from, _)| *from == c).map(|(_, to)| *to))
+        .collect();
+    match scripted {
+        Some(scripted) if !scripted.is_empty() => scripted,
+        _ => format!("{operator}{}", parenthesize(argument)),
+    }
+}
+
+fn parenthesize(text: &str) -> String {
+    if text.chars().count() <= 1 || text.chars().all(|c| c.is_alphanumeric()) {
+        text.to_string()
+    } else {
+        format!("({text})")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symbols() {
+        assert_eq!(render_latex("$1 \\le n \\le 10^5$"), "1 ≤ n ≤ 10⁵");
+        assert_eq!(render_latex("$\\alpha + \\Beta$"), "α + \\Beta");
+        assert_eq!(render_latex("$a \\ne b$, $\\{1, 2\\}$"), "a ≠ b, {1, 2}");
+    }
+
+    #[test]
+    fn scripts() {
+        assert_eq!(render_latex("$a_i$"), "aᵢ");
+        assert_eq!(render_latex("$a_{i+1}$"), "aᵢ₊₁");
+        assert_eq!(render_latex("$10^{9}$"), "10⁹");
+        assert_eq!(render_latex("$2^{2n}$"), "2²ⁿ");
+        assert_eq!(render_latex("$a_{bq}$"), "a_bq");
+        assert_eq!(render_latex("$2^{\\alpha}$"), "2^α");
+        assert_eq!(render_latex("$x_{i,j}$"), "x_(i,j)");
+    }
+
+    #[test]
+    fn commands_with_arguments() {
+        assert_eq!(render_latex("$\\frac{n}{2}$"), "n/2");
+        assert_eq!(render_latex("$\\frac{n+1}{2}$"), "(n+1)/2");
+        assert_eq!(render_latex("$\\sqrt{n}$"), "√n");
+        assert_eq!(render_latex("$\\text{YES}$"), "YES");
+        assert_eq!(render_latex("$\\left(a\\right)$"), "(a)");
+    }
+
+    #[test]
+    fn text_outside_formulas() {
+        assert_eq!(render_latex("costs $5"), "costs $5");
+        assert_eq!(render_latex("$$x^2$$ and $y$"), "x² and y");
+        assert_eq!(render_latex("no formulas"), "no formulas");
+    }
+}
@@ -3,7 +3,11 @@ pub mod base_component;
 pub mod component;
 pub mod error;
 pub mod event;
+pub mod fuzzy;
+pub mod keymap;
+pub mod latex;
 pub mod msg;
+pub mod theme;
 pub mod types;
 pub mod utils;
 pub mod view;
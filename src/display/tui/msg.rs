@@ -1,17 +1,20 @@
 use std::sync::mpsc::{self, RecvError, TryRecvError};
 
-use tuirealm::{
-    props::{Color, TextSpan},
-    tui::layout::Constraint,
-};
+use tuirealm::{props::TextSpan, tui::layout::Constraint};
 
-use crate::api::objects::Contest;
+use crate::api::{
+    locator::{ProblemLocator, ProblemSource},
+    objects::{Contest, Problem},
+};
 
 use super::{
     component::{HandleSelectionFn, UpdateFn},
+    keymap::{keymap, Action},
+    theme::theme,
     types::{Text, TextSpans},
     view::{
-        ContestBrowser, GetChunkFn, MainBrowser, PopupView, SelectPopupView, UpdatablePopupView,
+        ContestBrowser, GetChunkFn, MainBrowser, PaletteView, PopupView, SelectPopupView,
+        SettingsView, StatementView, UpdatablePopupView,
     },
     View,
 };
@@ -23,6 +26,8 @@ pub enum ComponentMsg {
     ExitCurrentView,
     ChangeToTab(usize),
     OpenedWebsite(String),
+    Submitted(i32),
+    Run(Command),
     Locked,
     Update,
     None,
@@ -30,7 +35,13 @@ pub enum ComponentMsg {
 
 pub enum ViewConstructor {
     MainBrowser,
-    ContestBrowser(Contest),
+    ContestBrowser(Contest, ProblemSource),
+    Statement(ProblemLocator, Problem),
+    Settings,
+    /// The keys of the actions given.
+    Help(Vec<Action>),
+    /// The command palette for the actions and the tabs given.
+    Palette(Vec<Action>, Vec<String>),
     ErrorPopup(String, String),
     UpdatablePopup(GetChunkFn, UpdateFn, TextSpans, Text),
     SelectPopup(
@@ -48,12 +59,31 @@ impl ViewConstructor {
         let sender = sender.clone();
         match self {
             ViewConstructor::MainBrowser => Box::new(MainBrowser::new(sender)),
-            ViewConstructor::ContestBrowser(contest) => {
-                Box::new(ContestBrowser::new(sender, contest))
+            ViewConstructor::ContestBrowser(contest, source) => {
+                Box::new(ContestBrowser::new(sender, contest, source))
+            }
+            ViewConstructor::Statement(locator, problem) => {
+                Box::new(StatementView::new(sender, locator, problem))
+            }
+            ViewConstructor::Settings => match SettingsView::new(sender.clone()) {
+                Ok(view) => Box::new(view),
+                Err(err) => Box::new(PopupView::new(
+                    sender,
+                    TextSpan::new("Error from Settings").fg(theme().error),
+                    format!("{err:#}"),
+                )),
+            },
+            ViewConstructor::Help(actions) => Box::new(PopupView::new(
+                sender,
+                TextSpan::new("Keys").fg(theme().accent),
+                keymap().help(&actions),
+            )),
+            ViewConstructor::Palette(actions, tabs) => {
+                Box::new(PaletteView::new(sender, &actions, &tabs))
             }
             ViewConstructor::ErrorPopup(title, text) => Box::new(PopupView::new(
                 sender,
-                TextSpan::new(title).fg(Color::Red),
+                TextSpan::new(title).fg(theme().error),
                 text,
             )),
             ViewConstructor::UpdatablePopup(get_chunk, update, title, text) => Box::new(
@@ -79,10 +109,21 @@ impl ViewConstructor {
     }
 }
 
+/// What the command palette runs on the view below it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Performs the action as if one of its keys was pressed.
+    Action(Action),
+    /// Switches to the tab numbered so.
+    Tab(usize),
+    OpenContest(ProblemSource),
+}
+
 pub enum ViewMsg {
     AppClose,
     EnterNewView(ViewConstructor),
     ExitCurrentView,
+    Run(Command),
     None,
 }
 
@@ -0,0 +1,358 @@
+//! Colors of the TUI, named by what they mean rather than how they look.
+//!
+//! The colors start from a preset and are changed in `theme` of the configuration, which may
+//! also pick one of the user themes in `themes`:
+//!
+//! ```json
+//! "theme": { "preset": "solarized", "colors": { "header": "#268bd2" } },
+//! "themes": {
+//!     "solarized": { "preset": "light", "colors": { "accepted": "#859900", "rejected": "#dc322f" } }
+//! }
+//! ```
+//!
+//! Every color is dropped when the `NO_COLOR` environment variable is set.
+
+use std::{
+    env,
+    sync::{Arc, RwLock},
+};
+
+use color_eyre::{
+    eyre::{bail, eyre},
+    Result,
+};
+use lazy_static::lazy_static;
+use tuirealm::{
+    props::{Color, Style},
+    tui::style::Modifier,
+    utils::parser::parse_color,
+};
+
+use crate::settings::{CFSettings, CFTheme, SETTINGS};
+
+/// The built-in themes, the first one being the default.
+pub const PRESETS: [&str; 4] = ["dark", "light", "high-contrast", "monochrome"];
+
+/// The colors of a theme. [`Color::Reset`] is the default color of the terminal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    /// Plain text drawn over the terminal background, e.g. names of tabs.
+    pub text: Color,
+    /// Text of little importance, e.g. unrated problems and settings that cannot be changed.
+    pub muted: Color,
+    /// Borders of tabs, pictures and popups listing keys.
+    pub accent: Color,
+    /// Titles and headings of statements.
+    pub title: Color,
+    /// Boxes around samples.
+    pub frame: Color,
+    /// Background of table headers, which are reversed when it is the default color.
+    pub header: Color,
+    pub header_text: Color,
+    /// Background of the selected row or tab, which is reversed when it is the default color.
+    pub highlight: Color,
+    pub highlight_text: Color,
+    /// Accepted submissions, passed tests and finished work.
+    pub accepted: Color,
+    /// Rejected submissions and failed tests.
+    pub rejected: Color,
+    /// Submissions being judged and tests running out of time.
+    pub pending: Color,
+    pub warning: Color,
+    pub error: Color,
+    /// Whether the colors of ranks are dropped as well.
+    pub monochrome: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::preset(PRESETS[0]).expect("The default preset exists")
+    }
+}
+
+impl Theme {
+    /// The names of the colors as written in the configuration.
+    pub const COLORS: [&'static str; 14] = [
+        "text",
+        "muted",
+        "accent",
+        "title",
+        "frame",
+        "header",
+        "header_text",
+        "highlight",
+        "highlight_text",
+        "accepted",
+        "rejected",
+        "pending",
+        "warning",
+        "error",
+    ];
+
+    pub fn preset(name: &str) -> Result<Self> {
+        Ok(match name {
+            "dark" => Self {
+                text: Color::White,
+                muted: Color::Gray,
+                accent: Color::Cyan,
+                title: Color::Yellow,
+                frame: Color::Blue,
+                header: Color::Magenta,
+                header_text: Color::Black,
+                highlight: Color::Reset,
+                highlight_text: Color::Reset,
+                accepted: Color::Green,
+                rejected: Color::Red,
+                pending: Color::Blue,
+                warning: Color::Yellow,
+                error: Color::Red,
+                monochrome: false,
+            },
+            "light" => Self {
+                text: Color::Black,
+                muted: Color::DarkGray,
+                accent: Color::Blue,
+                title: Color::Magenta,
+                header: Color::Cyan,
+                warning: Color::Magenta,
+                ..Self::preset("dark")?
+            },
+            // Blue and magenta instead of green and red, which are told apart by most
+            // colorblind people, on a black background.
+            "high-contrast" => Self {
+                text: Color::White,
+                muted: Color::White,
+                accent: Color::LightCyan,
+                title: Color::LightYellow,
+                frame: Color::White,
+                header: Color::White,
+                header_text: Color::Black,
+                highlight: Color::LightYellow,
+                highlight_text: Color::Black,
+                accepted: Color::LightBlue,
+                rejected: Color::LightMagenta,
+                pending: Color::White,
+                warning: Color::LightYellow,
+                error: Color::LightMagenta,
+                monochrome: false,
+            },
+            "monochrome" => Self {
+                text: Color::Reset,
+                muted: Color::Reset,
+                accent: Color::Reset,
+                title: Color::Reset,
+                frame: Color::Reset,
+                header: Color::Reset,
+                header_text: Color::Reset,
+                highlight: Color::Reset,
+                highlight_text: Color::Reset,
+                accepted: Color::Reset,
+                rejected: Color::Reset,
+                pending: Color::Reset,
+                warning: Color::Reset,
+                error: Color::Reset,
+                monochrome: true,
+            },
+            _ => bail!(
+                "Unknown preset `{name}`, expected one of {}",
+                PRESETS.join(", ")
+            ),
+        })
+    }
+
+    /// Builds the theme configured in `theme` and `themes`, failing on unknown themes and
+    /// colors and on invalid color values.
+    pub fn new(settings: &CFSettings) -> Result<Self> {
+        let config = settings.theme.clone().unwrap_or_default();
+        let name = config.preset.as_deref().unwrap_or(PRESETS[0]);
+        let mut theme = match settings.themes.as_ref().and_then(|themes| themes.get(name)) {
+            Some(user) if !PRESETS.contains(&name) => {
+                let preset = user.preset.as_deref().unwrap_or(PRESETS[0]);
+                let mut theme = Self::preset(preset)
+                    .map_err(|err| eyre!("Theme `{name}` is based on an unknown theme: {err}"))?;
+                theme.apply(user)?;
+                theme
+            }
+            _ => Self::preset(name).map_err(|_| {
+                let mut users: Vec<_> = settings
+                    .themes
+                    .iter()
+                    .flatten()
+                    .map(|(name, _)| name.as_str())
+                    .collect();
+                users.sort();
+                let names: Vec<_> = PRESETS.into_iter().chain(users).collect();
+                eyre!(
+                    "Unknown theme `{name}`, expected one of {}",
+                    names.join(", ")
+                )
+            })?,
+        };
+        theme.apply(&config)?;
+        Ok(theme)
+    }
+
+    /// Replaces the colors listed in `colors` of `config`.
+    fn apply(&mut self, config: &CFTheme) -> Result<()> {
+        let mut colors: Vec<_> = config.colors.iter().flatten().collect();
+        colors.sort();
+        for (name, value) in colors {
+            let color = parse_color(value).ok_or(eyre!(
+                "Invalid color `{value}` of `{name}`, expected a name like `red`, `#rrggbb` or `default`"
+            ))?;
+            *self.color_mut(name).ok_or(eyre!(
+                "Unknown color `{name}`, expected one of {}",
+                Self::COLORS.join(", ")
+            ))? = color;
+        }
+        Ok(())
+    }
+
+    fn color_mut(&mut self, name: &str) -> Option<&mut Color> {
+        Some(match name {
+            "text" => &mut self.text,
+            "muted" => &mut self.muted,
+            "accent" => &mut self.accent,
+            "title" => &mut self.title,
+            "frame" => &mut self.frame,
+            "header" => &mut self.header,
+            "header_text" => &mut self.header_text,
+            "highlight" => &mut self.highlight,
+            "highlight_text" => &mut self.highlight_text,
+            "accepted" => &mut self.accepted,
+            "rejected" => &mut self.rejected,
+            "pending" => &mut self.pending,
+            "warning" => &mut self.warning,
+            "error" => &mut self.error,
+            _ => return None,
+        })
+    }
+
+    pub fn header_style(&self) -> Style {
+        match self.header {
+            Color::Reset => Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD),
+            header => Style::default().fg(self.header_text).bg(header),
+        }
+    }
+
+    pub fn highlight_style(&self) -> Style {
+        match self.highlight {
+            Color::Reset => Style::default().add_modifier(Modifier::REVERSED),
+            highlight => Style::default().fg(self.highlight_text).bg(highlight),
+        }
+    }
+
+    /// The color of a rank on Codeforces, which is dropped in monochrome mode.
+    pub fn rank(&self, color: Color) -> Color {
+        match self.monochrome {
+            true => Color::Reset,
+            false => color,
+        }
+    }
+}
+
+/// Whether colors are disabled by a non-empty `NO_COLOR` environment variable.
+pub fn no_color() -> bool {
+    env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
+
+lazy_static! {
+    static ref THEME: RwLock<Option<(Arc<CFSettings>, Arc<Theme>)>> = RwLock::new(None);
+}
+
+/// Returns the theme of the current settings, built again whenever they are reloaded.
+///
+/// An invalid `theme` is reported in the log and the default theme is used instead.
+pub fn theme() -> Arc<Theme> {
+    let settings = SETTINGS.get();
+    if let Ok(cache) = THEME.read() {
+        if let Some((cached, theme)) = cache.as_ref() {
+            if Arc::ptr_eq(cached, &settings) {
+                return theme.clone();
+            }
+        }
+    }
+    let theme = match no_color() {
+        true => Theme::preset("monochrome"),
+        false => Theme::new(&settings),
+    };
+    let theme = Arc::new(theme.unwrap_or_else(|err| {
+        tracing::error!("Using the default theme: {err:#}");
+        Theme::default()
+    }));
+    if let Ok(mut cache) = THEME.write() {
+        *cache = Some((settings, theme.clone()));
+    }
+    theme
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn themes() {
+        for preset in PRESETS {
+            assert!(Theme::preset(preset).is_ok());
+        }
+        assert!(Theme::preset("solarized").is_err());
+        assert_eq!(
+            Theme::new(&CFSettings::default()).unwrap(),
+            Theme::default()
+        );
+
+        let colors = |colors: &[(&str, &str)]| {
+            Some(
+                colors
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect::<HashMap<_, _>>(),
+            )
+        };
+        let mut settings = CFSettings {
+            theme: Some(CFTheme {
+                preset: Some("mine".to_string()),
+                colors: colors(&[("header", "#268bd2")]),
+            }),
+            themes: Some(HashMap::from([(
+                "mine".to_string(),
+                CFTheme {
+                    preset: Some("light".to_string()),
+                    colors: colors(&[("accepted", "cyan"), ("header", "red")]),
+                },
+            )])),
+            ..Default::default()
+        };
+        let theme = Theme::new(&settings).unwrap();
+        assert_eq!(theme.accepted, Color::Cyan);
+        assert_eq!(theme.header, Color::Rgb(0x26, 0x8b, 0xd2));
+        assert_eq!(theme.text, Color::Black);
+        assert_eq!(
+            theme.header_style(),
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Rgb(0x26, 0x8b, 0xd2))
+        );
+
+        settings.theme.as_mut().unwrap().colors = colors(&[("headr", "red")]);
+        assert!(Theme::new(&settings).is_err());
+        settings.theme.as_mut().unwrap().colors = colors(&[("header", "reddish")]);
+        assert!(Theme::new(&settings).is_err());
+        settings.theme.as_mut().unwrap().preset = Some("other".to_string());
+        settings.theme.as_mut().unwrap().colors = None;
+        assert!(Theme::new(&settings).is_err());
+    }
+
+    #[test]
+    fn monochrome() {
+        let theme = Theme::preset("monochrome").unwrap();
+        assert_eq!(theme.accepted, Color::Reset);
+        assert_eq!(theme.rank(Color::Red), Color::Reset);
+        assert_eq!(
+            theme.highlight_style(),
+            Style::default().add_modifier(Modifier::REVERSED)
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 use color_eyre::Report;
 use similar::{ChangeTag, TextDiff};
 use tokio::process::Command;
@@ -9,6 +11,8 @@ use tuirealm::{
     },
 };
 
+use crate::display::tui::theme::theme;
+
 #[derive(Clone, Debug)]
 pub struct TextSpans(Vec<TextSpan>);
 
@@ -20,6 +24,55 @@ impl TextSpans {
     pub fn bg(self, color: Color) -> Self {
         Self(self.0.into_iter().map(|text| text.bg(color)).collect())
     }
+
+    /// Highlights the characters at `positions`, e.g. those matching what is searched.
+    pub fn highlight(&self, positions: &[usize]) -> Self {
+        if positions.is_empty() {
+            return self.clone();
+        }
+        let color = theme().accent;
+        let mut spans = vec![];
+        let mut index = 0;
+        for text in &self.0 {
+            let mut current = String::new();
+            let mut matched = false;
+            for c in text.content.chars() {
+                if positions.contains(&index) != matched && !current.is_empty() {
+                    spans.push(highlighted(
+                        text,
+                        std::mem::take(&mut current),
+                        matched,
+                        color,
+                    ));
+                }
+                matched = positions.contains(&index);
+                current.push(c);
+                index += 1;
+            }
+            spans.push(highlighted(text, current, matched, color));
+        }
+        Self(spans)
+    }
+}
+
+fn highlighted(text: &TextSpan, content: String, matched: bool, color: Color) -> TextSpan {
+    let span = TextSpan {
+        content,
+        ..text.clone()
+    };
+    match matched {
+        true => span.fg(color).bold().underlined(),
+        false => span,
+    }
+}
+
+impl Display for TextSpans {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for text in &self.0 {
+            write!(f, "{}", text.content)?;
+        }
+        Ok(())
+    }
 }
 
 impl From<&str> for TextSpans {
@@ -130,6 +183,26 @@ impl Text {
     pub fn height(&self) -> usize {
         self.lines.len()
     }
+
+    /// Highlights the characters at `positions`, counted as in the text joined by newlines.
+    pub fn highlight(&self, positions: &[usize]) -> Self {
+        let mut start = 0;
+        let lines = self
+            .lines
+            .iter()
+            .map(|line| {
+                let len = line.to_string().chars().count();
+                let line_positions: Vec<usize> = positions
+                    .iter()
+                    .filter(|&&position| position >= start && position < start + len)
+                    .map(|position| position - start)
+                    .collect();
+                start += len + 1;
+                line.highlight(&line_positions)
+            })
+            .collect();
+        Self { lines }
+    }
 }
 
 impl Text {
@@ -146,6 +219,18 @@ impl Text {
     }
 }
 
+impl Display for Text {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, line) in self.lines.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
 impl From<String> for Text {
     fn from(text: String) -> Self {
         let lines = text.split("\n").map(|line| TextSpans::from(line)).collect();
@@ -229,9 +314,10 @@ pub enum TestResult {
 
 impl TestResult {
     pub fn format(&self, id: usize) -> Text {
+        let theme = theme();
         match self {
             TestResult::Accepted => {
-                Text::from(TextSpan::new(format!("Passed #{id}.")).fg(Color::Green))
+                Text::from(TextSpan::new(format!("Passed #{id}.")).fg(theme.accepted))
             }
             TestResult::WrongAnswer(input, output, answer) => {
                 let diff: Vec<TextSpans> = TextDiff::from_lines(output, answer)
@@ -239,14 +325,16 @@ impl TestResult {
                     .map(|line| {
                         match line.tag() {
                             ChangeTag::Equal => TextSpan::new(line.value()),
-                            ChangeTag::Insert => TextSpan::new(line.value()).fg(Color::Green),
-                            ChangeTag::Delete => TextSpan::new(line.value()).fg(Color::Red),
+                            ChangeTag::Insert => TextSpan::new(line.value()).fg(theme.accepted),
+                            ChangeTag::Delete => TextSpan::new(line.value()).fg(theme.rejected),
                         }
                         .into()
                     })
                     .collect();
                 Text::from(vec![
-                    Text::from(TextSpan::new(format!("Wrong Answer on Test #{id}")).fg(Color::Red)),
+                    Text::from(
+                        TextSpan::new(format!("Wrong Answer on Test #{id}")).fg(theme.rejected),
+                    ),
                     Text::from("--- Input ---"),
                     Text::from(input.clone()),
                     Text::from("--- Output ---"),
@@ -258,10 +346,10 @@ impl TestResult {
                 ])
             }
             TestResult::TimeLimitExceeded => Text::from(
-                TextSpan::new(format!("Time Limit Exceeded on Test #{id}")).fg(Color::Blue),
+                TextSpan::new(format!("Time Limit Exceeded on Test #{id}")).fg(theme.pending),
             ),
             TestResult::Err(err) => Text::from(
-                TextSpan::new(format!("Error occured on Test #{id}: {err:#?}")).fg(Color::Red),
+                TextSpan::new(format!("Error occured on Test #{id}: {err:#?}")).fg(theme.error),
             ),
             TestResult::Testing => Text::from(format!("Testing #{id}...")),
         }
@@ -1,46 +1,76 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
 
+use super::keymap::{is_action, Action};
+
 pub fn is_key(key: &KeyEvent, code: KeyCode, modifiers: KeyModifiers) -> bool {
     key.code == code && key.modifiers == modifiers
 }
 
 pub fn is_up_key(evt: &KeyEvent) -> bool {
-    is_key(evt, KeyCode::Char('k'), KeyModifiers::NONE)
-        || is_key(evt, KeyCode::Up, KeyModifiers::NONE)
+    is_action(evt, Action::Up)
 }
 
 pub fn is_down_key(evt: &KeyEvent) -> bool {
-    is_key(evt, KeyCode::Char('j'), KeyModifiers::NONE)
-        || is_key(evt, KeyCode::Down, KeyModifiers::NONE)
+    is_action(evt, Action::Down)
 }
 
 pub fn is_right_key(evt: &KeyEvent) -> bool {
-    is_key(evt, KeyCode::Char('l'), KeyModifiers::NONE)
-        || is_key(evt, KeyCode::Right, KeyModifiers::NONE)
-        || is_key(evt, KeyCode::Tab, KeyModifiers::NONE)
+    is_action(evt, Action::NextTab)
 }
 
 pub fn is_left_key(evt: &KeyEvent) -> bool {
-    is_key(evt, KeyCode::Char('h'), KeyModifiers::NONE)
-        || is_key(evt, KeyCode::Left, KeyModifiers::NONE)
-        || is_key(evt, KeyCode::Tab, KeyModifiers::SHIFT)
+    is_action(evt, Action::PrevTab)
 }
 
 pub fn is_refresh_key(evt: &KeyEvent) -> bool {
-    is_key(evt, KeyCode::F(5), KeyModifiers::NONE)
+    is_action(evt, Action::Refresh)
 }
 
 pub fn is_enter_key(evt: &KeyEvent) -> bool {
-    is_key(evt, KeyCode::Enter, KeyModifiers::NONE)
+    is_action(evt, Action::Select)
 }
 
 pub fn is_exit_key(evt: &KeyEvent) -> bool {
-    is_key(evt, KeyCode::Char('q'), KeyModifiers::NONE)
-        || is_key(evt, KeyCode::Esc, KeyModifiers::NONE)
+    is_action(evt, Action::Back)
 }
 
 pub fn is_terminate_key(evt: &KeyEvent) -> bool {
-    is_key(evt, KeyCode::Char('c'), KeyModifiers::CONTROL)
+    is_action(evt, Action::Quit)
+}
+
+/// Returns whether `evt` types a character into a text being edited.
+fn is_typing_key(evt: &KeyEvent) -> bool {
+    matches!(evt.code, KeyCode::Char(_))
+        && evt.modifiers - KeyModifiers::SHIFT == KeyModifiers::NONE
+}
+
+/// Returns whether `evt` cancels editing a text: `Esc`, or a key of `back` not typing a character.
+pub fn is_cancel_edit_key(evt: &KeyEvent) -> bool {
+    is_key(evt, KeyCode::Esc, KeyModifiers::NONE) || (is_exit_key(evt) && !is_typing_key(evt))
+}
+
+/// Returns whether `evt` confirms a text edited: `Enter`, or a key of `select` not typing a
+/// character.
+pub fn is_confirm_edit_key(evt: &KeyEvent) -> bool {
+    is_key(evt, KeyCode::Enter, KeyModifiers::NONE) || (is_enter_key(evt) && !is_typing_key(evt))
+}
+
+/// Returns whether `evt` moves up while a text is edited, which keys typing characters do not.
+pub fn is_edit_up_key(evt: &KeyEvent) -> bool {
+    is_up_key(evt) && !is_typing_key(evt)
+}
+
+/// Returns whether `evt` moves down while a text is edited, which keys typing characters do not.
+pub fn is_edit_down_key(evt: &KeyEvent) -> bool {
+    is_down_key(evt) && !is_typing_key(evt)
+}
+
+pub fn is_help_key(evt: &KeyEvent) -> bool {
+    is_action(evt, Action::Help)
+}
+
+pub fn is_palette_key(evt: &KeyEvent) -> bool {
+    is_action(evt, Action::Palette)
 }
 
 pub fn is_scroll_up(evt: &MouseEvent) -> bool {
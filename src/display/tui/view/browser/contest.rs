@@ -3,10 +3,11 @@ use color_eyre::Result;
 use tuirealm::tui::layout::{Constraint, Direction, Layout};
 
 use crate::{
-    api::objects::Contest,
+    api::{locator::ProblemSource, objects::Contest},
     display::tui::{
         component::{ContestBrowserTabs, ProblemsList, StandingsList, SubmissionsList},
         event::AppEvent,
+        keymap::Action,
         msg::{ChannelHandler, ComponentMsg, ViewMsg},
         utils::is_exit_key,
         view::ViewSender,
@@ -45,6 +46,15 @@ impl View for ContestBrowser {
             AppEvent::Tick => {
                 self.tick();
             }
+            // Keys typed into a filter go to the list only, as they may be bound to actions.
+            AppEvent::Key(_) | AppEvent::Paste(_) if self.is_editing() => {
+                match self.tabs.selected() {
+                    0 => self.problems_list.on(event)?,
+                    1 => self.standings_list.on(event)?,
+                    2 => self.submissions_list.on(event)?,
+                    _ => unreachable!(),
+                };
+            }
             AppEvent::Key(evt) if is_exit_key(evt) => {
                 self.send(ViewMsg::ExitCurrentView)?;
             }
@@ -77,13 +87,42 @@ impl View for ContestBrowser {
     fn is_fullscreen(&self) -> bool {
         true
     }
+
+    fn is_editing(&self) -> bool {
+        match self.tabs.selected() {
+            0 => self.problems_list.is_editing(),
+            1 => self.standings_list.is_editing(),
+            2 => self.submissions_list.is_editing(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        let mut actions = self.tabs.actions();
+        actions.extend(match self.tabs.selected() {
+            0 => self.problems_list.actions(),
+            1 => self.standings_list.actions(),
+            2 => self.submissions_list.actions(),
+            _ => unreachable!(),
+        });
+        actions.push(Action::Back);
+        actions
+    }
+
+    fn tabs(&self) -> Vec<String> {
+        self.tabs.titles()
+    }
+
+    fn select_tab(&mut self, index: usize) -> Result<()> {
+        self.tabs.select(index)
+    }
 }
 
 impl ContestBrowser {
-    pub fn new(sender: ViewSender, contest: Contest) -> Self {
+    pub fn new(sender: ViewSender, contest: Contest, source: ProblemSource) -> Self {
         let handler = ChannelHandler::new();
         let tabs = ContestBrowserTabs::new(handler.sender.clone());
-        let mut problems_list = ProblemsList::new(handler.sender.clone(), contest.clone());
+        let mut problems_list = ProblemsList::new(handler.sender.clone(), contest.clone(), source);
         let mut standings_list = StandingsList::new(handler.sender.clone(), contest.clone());
         let mut submissions_list = SubmissionsList::new(handler.sender.clone(), contest.clone());
 
@@ -121,6 +160,10 @@ impl ContestBrowser {
             ComponentMsg::ChangeToTab(index) => {
                 self.tabs.select(index)?;
             }
+            ComponentMsg::Submitted(id) => {
+                self.tabs.select(2)?;
+                self.submissions_list.track(id);
+            }
             _ => (),
         };
         Ok(())
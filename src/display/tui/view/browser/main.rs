@@ -1,16 +1,22 @@
 use color_eyre::Result;
+use crossterm::event::KeyEvent;
 
 use tuirealm::tui::layout::{Constraint, Direction, Layout};
 
 use crate::display::tui::{
     component::{ContestList, MainBrowserTabs, ProblemsetList},
     event::AppEvent,
-    msg::{ChannelHandler, ComponentMsg, ViewMsg},
+    keymap::{is_action, Action},
+    msg::{ChannelHandler, ComponentMsg, ViewConstructor, ViewMsg},
     utils::is_exit_key,
     view::ViewSender,
     Component, View,
 };
 
+fn is_settings_key(evt: &KeyEvent) -> bool {
+    is_action(evt, Action::Settings)
+}
+
 pub struct MainBrowser {
     pub sender: ViewSender,
     pub handler: ChannelHandler<ComponentMsg>,
@@ -39,9 +45,20 @@ impl View for MainBrowser {
             AppEvent::Tick => {
                 self.tick();
             }
+            // Keys typed into a filter go to the list only, as they may be bound to actions.
+            AppEvent::Key(_) | AppEvent::Paste(_) if self.is_editing() => {
+                match self.tabs.selected() {
+                    0 => self.contest_list.on(event)?,
+                    1 => self.problemset_list.on(event)?,
+                    _ => unreachable!(),
+                };
+            }
             AppEvent::Key(evt) if is_exit_key(evt) => {
                 self.send(ViewMsg::ExitCurrentView)?;
             }
+            AppEvent::Key(evt) if is_settings_key(evt) => {
+                self.send(ViewMsg::EnterNewView(ViewConstructor::Settings))?;
+            }
             event => {
                 self.tabs.on(event)?;
                 match self.tabs.selected() {
@@ -69,6 +86,33 @@ impl View for MainBrowser {
     fn is_fullscreen(&self) -> bool {
         true
     }
+
+    fn is_editing(&self) -> bool {
+        match self.tabs.selected() {
+            0 => self.contest_list.is_editing(),
+            1 => self.problemset_list.is_editing(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        let mut actions = self.tabs.actions();
+        actions.extend(match self.tabs.selected() {
+            0 => self.contest_list.actions(),
+            1 => self.problemset_list.actions(),
+            _ => unreachable!(),
+        });
+        actions.extend([Action::Settings, Action::Back]);
+        actions
+    }
+
+    fn tabs(&self) -> Vec<String> {
+        self.tabs.titles()
+    }
+
+    fn select_tab(&mut self, index: usize) -> Result<()> {
+        self.tabs.select(index)
+    }
 }
 
 impl MainBrowser {
@@ -3,19 +3,41 @@ use std::sync::mpsc;
 use color_eyre::Result;
 use tuirealm::Frame;
 
-use super::{event::AppEvent, msg::ViewMsg};
+use super::{event::AppEvent, keymap::Action, msg::ViewMsg};
 
 mod browser;
+mod palette;
 mod popup;
+mod settings;
+mod statement;
 
 pub use browser::{ContestBrowser, MainBrowser};
+pub use palette::PaletteView;
 pub use popup::{get_chunk_with_ratio, GetChunkFn, PopupView, SelectPopupView, UpdatablePopupView};
+pub use settings::SettingsView;
+pub use statement::StatementView;
 
 pub trait View {
     fn render(&mut self, frame: &mut Frame<'_>);
     fn handle_event(&mut self, event: &AppEvent) -> Result<()>;
     fn tick(&mut self) {}
     fn is_fullscreen(&self) -> bool;
+    /// Whether text is being typed into the view, which then gets every key.
+    fn is_editing(&self) -> bool {
+        false
+    }
+    /// The actions of the view and of the component in focus, listed by the help and the
+    /// command palette.
+    fn actions(&self) -> Vec<Action> {
+        vec![]
+    }
+    /// The names of the tabs of the view, which the command palette switches between.
+    fn tabs(&self) -> Vec<String> {
+        vec![]
+    }
+    fn select_tab(&mut self, _index: usize) -> Result<()> {
+        Ok(())
+    }
 }
 
 pub type ViewSender = mpsc::Sender<ViewMsg>;
@@ -0,0 +1,85 @@
+use color_eyre::Result;
+use tuirealm::{tui::widgets::Clear, Frame};
+
+use crate::display::tui::{
+    component::CommandPalette,
+    event::AppEvent,
+    keymap::Action,
+    msg::{ChannelHandler, ComponentMsg, ViewMsg},
+    utils::is_cancel_edit_key,
+    view::ViewSender,
+    Component, View,
+};
+
+use super::{get_chunk_with_ratio, GetChunkFn};
+
+pub struct PaletteView {
+    sender: ViewSender,
+    handler: ChannelHandler<ComponentMsg>,
+    get_chunk: GetChunkFn,
+    component: CommandPalette,
+}
+
+impl View for PaletteView {
+    fn render(&mut self, frame: &mut Frame<'_>) {
+        let chunk = (self.get_chunk)(frame.size());
+        frame.render_widget(Clear, chunk);
+        self.component.render(frame, chunk);
+    }
+
+    fn handle_event(&mut self, event: &AppEvent) -> Result<()> {
+        match event {
+            // Keys of `back` typing a character, like `q`, are typed into the search instead.
+            AppEvent::Key(evt) if is_cancel_edit_key(evt) => {
+                self.send(ViewMsg::ExitCurrentView)?;
+            }
+            event => {
+                self.component.on(event)?;
+            }
+        }
+
+        while let Ok(msg) = self.handler.try_next() {
+            self.handle_msg(msg)?;
+        }
+        Ok(())
+    }
+
+    fn is_fullscreen(&self) -> bool {
+        false
+    }
+
+    fn is_editing(&self) -> bool {
+        true
+    }
+}
+
+impl PaletteView {
+    pub fn new(sender: ViewSender, actions: &[Action], tabs: &[String]) -> Self {
+        let handler = ChannelHandler::new();
+        let component = CommandPalette::new(handler.sender.clone(), actions, tabs);
+        Self {
+            sender,
+            handler,
+            get_chunk: get_chunk_with_ratio((1, 3, 1), (1, 3, 1)),
+            component,
+        }
+    }
+
+    fn send(&mut self, msg: ViewMsg) -> Result<()> {
+        self.sender.send(msg)?;
+        Ok(())
+    }
+
+    fn handle_msg(&mut self, msg: ComponentMsg) -> Result<()> {
+        match msg {
+            ComponentMsg::ExitCurrentView => {
+                self.send(ViewMsg::ExitCurrentView)?;
+            }
+            ComponentMsg::Run(command) => {
+                self.send(ViewMsg::Run(command))?;
+            }
+            _ => (),
+        };
+        Ok(())
+    }
+}
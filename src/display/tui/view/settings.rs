@@ -0,0 +1,85 @@
+use color_eyre::Result;
+use tuirealm::Frame;
+
+use crate::display::tui::{
+    component::SettingsForm,
+    event::AppEvent,
+    keymap::Action,
+    msg::{ChannelHandler, ComponentMsg, ViewMsg},
+    view::ViewSender,
+    Component, View,
+};
+
+pub struct SettingsView {
+    sender: ViewSender,
+    handler: ChannelHandler<ComponentMsg>,
+    component: SettingsForm,
+}
+
+impl View for SettingsView {
+    fn render(&mut self, frame: &mut Frame<'_>) {
+        self.component.render(frame, frame.size());
+    }
+
+    fn handle_event(&mut self, event: &AppEvent) -> Result<()> {
+        // Exit keys are left to the form, as they are typed into the field being edited.
+        self.component.on(event)?;
+        while let Ok(msg) = self.handler.try_next() {
+            self.handle_msg(msg)?;
+        }
+        Ok(())
+    }
+
+    fn is_fullscreen(&self) -> bool {
+        true
+    }
+
+    fn is_editing(&self) -> bool {
+        self.component.is_editing()
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        self.component.actions()
+    }
+
+    fn tabs(&self) -> Vec<String> {
+        self.component.sections()
+    }
+
+    fn select_tab(&mut self, index: usize) -> Result<()> {
+        self.component.select_tab(index)
+    }
+}
+
+impl SettingsView {
+    pub fn new(sender: ViewSender) -> Result<Self> {
+        let handler = ChannelHandler::new();
+        let component = SettingsForm::new(handler.sender.clone())?;
+        Ok(Self {
+            sender,
+            handler,
+            component,
+        })
+    }
+
+    fn send(&mut self, msg: ViewMsg) -> Result<()> {
+        self.sender.send(msg)?;
+        Ok(())
+    }
+
+    fn handle_msg(&mut self, msg: ComponentMsg) -> Result<()> {
+        match msg {
+            ComponentMsg::AppClose => {
+                self.send(ViewMsg::AppClose)?;
+            }
+            ComponentMsg::EnterNewView(constructor) => {
+                self.send(ViewMsg::EnterNewView(constructor))?;
+            }
+            ComponentMsg::ExitCurrentView => {
+                self.send(ViewMsg::ExitCurrentView)?;
+            }
+            _ => (),
+        };
+        Ok(())
+    }
+}
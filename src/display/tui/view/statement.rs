@@ -0,0 +1,95 @@
+use color_eyre::Result;
+
+use tuirealm::Frame;
+
+use crate::{
+    api::{locator::ProblemLocator, objects::Problem},
+    display::tui::{
+        component::Statement,
+        event::AppEvent,
+        keymap::Action,
+        msg::{ChannelHandler, ComponentMsg, ViewMsg},
+        utils::is_exit_key,
+        view::ViewSender,
+        Component, View,
+    },
+};
+
+pub struct StatementView {
+    sender: ViewSender,
+    handler: ChannelHandler<ComponentMsg>,
+    component: Statement,
+}
+
+impl View for StatementView {
+    fn render(&mut self, frame: &mut Frame<'_>) {
+        self.component.render(frame, frame.size());
+    }
+
+    fn handle_event(&mut self, event: &AppEvent) -> Result<()> {
+        match event {
+            AppEvent::Tick => {
+                self.tick();
+            }
+            AppEvent::Key(evt) if is_exit_key(evt) => {
+                self.send(ViewMsg::ExitCurrentView)?;
+            }
+            event => {
+                self.component.on(event)?;
+            }
+        }
+
+        while let Ok(msg) = self.handler.try_next() {
+            self.handle_msg(msg)?;
+        }
+        Ok(())
+    }
+
+    fn tick(&mut self) {
+        self.component.tick();
+    }
+
+    fn is_fullscreen(&self) -> bool {
+        true
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        let mut actions = self.component.actions();
+        actions.push(Action::Back);
+        actions
+    }
+}
+
+impl StatementView {
+    pub fn new(sender: ViewSender, locator: ProblemLocator, problem: Problem) -> Self {
+        let handler = ChannelHandler::new();
+        let mut component = Statement::new(handler.sender.clone(), locator, problem);
+        component.update();
+        Self {
+            sender,
+            handler,
+            component,
+        }
+    }
+
+    fn send(&mut self, msg: ViewMsg) -> Result<()> {
+        self.sender.send(msg)?;
+        Ok(())
+    }
+
+    fn handle_msg(&mut self, msg: ComponentMsg) -> Result<()> {
+        match msg {
+            ComponentMsg::AppClose => {
+                self.send(ViewMsg::AppClose)?;
+            }
+            ComponentMsg::EnterNewView(constructor) => {
+                self.send(ViewMsg::EnterNewView(constructor))?;
+            }
+            ComponentMsg::ExitCurrentView => {
+                self.send(ViewMsg::ExitCurrentView)?;
+            }
+            _ => (),
+        };
+        Ok(())
+    }
+}
@@ -0,0 +1,576 @@
+//! Checks of the configuration, reported by `cf-tui doctor` and `cf-cli doctor`.
+//!
+//! Misconfiguration otherwise only shows up when a problem is generated, tested or submitted.
+
+use std::{
+    env,
+    fmt::Display,
+    fs,
+    io::IsTerminal,
+    path::{Path, PathBuf},
+};
+
+use crossterm::style::Stylize;
+
+use crate::{
+    api::{
+        languages::{language_for_extension, load_languages, Language},
+        methods::{build_client, check_authorization},
+    },
+    credentials::CFCredentials,
+    display::tui::{
+        component::{SCRIPT_PLACEHOLDERS, TEMPLATE_PLACEHOLDERS},
+        keymap::Keymap,
+        theme::Theme,
+    },
+    settings::{CFScripts, CFSettings, CFTemplate},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// The result of checking one item of the configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnosis {
+    pub severity: Severity,
+    /// The item checked, e.g. `home_dir` or `commands.cpp`.
+    pub item: String,
+    pub message: String,
+    /// How to fix the problem found.
+    pub fix: Option<String>,
+}
+
+impl Diagnosis {
+    pub fn ok(item: impl Display, message: impl Display) -> Self {
+        Self {
+            severity: Severity::Ok,
+            item: item.to_string(),
+            message: message.to_string(),
+            fix: None,
+        }
+    }
+
+    pub fn warning(item: impl Display, message: impl Display, fix: impl Display) -> Self {
+        Self {
+            severity: Severity::Warning,
+            item: item.to_string(),
+            message: message.to_string(),
+            fix: Some(fix.to_string()),
+        }
+    }
+
+    pub fn error(item: impl Display, message: impl Display, fix: impl Display) -> Self {
+        Self {
+            severity: Severity::Error,
+            item: item.to_string(),
+            message: message.to_string(),
+            fix: Some(fix.to_string()),
+        }
+    }
+}
+
+/// Returns the placeholders like `<% name %>` in `content` which are not in `known`.
+fn unknown_placeholders(content: &str, known: &[&str]) -> Vec<String> {
+    let mut unknown = vec![];
+    let mut rest = content;
+    while let Some(start) = rest.find("<%") {
+        let Some(end) = rest[start..].find("%>") else {
+            break;
+        };
+        let placeholder = &rest[start..start + end + 2];
+        if !known.contains(&placeholder) && !unknown.iter().any(|found| found == placeholder) {
+            unknown.push(placeholder.to_string());
+        }
+        rest = &rest[start + end + 2..];
+    }
+    unknown
+}
+
+/// Returns where `program` is found on `PATH`, like `which`.
+fn find_program(program: &str) -> Option<PathBuf> {
+    let program = Path::new(program);
+    if program.components().count() > 1 {
+        return program.is_file().then(|| program.to_path_buf());
+    }
+    let extensions: Vec<String> = match cfg!(windows) {
+        true => env::var("PATHEXT")
+            .unwrap_or(String::from(".EXE;.CMD;.BAT;.COM"))
+            .split(';')
+            .map(|ext| ext.to_string())
+            .chain([String::new()])
+            .collect(),
+        false => vec![String::new()],
+    };
+    env::split_paths(&env::var_os("PATH")?)
+        .flat_map(|dir| {
+            extensions
+                .iter()
+                .map(move |ext| dir.join(format!("{}{ext}", program.display())))
+        })
+        .find(|path| path.is_file())
+}
+
+fn check_username(settings: &CFSettings) -> Diagnosis {
+    match &settings.username {
+        Some(username) if !username.trim().is_empty() => {
+            Diagnosis::ok("username", format!("Configured as {username}"))
+        }
+        _ => Diagnosis::error(
+            "username",
+            "Not configured",
+            "Run `cf-tui config login` or set `username`.",
+        ),
+    }
+}
+
+fn check_credentials(credentials: &CFCredentials) -> Diagnosis {
+    match (&credentials.key, &credentials.secret) {
+        (Some(_), Some(_)) => Diagnosis::ok("key, secret", "Configured"),
+        _ => Diagnosis::warning(
+            "key, secret",
+            "Not configured, so only public data can be fetched from the API",
+            "Create them at https://codeforces.com/settings/api and run `cf-tui config login`.",
+        ),
+    }
+}
+
+fn check_home_dir(settings: &CFSettings) -> Diagnosis {
+    let Some(home_dir) = &settings.home_dir else {
+        return Diagnosis::error(
+            "home_dir",
+            "Not configured, so no problem can be parsed, generated or tested",
+            "Set `home_dir` to the directory to keep codes and samples in.",
+        );
+    };
+    match fs::metadata(home_dir) {
+        Ok(metadata) if !metadata.is_dir() => Diagnosis::error(
+            "home_dir",
+            format!("{} is not a directory", home_dir.display()),
+            "Set `home_dir` to a directory.",
+        ),
+        Ok(metadata) if metadata.permissions().readonly() => Diagnosis::error(
+            "home_dir",
+            format!("{} is read-only", home_dir.display()),
+            format!("Make {} writable.", home_dir.display()),
+        ),
+        Ok(_) => Diagnosis::ok("home_dir", home_dir.display()),
+        Err(_) => Diagnosis::warning(
+            "home_dir",
+            format!("{} does not exist yet", home_dir.display()),
+            "It is created when a problem is first parsed or generated, check the path is right.",
+        ),
+    }
+}
+
+fn check_template(
+    index: usize,
+    template: &CFTemplate,
+    settings: &CFSettings,
+    catalog: &[Language],
+) -> Vec<Diagnosis> {
+    let item = format!("templates[{index}] ({})", template.alias);
+    let mut diagnoses = vec![];
+    let path = match template.full_path() {
+        Ok(path) => path,
+        Err(err) => return vec![Diagnosis::error(item, format!("{err:#}"), "Check `path`.")],
+    };
+    match fs::read_to_string(&path) {
+        Ok(content) => {
+            diagnoses.push(Diagnosis::ok(&item, path.display()));
+            for placeholder in unknown_placeholders(&content, TEMPLATE_PLACEHOLDERS) {
+                diagnoses.push(Diagnosis::warning(
+                    &item,
+                    format!("Unknown placeholder `{placeholder}` is kept as it is"),
+                    format!("Use one of {}.", TEMPLATE_PLACEHOLDERS.join(", ")),
+                ));
+            }
+            if content.contains("<% username %>") && settings.username.is_none() {
+                diagnoses.push(Diagnosis::error(
+                    &item,
+                    "`<% username %>` is used but no username is configured",
+                    "Run `cf-tui config login` or set `username`.",
+                ));
+            }
+        }
+        Err(err) => diagnoses.push(Diagnosis::error(
+            &item,
+            format!("Cannot read {}: {err}", path.display()),
+            "Create the file or correct `path` with `cf-tui config templates`.",
+        )),
+    }
+
+    let Some(ext) = path.extension().and_then(|ext| ext.to_str()) else {
+        diagnoses.push(Diagnosis::error(
+            &item,
+            format!(
+                "{} has no extension, so codes generated from it cannot be tested or submitted",
+                path.display()
+            ),
+            "Add the extension of the language to `path`.",
+        ));
+        return diagnoses;
+    };
+    if !settings
+        .commands
+        .as_ref()
+        .is_some_and(|commands| commands.contains_key(ext))
+    {
+        diagnoses.push(Diagnosis::warning(
+            &item,
+            format!("No commands for .{ext}, so codes generated from it cannot be tested"),
+            format!("Add commands for {ext} with `cf-tui config commands`."),
+        ));
+    }
+    match template.program_type_id {
+        Some(id) if !catalog.iter().any(|language| language.id == id) => {
+            diagnoses.push(Diagnosis::warning(
+                &item,
+                format!("program_type_id {id} is not a known language"),
+                "Pick one listed by `cf-cli languages`, or run `cf-cli languages --refresh`.",
+            ))
+        }
+        Some(_) => {}
+        None => {
+            if language_for_extension(ext, settings.languages.as_ref(), None, catalog).is_none() {
+                diagnoses.push(Diagnosis::error(
+                    &item,
+                    format!("No language to submit .{ext} codes in"),
+                    "Set `program_type_id` or add the extension to `languages`.",
+                ));
+            }
+        }
+    }
+    diagnoses
+}
+
+fn check_script(item: &str, name: &str, script: &str, required: bool) -> Vec<Diagnosis> {
+    let mut diagnoses = vec![];
+    for placeholder in unknown_placeholders(script, SCRIPT_PLACEHOLDERS) {
+        diagnoses.push(Diagnosis::error(
+            item,
+            format!("Unknown placeholder `{placeholder}` in {name}"),
+            format!("Use one of {}.", SCRIPT_PLACEHOLDERS.join(", ")),
+        ));
+    }
+    let Some(program) = script.split_whitespace().next() else {
+        if required {
+            diagnoses.push(Diagnosis::error(
+                item,
+                format!("{name} is empty"),
+                format!("Set {name} to the command running your code."),
+            ));
+        }
+        return diagnoses;
+    };
+    // Programs built by the scripts themselves, e.g. `./<% file %>`, cannot be checked.
+    if program.contains("<%") || program.starts_with('.') {
+        return diagnoses;
+    }
+    match find_program(program) {
+        Some(path) => diagnoses.push(Diagnosis::ok(
+            item,
+            format!("{name} runs {}", path.display()),
+        )),
+        None => {
+            let message = format!("{program} of {name} is not found on PATH");
+            let fix = format!("Install {program} or add its directory to PATH.");
+            // Opening is optional, while nothing can be tested without the others.
+            diagnoses.push(match name {
+                "open_script" => Diagnosis::warning(item, message, fix),
+                _ => Diagnosis::error(item, message, fix),
+            })
+        }
+    }
+    diagnoses
+}
+
+fn check_commands(ext: &str, scripts: &CFScripts) -> Vec<Diagnosis> {
+    let item = format!("commands.{ext}");
+    let scripts = [
+        ("before_script", scripts.before_script.as_deref(), false),
+        ("script", Some(scripts.script.as_str()), true),
+        ("after_script", scripts.after_script.as_deref(), false),
+        ("open_script", scripts.open_script.as_deref(), false),
+    ];
+    scripts
+        .into_iter()
+        .filter_map(|(name, script, required)| Some((name, script?, required)))
+        .flat_map(|(name, script, required)| check_script(&item, name, script, required))
+        .collect()
+}
+
+fn check_languages(settings: &CFSettings, catalog: &[Language]) -> Vec<Diagnosis> {
+    let mut languages: Vec<_> = settings.languages.iter().flatten().collect();
+    languages.sort();
+    languages
+        .into_iter()
+        .map(|(ext, id)| {
+            let item = format!("languages.{ext}");
+            match catalog.iter().find(|language| language.id == *id) {
+                Some(language) => Diagnosis::ok(item, language),
+                None => Diagnosis::warning(
+                    item,
+                    format!("{id} is not a known language"),
+                    "Pick one listed by `cf-cli languages`, or run `cf-cli languages --refresh`.",
+                ),
+            }
+        })
+        .collect()
+}
+
+fn check_keybindings(settings: &CFSettings) -> Option<Diagnosis> {
+    let keybindings = settings.keybindings.as_ref()?;
+    Some(match Keymap::new(Some(keybindings)) {
+        Ok(_) => Diagnosis::ok("keybindings", "Valid"),
+        Err(err) => Diagnosis::error(
+            "keybindings",
+            format!("{err:#}").replace('\n', " "),
+            "Correct `keybindings`, the default keys are used meanwhile.",
+        ),
+    })
+}
+
+fn check_theme(settings: &CFSettings) -> Option<Diagnosis> {
+    if settings.theme.is_none() && settings.themes.is_none() {
+        return None;
+    }
+    Some(match Theme::new(settings) {
+        Ok(_) => Diagnosis::ok("theme", "Valid"),
+        Err(err) => Diagnosis::error(
+            "theme",
+            format!("{err:#}"),
+            "Correct `theme` or `themes`, the default theme is used meanwhile.",
+        ),
+    })
+}
+
+fn check_http(settings: &CFSettings) -> Option<Diagnosis> {
+    let http = settings.http.as_ref()?;
+    Some(match build_client(Some(http)) {
+        Ok(_) => Diagnosis::ok("http", "Valid"),
+        Err(err) => Diagnosis::error("http", format!("{err:#}"), "Correct `http`."),
+    })
+}
+
+/// Checks the configuration without making any request.
+pub fn validate(settings: &CFSettings, credentials: &CFCredentials) -> Vec<Diagnosis> {
+    let catalog = load_languages();
+    let mut diagnoses = vec![
+        check_username(settings),
+        check_credentials(credentials),
+        check_home_dir(settings),
+    ];
+    match &settings.templates {
+        Some(templates) if !templates.is_empty() => {
+            for (index, template) in templates.iter().enumerate() {
+                diagnoses.extend(check_template(index, template, settings, &catalog));
+            }
+        }
+        _ => diagnoses.push(Diagnosis::warning(
+            "templates",
+            "No templates, so no code can be generated",
+            "Add one with `cf-tui config templates`.",
+        )),
+    }
+    let mut commands: Vec<_> = settings.commands.iter().flatten().collect();
+    commands.sort_by_key(|(ext, _)| *ext);
+    for (ext, scripts) in commands {
+        diagnoses.extend(check_commands(ext, scripts));
+    }
+    diagnoses.extend(check_languages(settings, &catalog));
+    diagnoses.extend(check_keybindings(settings));
+    diagnoses.extend(check_theme(settings));
+    diagnoses.extend(check_http(settings));
+    diagnoses
+}
+
+/// Checks that the API accepts the key and secret with an authorized call.
+pub async fn check_api(credentials: &CFCredentials) -> Option<Diagnosis> {
+    credentials.key.as_ref().and(credentials.secret.as_ref())?;
+    Some(match check_authorization().await {
+        Ok(_) => Diagnosis::ok("API", "The key and secret are accepted"),
+        Err(err) => Diagnosis::error(
+            "API",
+            match err.root_cause().to_string() {
+                cause if cause == err.to_string() => cause,
+                cause => format!("{err}: {cause}"),
+            }
+            .replace('\n', " "),
+            "Check the key and secret at https://codeforces.com/settings/api and the `http` settings.",
+        ),
+    })
+}
+
+/// Whether the report is colored, which it is on a terminal unless `NO_COLOR` is set.
+fn colored() -> bool {
+    env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()) && std::io::stdout().is_terminal()
+}
+
+/// Formats `diagnoses` one per line, with the fixes of problems below them.
+pub fn report(diagnoses: &[Diagnosis], colored: bool) -> String {
+    let mut lines = vec![];
+    for diagnosis in diagnoses {
+        let mark = match diagnosis.severity {
+            Severity::Ok => "✓".green(),
+            Severity::Warning => "!".yellow(),
+            Severity::Error => "✗".red(),
+        };
+        let mark = match colored {
+            true => mark.bold().to_string(),
+            false => mark.content().to_string(),
+        };
+        lines.push(format!("{mark} {}: {}", diagnosis.item, diagnosis.message));
+        if let Some(fix) = &diagnosis.fix {
+            let fix = format!("  Fix: {fix}");
+            lines.push(match colored {
+                true => fix.dark_grey().to_string(),
+                false => fix,
+            });
+        }
+    }
+    let count = |severity| {
+        diagnoses
+            .iter()
+            .filter(|diagnosis| diagnosis.severity == severity)
+            .count()
+    };
+    let (errors, warnings) = (count(Severity::Error), count(Severity::Warning));
+    lines.push(String::new());
+    lines.push(match (errors, warnings) {
+        (0, 0) => String::from("No problems found."),
+        _ => format!(
+            "{errors} error{}, {warnings} warning{}.",
+            if errors == 1 { "" } else { "s" },
+            if warnings == 1 { "" } else { "s" }
+        ),
+    });
+    lines.join("\n")
+}
+
+/// Checks the configuration, prints the report and returns whether no error is found.
+pub async fn doctor(settings: &CFSettings, credentials: &CFCredentials) -> bool {
+    let mut diagnoses = validate(settings, credentials);
+    diagnoses.extend(check_api(credentials).await);
+    println!("{}", report(&diagnoses, colored()));
+    diagnoses
+        .iter()
+        .all(|diagnosis| diagnosis.severity != Severity::Error)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn placeholders() {
+        assert_eq!(
+            unknown_placeholders(
+                "<% username %> <% date %> <%file%> <% date %> <% year",
+                TEMPLATE_PLACEHOLDERS
+            ),
+            vec!["<% date %>", "<%file%>"]
+        );
+        assert!(
+            unknown_placeholders("g++ <% full %> -o <% file %>", SCRIPT_PLACEHOLDERS).is_empty()
+        );
+    }
+
+    #[test]
+    fn scripts() {
+        let scripts = CFScripts {
+            before_script: Some(String::from("no-such-compiler <% full %>")),
+            script: String::from("./<% file %> <% input %>"),
+            after_script: Some(String::new()),
+            open_script: Some(String::from("no-such-editor <% full %>")),
+        };
+        let severities: Vec<_> = check_commands("cpp", &scripts)
+            .into_iter()
+            .map(|diagnosis| (diagnosis.severity, diagnosis.message))
+            .collect();
+        assert_eq!(
+            severities,
+            vec![
+                (
+                    Severity::Error,
+                    String::from("no-such-compiler of before_script is not found on PATH")
+                ),
+                (
+                    Severity::Error,
+                    String::from("Unknown placeholder `<% input %>` in script")
+                ),
+                (
+                    Severity::Warning,
+                    String::from("no-such-editor of open_script is not found on PATH")
+                ),
+            ]
+        );
+        if cfg!(unix) {
+            assert_eq!(
+                check_script("", "script", "sh <% full %>", true)[0].severity,
+                Severity::Ok
+            );
+        }
+    }
+
+    #[test]
+    fn templates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("template.cpp");
+        fs::write(&path, "// <% username %> <% date %>").unwrap();
+        let template = CFTemplate {
+            alias: String::from("cpp"),
+            lang: String::from("C++"),
+            path,
+            program_type_id: Some(1),
+        };
+        let settings = CFSettings {
+            commands: Some(HashMap::new()),
+            ..Default::default()
+        };
+        let catalog = vec![Language::new(54, "GNU G++17", "7.3.0")];
+        let severities: Vec<_> = check_template(0, &template, &settings, &catalog)
+            .into_iter()
+            .map(|diagnosis| diagnosis.severity)
+            .collect();
+        // Unknown placeholder, no username, no commands and an unknown language.
+        assert_eq!(
+            severities,
+            vec![
+                Severity::Ok,
+                Severity::Warning,
+                Severity::Error,
+                Severity::Warning,
+                Severity::Warning
+            ]
+        );
+
+        let template = CFTemplate {
+            path: dir.path().join("missing"),
+            program_type_id: None,
+            ..template
+        };
+        let diagnoses = check_template(1, &template, &settings, &catalog);
+        assert_eq!(diagnoses.len(), 2);
+        assert!(diagnoses
+            .iter()
+            .all(|diagnosis| diagnosis.severity == Severity::Error));
+    }
+
+    #[test]
+    fn plain_report() {
+        let diagnoses = vec![
+            Diagnosis::ok("home_dir", "/home/cf"),
+            Diagnosis::error("username", "Not configured", "Run `cf-tui config login`."),
+        ];
+        assert_eq!(
+            report(&diagnoses, false),
+            "✓ home_dir: /home/cf\n✗ username: Not configured\n  Fix: Run `cf-tui config login`.\n\n1 error, 0 warnings."
+        );
+    }
+}
@@ -0,0 +1,410 @@
+//! Imports the configuration and the session of [cf-tool](https://github.com/xalanq/cf-tool)
+//! by xalanq, kept in `~/.cf/config` and `~/.cf/session`.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use color_eyre::{
+    eyre::{eyre, Context},
+    Result,
+};
+use reqwest::Url;
+use serde::Deserialize;
+
+use crate::{
+    api::{
+        languages::{find_language, load_languages},
+        session::COOKIE_JAR,
+        utils::base_url,
+    },
+    settings::{
+        get_config_file_path, load_global_settings, save_global_settings, CFHttp, CFScripts,
+        CFSettings, CFTemplate,
+    },
+};
+
+/// Placeholders of cf-tool and the ones replacing them in template codes.
+static TEMPLATE_PLACEHOLDERS: &[(&str, &str)] = &[
+    ("$%U%$", "<% username %>"),
+    ("$%Y%$", "<% year %>"),
+    ("$%M%$", "<% month %>"),
+    ("$%D%$", "<% day %>"),
+    ("$%h%$", "<% hour %>"),
+    ("$%m%$", "<% minute %>"),
+    ("$%s%$", "<% second %>"),
+];
+
+/// Placeholders of cf-tool and the ones replacing them in scripts.
+static SCRIPT_PLACEHOLDERS: &[(&str, &str)] = &[
+    ("$%path%$", "<% path %>"),
+    ("$%full%$", "<% full %>"),
+    ("$%file%$", "<% file %>"),
+];
+
+#[derive(Debug, Deserialize)]
+struct XalanqTemplate {
+    alias: String,
+    /// The `programTypeId` codes are submitted in.
+    lang: String,
+    path: PathBuf,
+    #[serde(default)]
+    suffix: Vec<String>,
+    #[serde(default)]
+    before_script: String,
+    #[serde(default)]
+    script: String,
+    #[serde(default)]
+    after_script: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct XalanqConfig {
+    #[serde(default)]
+    template: Vec<XalanqTemplate>,
+    #[serde(default)]
+    host: String,
+    #[serde(default)]
+    proxy: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct XalanqSession {
+    #[serde(default)]
+    handle: String,
+    #[serde(default)]
+    cookies: serde_json::Value,
+}
+
+/// Returns `~/.cf`, where cf-tool keeps its files.
+pub fn default_xalanq_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home_dir| home_dir.join(".cf"))
+}
+
+fn replace_placeholders(text: &str, placeholders: &[(&str, &str)]) -> String {
+    placeholders
+        .iter()
+        .fold(text.to_string(), |text, (old, new)| text.replace(old, new))
+}
+
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(path), Some(home_dir)) => home_dir.join(path),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Merges the templates, scripts and `http` settings of `config` into `settings`, copying
+/// template codes to `template_dir`. Returns notes on what was done.
+fn import_config(
+    config: XalanqConfig,
+    settings: &mut CFSettings,
+    template_dir: &Path,
+) -> Result<Vec<String>> {
+    let catalog = load_languages();
+    let mut notes = vec![];
+    for template in config.template {
+        let templates = settings.templates.get_or_insert_with(Vec::new);
+        if templates.iter().any(|known| known.alias == template.alias) {
+            notes.push(format!(
+                "Skipped template {}, which is already configured.",
+                template.alias
+            ));
+            continue;
+        }
+
+        let source = expand_home(&template.path);
+        let file_name = source
+            .file_name()
+            .ok_or(eyre!("Template {} has no file name", template.alias))?;
+        let target = template_dir.join(file_name);
+        if target.exists() {
+            notes.push(format!(
+                "Kept {}, which already exists, for template {}.",
+                target.display(),
+                template.alias
+            ));
+        } else {
+            let content = fs::read_to_string(&source).wrap_err(format!(
+                "Error occured when reading from {}",
+                source.display()
+            ))?;
+            fs::create_dir_all(template_dir)?;
+            fs::write(
+                &target,
+                replace_placeholders(&content, TEMPLATE_PLACEHOLDERS),
+            )
+            .wrap_err(format!(
+                "Error occured when writing to {}",
+                target.display()
+            ))?;
+            notes.push(format!(
+                "Copied template {} to {}.",
+                template.alias,
+                target.display()
+            ));
+        }
+
+        let program_type_id = template.lang.trim().parse().ok();
+        templates.push(CFTemplate {
+            alias: template.alias.clone(),
+            lang: program_type_id
+                .map(|id| find_language(&catalog, id).name)
+                .unwrap_or(template.lang),
+            path: PathBuf::from(file_name),
+            program_type_id,
+        });
+
+        let scripts = CFScripts {
+            before_script: Some(replace_placeholders(
+                &template.before_script,
+                SCRIPT_PLACEHOLDERS,
+            )),
+            script: replace_placeholders(&template.script, SCRIPT_PLACEHOLDERS),
+            after_script: Some(replace_placeholders(
+                &template.after_script,
+                SCRIPT_PLACEHOLDERS,
+            )),
+            open_script: None,
+        };
+        if [
+            &template.before_script,
+            &template.script,
+            &template.after_script,
+        ]
+        .iter()
+        .any(|script| script.contains("$%rand%$"))
+        {
+            notes.push(format!(
+                "Scripts of template {} use $%rand%$, which is not supported.",
+                template.alias
+            ));
+        }
+        let extension = target
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_string());
+        let commands = settings.commands.get_or_insert_with(HashMap::new);
+        for ext in template.suffix.into_iter().chain(extension) {
+            commands.entry(ext).or_insert(scripts.clone());
+        }
+    }
+
+    let host = config.host.trim().trim_end_matches('/');
+    let proxy = config.proxy.trim();
+    if (!host.is_empty() && host != "https://codeforces.com") || !proxy.is_empty() {
+        let http = settings.http.get_or_insert_with(CFHttp::default);
+        if !host.is_empty() && host != "https://codeforces.com" {
+            http.base_url = Some(format!("{host}/"));
+        }
+        if !proxy.is_empty() {
+            http.proxy = Some(proxy.to_string());
+        }
+    }
+    Ok(notes)
+}
+
+/// Returns the cookies of a cf-tool session as `Set-Cookie` headers.
+///
+/// cf-tool saves the entries of its cookie jar, with `Name`, `Value`, `Path`, `Expires` and so on.
+fn xalanq_cookies(cookies: &serde_json::Value) -> Vec<String> {
+    let mut set_cookies = vec![];
+    match cookies {
+        serde_json::Value::Object(entry)
+            if entry.get("Name").is_some_and(|name| name.is_string()) =>
+        {
+            let field = |key: &str| entry.get(key).and_then(|value| value.as_str());
+            let flag = |key: &str| entry.get(key).and_then(|value| value.as_bool());
+            let mut set_cookie = format!(
+                "{}={}",
+                field("Name").unwrap_or_default(),
+                field("Value").unwrap_or_default()
+            );
+            set_cookie.push_str(&format!("; Path={}", field("Path").unwrap_or("/")));
+            if flag("Persistent") != Some(false) {
+                if let Some(expires) =
+                    field("Expires").and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+                {
+                    set_cookie.push_str(&format!(
+                        "; Expires={}",
+                        expires
+                            .with_timezone(&Utc)
+                            .format("%a, %d %b %Y %H:%M:%S GMT")
+                    ));
+                }
+            }
+            if flag("Secure") == Some(true) {
+                set_cookie.push_str("; Secure");
+            }
+            if flag("HttpOnly") == Some(true) {
+                set_cookie.push_str("; HttpOnly");
+            }
+            set_cookies.push(set_cookie);
+        }
+        serde_json::Value::Object(entries) => {
+            for entries in entries.values() {
+                set_cookies.extend(xalanq_cookies(entries));
+            }
+        }
+        serde_json::Value::Array(entries) => {
+            for entries in entries {
+                set_cookies.extend(xalanq_cookies(entries));
+            }
+        }
+        _ => {}
+    }
+    set_cookies
+}
+
+/// Imports `config` and `session` of cf-tool in `dir` into the configuration and the session.
+/// Returns notes on what was done.
+pub fn import_xalanq(dir: &Path) -> Result<Vec<String>> {
+    let config_path = dir.join("config");
+    let session_path = dir.join("session");
+    if !config_path.exists() && !session_path.exists() {
+        return Err(eyre!(
+            "Neither config nor session of cf-tool is found in {}",
+            dir.display()
+        ));
+    }
+    let config_file_path = get_config_file_path()?;
+    let mut settings = match config_file_path.try_exists()? {
+        true => load_global_settings()?,
+        false => CFSettings::default(),
+    };
+    let mut notes = vec![];
+
+    if config_path.exists() {
+        let content = fs::read_to_string(&config_path).wrap_err(format!(
+            "Error occured when reading from {}",
+            config_path.display()
+        ))?;
+        let config: XalanqConfig = serde_json::from_str(&content)
+            .wrap_err(format!("Failed to parse {}", config_path.display()))?;
+        let template_dir = config_file_path.with_file_name("templates");
+        notes.extend(import_config(config, &mut settings, &template_dir)?);
+    }
+
+    let mut set_cookies = vec![];
+    let host = settings
+        .http
+        .as_ref()
+        .and_then(|http| http.base_url.clone());
+    if session_path.exists() {
+        let content = fs::read_to_string(&session_path).wrap_err(format!(
+            "Error occured when reading from {}",
+            session_path.display()
+        ))?;
+        let session: XalanqSession = serde_json::from_str(&content)
+            .wrap_err(format!("Failed to parse {}", session_path.display()))?;
+        if settings.username.is_none() && !session.handle.is_empty() {
+            settings.username = Some(session.handle.clone());
+            notes.push(format!("Set username to {}.", session.handle));
+        }
+        set_cookies = xalanq_cookies(&session.cookies);
+        notes.push(String::from("The password of cf-tool is encrypted and cannot be imported, run `cf-cli login` when the session expires."));
+    }
+
+    save_global_settings(&settings)?;
+    notes.push(format!(
+        "Saved the configuration to {}.",
+        config_file_path.display()
+    ));
+    if !set_cookies.is_empty() {
+        let url = host.unwrap_or_else(base_url);
+        let url = Url::parse(&url).wrap_err(format!("Invalid URL {url}"))?;
+        COOKIE_JAR.import(&url, &set_cookies)?;
+        notes.push(format!(
+            "Imported {} cookies of the session.",
+            set_cookies.len()
+        ));
+    }
+    Ok(notes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cookies() {
+        let cookies = serde_json::json!({
+            "codeforces.com": {
+                "codeforces.com;/;JSESSIONID": {
+                    "Name": "JSESSIONID",
+                    "Value": "ABC",
+                    "Domain": "codeforces.com",
+                    "Path": "/",
+                    "Secure": false,
+                    "HttpOnly": true,
+                    "Persistent": false,
+                    "HostOnly": true,
+                    "Expires": "9999-12-31T23:59:59Z",
+                },
+                "codeforces.com;/;39ce7": {
+                    "Name": "39ce7",
+                    "Value": "CFh",
+                    "Path": "/",
+                    "Secure": true,
+                    "Persistent": true,
+                    "Expires": "2030-01-02T03:04:05+08:00",
+                },
+            }
+        });
+        let mut cookies = xalanq_cookies(&cookies);
+        cookies.sort();
+        assert_eq!(
+            cookies,
+            vec![
+                "39ce7=CFh; Path=/; Expires=Tue, 01 Jan 2030 19:04:05 GMT; Secure",
+                "JSESSIONID=ABC; Path=/; HttpOnly",
+            ]
+        );
+    }
+
+    #[test]
+    fn config() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source.cpp");
+        fs::write(&source, "// $%U%$ $%Y%$-$%M%$-$%D%$").unwrap();
+        let config: XalanqConfig = serde_json::from_value(serde_json::json!({
+            "template": [{
+                "alias": "cpp",
+                "lang": "54",
+                "path": source,
+                "suffix": ["cxx", "cc"],
+                "before_script": "g++ $%full%$ -o $%file%$.exe",
+                "script": "./$%file%$.exe",
+                "after_script": "rm $%file%$.exe",
+            }],
+            "default": 0,
+            "host": "https://codeforces.com",
+            "proxy": "http://127.0.0.1:7890",
+        }))
+        .unwrap();
+        let template_dir = dir.path().join("templates");
+        let mut settings = CFSettings::default();
+        import_config(config, &mut settings, &template_dir).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(template_dir.join("source.cpp")).unwrap(),
+            "// <% username %> <% year %>-<% month %>-<% day %>"
+        );
+        let template = &settings.templates.unwrap()[0];
+        assert_eq!(template.path, PathBuf::from("source.cpp"));
+        assert_eq!(template.program_type_id, Some(54));
+        let commands = settings.commands.unwrap();
+        let mut exts: Vec<_> = commands.keys().collect();
+        exts.sort();
+        assert_eq!(exts, vec!["cc", "cpp", "cxx"]);
+        assert_eq!(
+            commands["cpp"].before_script.as_deref(),
+            Some("g++ <% full %> -o <% file %>.exe")
+        );
+        let http = settings.http.unwrap();
+        assert_eq!(http.base_url, None);
+        assert_eq!(http.proxy.as_deref(), Some("http://127.0.0.1:7890"));
+    }
+}
@@ -1,5 +1,10 @@
 pub mod api;
 pub mod args;
+pub mod credentials;
 pub mod display;
+pub mod doctor;
+pub mod import;
 pub mod log;
+#[cfg(feature = "mock")]
+pub mod mock;
 pub mod settings;
@@ -0,0 +1,402 @@
+//! A local stand-in for Codeforces, serving recorded fixtures.
+//!
+//! Every endpoint in [`crate::api::methods`] and the problem pages read by
+//! [`crate::api::parse`] are served from `src/mock/fixtures`, so the whole tool can run
+//! offline by pointing it to the server with `CF_BASE_URL` or [`crate::api::utils::set_base_url`].
+//! The pages used by [`crate::api::session`] are simulated by [`web`].
+
+pub mod web;
+
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    fs,
+    future::Future,
+    net::{SocketAddr, TcpListener},
+    path::PathBuf,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+
+use color_eyre::{eyre::eyre, Result};
+use hyper::{
+    header::{CONNECTION, CONTENT_TYPE},
+    http::request::Parts,
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server, StatusCode,
+};
+use tokio::sync::oneshot;
+
+use self::web::WebState;
+
+static API_FIXTURES: &[(&str, &str)] = &[
+    (
+        "blogEntry.comments",
+        include_str!("fixtures/api/blogEntry.comments.json"),
+    ),
+    (
+        "blogEntry.view",
+        include_str!("fixtures/api/blogEntry.view.json"),
+    ),
+    (
+        "contest.hacks",
+        include_str!("fixtures/api/contest.hacks.json"),
+    ),
+    (
+        "contest.list",
+        include_str!("fixtures/api/contest.list.json"),
+    ),
+    (
+        "contest.ratingChanges",
+        include_str!("fixtures/api/contest.ratingChanges.json"),
+    ),
+    (
+        "contest.standings",
+        include_str!("fixtures/api/contest.standings.json"),
+    ),
+    (
+        "contest.status",
+        include_str!("fixtures/api/contest.status.json"),
+    ),
+    (
+        "problemset.problems",
+        include_str!("fixtures/api/problemset.problems.json"),
+    ),
+    (
+        "problemset.recentStatus",
+        include_str!("fixtures/api/problemset.recentStatus.json"),
+    ),
+    (
+        "recentActions",
+        include_str!("fixtures/api/recentActions.json"),
+    ),
+    (
+        "user.blogEntries",
+        include_str!("fixtures/api/user.blogEntries.json"),
+    ),
+    (
+        "user.friends",
+        include_str!("fixtures/api/user.friends.json"),
+    ),
+    ("user.info", include_str!("fixtures/api/user.info.json")),
+    (
+        "user.ratedList",
+        include_str!("fixtures/api/user.ratedList.json"),
+    ),
+    ("user.rating", include_str!("fixtures/api/user.rating.json")),
+    ("user.status", include_str!("fixtures/api/user.status.json")),
+];
+
+static PROBLEM_FIXTURES: &[(&str, &str)] = &[
+    ("1A", include_str!("fixtures/problem/1A.html")),
+    ("1B", include_str!("fixtures/problem/1B.html")),
+    ("1C", include_str!("fixtures/problem/1C.html")),
+];
+
+static PROBLEMS_FIXTURES: &[(&str, &str)] = &[("1", include_str!("fixtures/problems/1.html"))];
+
+static IMAGE_FIXTURES: &[(&str, &[u8])] = &[("1C.png", include_bytes!("fixtures/image/1C.png"))];
+
+/// Source of the responses served by [`MockServer`].
+///
+/// Fixtures are looked up in `dir` first (as `api/{method}.json`,
+/// `problem/{contest_id}{index}.html`, `problems/{contest_id}.html` and `image/{name}`), then
+/// in the fixtures built into the binary.
+#[derive(Debug, Clone, Default)]
+pub struct Fixtures {
+    dir: Option<PathBuf>,
+}
+
+impl Fixtures {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_dir(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: Some(dir.into()),
+        }
+    }
+
+    fn lookup(&self, path: String, builtin: &[(&str, &str)], key: &str) -> Option<String> {
+        if let Some(dir) = &self.dir {
+            if let Ok(content) = fs::read_to_string(dir.join(path)) {
+                return Some(content);
+            }
+        }
+        builtin
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, content)| content.to_string())
+    }
+
+    /// Returns the recorded response of the API method, e.g. `contest.list`.
+    pub fn api(&self, method: &str) -> Option<String> {
+        self.lookup(format!("api/{method}.json"), API_FIXTURES, method)
+    }
+
+    /// Returns the recorded page of the problem, e.g. contest `1` problem `A`.
+    pub fn problem(&self, contest_id: &str, index: &str) -> Option<String> {
+        let key = format!("{contest_id}{index}");
+        self.lookup(format!("problem/{key}.html"), PROBLEM_FIXTURES, &key)
+    }
+
+    /// Returns the recorded page of all problems of the contest, e.g. contest `1`.
+    pub fn problems(&self, contest_id: &str) -> Option<String> {
+        self.lookup(
+            format!("problems/{contest_id}.html"),
+            PROBLEMS_FIXTURES,
+            contest_id,
+        )
+    }
+
+    /// Returns the image referenced by statements as `/predownloaded/{name}`.
+    pub fn image(&self, name: &str) -> Option<Vec<u8>> {
+        if let Some(dir) = &self.dir {
+            if let Ok(content) = fs::read(dir.join("image").join(name)) {
+                return Some(content);
+            }
+        }
+        IMAGE_FIXTURES
+            .iter()
+            .find(|(image, _)| *image == name)
+            .map(|(_, content)| content.to_vec())
+    }
+}
+
+/// The number of requests made to every path, to tell how many requests something takes.
+#[derive(Debug, Clone, Default)]
+pub struct RequestCounter {
+    counts: Arc<Mutex<HashMap<String, usize>>>,
+}
+
+impl RequestCounter {
+    fn add(&self, path: &str) {
+        if let Ok(mut counts) = self.counts.lock() {
+            *counts.entry(path.to_string()).or_default() += 1;
+        }
+    }
+
+    /// Returns the number of requests made to `path` so far, e.g. `/contest/1/problems`.
+    pub fn count(&self, path: &str) -> usize {
+        self.counts
+            .lock()
+            .ok()
+            .and_then(|counts| counts.get(path).copied())
+            .unwrap_or_default()
+    }
+}
+
+fn respond(status: StatusCode, content_type: &str, body: impl Into<Body>) -> Response<Body> {
+    let mut response = Response::new(body.into());
+    *response.status_mut() = status;
+    if let Ok(content_type) = content_type.parse() {
+        response.headers_mut().insert(CONTENT_TYPE, content_type);
+    }
+    // Every test runs in its own runtime, so pooled connections must not outlive a request.
+    if let Ok(close) = "close".parse() {
+        response.headers_mut().insert(CONNECTION, close);
+    }
+    response
+}
+
+fn query_parameter<'a>(query: Option<&'a str>, key: &str) -> Option<&'a str> {
+    query?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(name, _)| *name == key)
+        .map(|(_, value)| value)
+}
+
+/// Applies the 1-based `from` and `count` parameters to a list result, like the API does.
+fn paginate(json: String, query: Option<&str>) -> String {
+    let parameter = |key: &str| -> Option<usize> { query_parameter(query, key)?.parse().ok() };
+    if parameter("from").is_none() && parameter("count").is_none() {
+        return json;
+    }
+    let mut response: serde_json::Value = match serde_json::from_str(&json) {
+        Ok(response) => response,
+        Err(_) => return json,
+    };
+    if let Some(serde_json::Value::Array(items)) = response.get_mut("result") {
+        let from = parameter("from").unwrap_or(1).max(1) - 1;
+        let count = parameter("count").unwrap_or(usize::MAX);
+        *items = items.drain(..).skip(from).take(count).collect();
+    }
+    response.to_string()
+}
+
+fn route(fixtures: &Fixtures, web: &Mutex<WebState>, parts: &Parts, body: &[u8]) -> Response<Body> {
+    if let Ok(mut web) = web.lock() {
+        if let Some(response) = web.route(fixtures, parts, body) {
+            return response;
+        }
+    }
+    let uri = &parts.uri;
+    let segments: Vec<&str> = uri.path().split('/').filter(|s| !s.is_empty()).collect();
+    let problem = match segments.as_slice() {
+        ["api", method] => {
+            return match fixtures.api(method) {
+                Some(json) => {
+                    let json = match (*method, web.lock()) {
+                        ("contest.status" | "user.status", Ok(mut web)) => {
+                            web.add_submissions(fixtures, json, uri.query())
+                        }
+                        _ => json,
+                    };
+                    respond(
+                        StatusCode::OK,
+                        "application/json",
+                        paginate(json, uri.query()),
+                    )
+                }
+                None => respond(
+                    StatusCode::BAD_REQUEST,
+                    "application/json",
+                    format!(
+                        r#"{{"status":"FAILED","comment":"method: Method {method} not found"}}"#
+                    ),
+                ),
+            };
+        }
+        ["contest", contest_id, "problem", index]
+        | ["gym", contest_id, "problem", index]
+        | ["group", _, "contest", contest_id, "problem", index]
+        | ["problemset", "problem", contest_id, index] => fixtures.problem(contest_id, index),
+        ["contest", contest_id, "problems"]
+        | ["gym", contest_id, "problems"]
+        | ["group", _, "contest", contest_id, "problems"] => fixtures.problems(contest_id),
+        ["predownloaded", name] => {
+            if let Some(image) = fixtures.image(name) {
+                return respond(StatusCode::OK, "image/png", image);
+            }
+            None
+        }
+        _ => None,
+    };
+    match problem {
+        Some(html) => respond(StatusCode::OK, "text/html; charset=utf-8", html),
+        None => respond(
+            StatusCode::NOT_FOUND,
+            "text/html; charset=utf-8",
+            String::from("<html><body>Not Found</body></html>"),
+        ),
+    }
+}
+
+/// Serves `fixtures` on `listener` until `shutdown` resolves, counting the requests in
+/// `requests`.
+///
+/// Must be called within a tokio runtime.
+pub async fn serve(
+    listener: TcpListener,
+    fixtures: Fixtures,
+    requests: RequestCounter,
+    shutdown: impl Future<Output = ()>,
+) -> Result<()> {
+    listener.set_nonblocking(true)?;
+    let fixtures = Arc::new(fixtures);
+    let web = Arc::new(Mutex::new(WebState::default()));
+    let make_service = make_service_fn(move |_| {
+        let fixtures = fixtures.clone();
+        let web = web.clone();
+        let requests = requests.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                let fixtures = fixtures.clone();
+                let web = web.clone();
+                requests.add(request.uri().path());
+                async move {
+                    let (parts, body) = request.into_parts();
+                    let body = hyper::body::to_bytes(body).await.unwrap_or_default();
+                    Ok::<_, Infallible>(route(&fixtures, &web, &parts, &body))
+                }
+            }))
+        }
+    });
+    Server::from_tcp(listener)?
+        .http1_keepalive(false)
+        .serve(make_service)
+        .with_graceful_shutdown(shutdown)
+        .await?;
+    Ok(())
+}
+
+/// A mock Codeforces server running on a background thread.
+///
+/// The server is shut down when this is dropped.
+pub struct MockServer {
+    addr: SocketAddr,
+    requests: RequestCounter,
+    shutdown: Option<oneshot::Sender<()>>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl MockServer {
+    /// Starts a server on a random local port.
+    pub fn start(fixtures: Fixtures) -> Result<Self> {
+        Self::start_on(SocketAddr::from(([127, 0, 0, 1], 0)), fixtures)
+    }
+
+    pub fn start_on(addr: SocketAddr, fixtures: Fixtures) -> Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let (shutdown, shutdown_receiver) = oneshot::channel::<()>();
+        let (error_sender, error_receiver) = mpsc::channel();
+        let requests = RequestCounter::default();
+        let counter = requests.clone();
+        let handle = thread::spawn(move || {
+            let runtime = match tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            {
+                Ok(runtime) => runtime,
+                Err(err) => {
+                    error_sender.send(Some(eyre!(err))).ok();
+                    return;
+                }
+            };
+            error_sender.send(None).ok();
+            let shutdown = async {
+                shutdown_receiver.await.ok();
+            };
+            if let Err(err) = runtime.block_on(serve(listener, fixtures, counter, shutdown)) {
+                eprintln!("Mock server stopped: {err:#}");
+            }
+        });
+        if let Ok(Some(err)) = error_receiver.recv() {
+            return Err(err);
+        }
+        Ok(Self {
+            addr,
+            requests,
+            shutdown: Some(shutdown),
+            handle: Some(handle),
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns the number of requests made to `path` so far, e.g. `/contest/1/problems`.
+    pub fn requests(&self, path: &str) -> usize {
+        self.requests.count(path)
+    }
+
+    /// Returns the URL to pass to [`crate::api::utils::set_base_url`].
+    pub fn base_url(&self) -> String {
+        format!("http://{}/", self.addr)
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            shutdown.send(()).ok();
+        }
+        if let Some(handle) = self.handle.take() {
+            handle.join().ok();
+        }
+    }
+}
@@ -0,0 +1,532 @@
+//! The web pages behind [`crate::api::session`]: logging in and submitting.
+//!
+//! Like Codeforces, every visitor gets a session cookie, and logging in also sets the cookie
+//! remembering the user. Any handle can log in with [`PASSWORD`], and stays logged in while the
+//! server runs, so a session saved by an earlier server looks expired. Accepted submissions are listed on the `my`
+//! pages and returned by `contest.status` and `user.status`, as `TESTING` the first time they
+//! are requested and as `OK` afterwards, so tracking them can be tested.
+
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use hyper::{
+    header::{COOKIE, LOCATION, SET_COOKIE},
+    http::request::Parts,
+    Body, Method, Response, StatusCode,
+};
+
+use super::{query_parameter, respond, Fixtures};
+
+/// The password of every handle.
+pub static PASSWORD: &str = "password";
+
+static CSRF_TOKEN: &str = "0123456789abcdef0123456789abcdef";
+
+static SESSION_COOKIE: &str = "JSESSIONID";
+
+/// The cookie set only by logging in, to remember the user.
+static LOGIN_COOKIE: &str = "X-User-Sha1";
+
+/// Ids of new submissions start from this, above the ids of the recorded ones.
+static FIRST_SUBMISSION_ID: i32 = 200000000;
+
+/// The contest acmsguru problems are submitted to.
+static ACMSGURU_CONTEST_ID: i32 = 99999;
+
+static LANGUAGES: &[(u32, &str)] = &[
+    (43, "GNU GCC C11 5.1.0"),
+    (54, "GNU G++17 7.3.0"),
+    (73, "GNU G++20 11.2.0 (64 bit, winlibs)"),
+    (89, "GNU G++20 13.2 (64 bit, winlibs)"),
+    (91, "GNU G++23 14.2 (64 bit, msys2)"),
+    (79, "C# 10, .NET SDK 6.0"),
+    (32, "Go 1.22.2"),
+    (12, "Haskell GHC 8.10.1"),
+    (60, "Java 11.0.6"),
+    (87, "Java 21 64bit"),
+    (55, "Node.js 15.8.0 (64bit)"),
+    (34, "JavaScript V8 4.8.0"),
+    (83, "Kotlin 1.7.20"),
+    (88, "Kotlin 1.9.21"),
+    (4, "Free Pascal 3.2.2"),
+    (31, "Python 3.8.10"),
+    (70, "PyPy 3.10 (7.3.15, 64bit)"),
+    (67, "Ruby 3.2.2"),
+    (75, "Rust 1.75.0 (2021)"),
+];
+
+#[derive(Debug)]
+struct MockSubmission {
+    id: i32,
+    handle: String,
+    contest_id: i32,
+    index: String,
+    language: String,
+    source: String,
+    creation_time: u64,
+    requested: bool,
+}
+
+/// Submissions made through the mock server.
+#[derive(Debug, Default)]
+pub struct WebState {
+    submissions: Vec<MockSubmission>,
+    /// Handles logged in, by the value of their session cookie.
+    sessions: HashMap<String, String>,
+    /// Visitors given a session cookie without logging in.
+    visitors: usize,
+}
+
+/// Decodes an `application/x-www-form-urlencoded` body or query.
+fn decode_form(form: &str) -> Vec<(String, String)> {
+    let decode = |text: &str| -> String {
+        let bytes = text.as_bytes();
+        let mut decoded = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'+' => decoded.push(b' '),
+                b'%' if i + 2 < bytes.len() => {
+                    let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or_default();
+                    match u8::from_str_radix(hex, 16) {
+                        Ok(byte) => {
+                            decoded.push(byte);
+                            i += 2;
+                        }
+                        Err(_) => decoded.push(b'%'),
+                    }
+                }
+                byte => decoded.push(byte),
+            }
+            i += 1;
+        }
+        String::from_utf8_lossy(&decoded).to_string()
+    };
+    form.split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((name, value)) => (decode(name), decode(value)),
+            None => (decode(pair), String::new()),
+        })
+        .collect()
+}
+
+fn field<'a>(form: &'a [(String, String)], name: &str) -> &'a str {
+    form.iter()
+        .find(|(key, _)| key == name)
+        .map_or("", |(_, value)| value.as_str())
+}
+
+/// Returns the handle logged in with the session cookie of the request.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs())
+}
+
+fn session_cookie(parts: &Parts) -> Option<&str> {
+    let cookies = parts.headers.get(COOKIE)?.to_str().ok()?;
+    cookies
+        .split(';')
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(name, _)| *name == SESSION_COOKIE)
+        .map(|(_, value)| value)
+}
+
+fn set_cookies(response: &mut Response<Body>, cookies: &[String]) {
+    for cookie in cookies {
+        if let Ok(cookie) = cookie.parse() {
+            response.headers_mut().append(SET_COOKIE, cookie);
+        }
+    }
+}
+
+fn redirect(location: &str, cookies: &[String]) -> Response<Body> {
+    let mut response = respond(StatusCode::FOUND, "text/html; charset=utf-8", "");
+    if let Ok(location) = location.parse() {
+        response.headers_mut().insert(LOCATION, location);
+    }
+    set_cookies(&mut response, cookies);
+    response
+}
+
+fn page(handle: Option<&str>, title: &str, content: &str) -> Response<Body> {
+    let header = match handle {
+        Some(handle) => format!(
+            r#"<a href="/profile/{handle}">{handle}</a> | <a href="/{CSRF_TOKEN}/logout">Logout</a>"#
+        ),
+        None => String::from(r#"<a href="/enter">Enter</a> | <a href="/register">Register</a>"#),
+    };
+    let html = format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta http-equiv="Content-Type" content="text/html; charset=utf-8"/>
+    <meta name="X-Csrf-Token" content="{CSRF_TOKEN}"/>
+    <title>{title} - Codeforces</title>
+</head>
+<body>
+<div id="header"><div class="lang-chooser">{header}</div></div>
+<div id="body">
+{content}
+</div>
+</body>
+</html>
+"#
+    );
+    respond(StatusCode::OK, "text/html; charset=utf-8", html)
+}
+
+fn login_page(error: &str) -> Response<Body> {
+    let content = format!(
+        r#"<form method="post" action="/enter" id="enterForm">
+<input type="hidden" name="csrf_token" value="{CSRF_TOKEN}"/>
+<input type="hidden" name="action" value="enter"/>
+<input name="handleOrEmail" id="handleOrEmail"/>
+<input name="password" type="password" id="password"/>
+<span class="error for__password">{error}</span>
+</form>"#
+    );
+    page(None, "Enter", &content)
+}
+
+fn submit_page(handle: &str, action: &str) -> Response<Body> {
+    let options: String = LANGUAGES
+        .iter()
+        .map(|(id, name)| format!("<option value=\"{id}\">{name}</option>\n"))
+        .collect();
+    let content = format!(
+        r#"<form class="submit-form" method="post" action="{action}?csrf_token={CSRF_TOKEN}">
+<input type="hidden" name="csrf_token" value="{CSRF_TOKEN}"/>
+<input type="hidden" name="action" value="submitSolutionFormSubmitted"/>
+<select name="programTypeId">
+{options}</select>
+<textarea name="source"></textarea>
+</form>"#
+    );
+    page(Some(handle), "Submit", &content)
+}
+
+fn submit_error(handle: &str, action: &str, field: &str, error: &str) -> Response<Body> {
+    let content = format!(
+        r#"<form class="submit-form" method="post" action="{action}?csrf_token={CSRF_TOKEN}">
+<span class="error for__{field}">{error}</span>
+</form>"#
+    );
+    page(Some(handle), "Submit", &content)
+}
+
+impl WebState {
+    fn session_handle(&self, parts: &Parts) -> Option<String> {
+        self.sessions.get(session_cookie(parts)?).cloned()
+    }
+
+    fn login(&mut self, form: &[(String, String)]) -> Response<Body> {
+        let handle = field(form, "handleOrEmail");
+        let valid = field(form, "csrf_token") == CSRF_TOKEN
+            && !field(form, "ftaa").is_empty()
+            && !field(form, "bfaa").is_empty()
+            && !handle.is_empty()
+            && field(form, "password") == PASSWORD;
+        if !valid {
+            return login_page("Invalid handle/email or password");
+        }
+        let session = format!("mock-{}-{}", self.sessions.len() + 1, now());
+        self.sessions.insert(session.clone(), handle.to_string());
+        redirect(
+            "/",
+            &[
+                format!("{SESSION_COOKIE}={session}; Path=/"),
+                format!("{LOGIN_COOKIE}={session}; Path=/; Max-Age=2592000"),
+            ],
+        )
+    }
+
+    /// Accepts a submission to `problem`, given as contest id and index, and redirects to `my`.
+    fn submit(
+        &mut self,
+        fixtures: &Fixtures,
+        handle: &str,
+        action: &str,
+        problem: Option<(i32, String)>,
+        my: &str,
+        form: &[(String, String)],
+    ) -> Response<Body> {
+        if field(form, "csrf_token") != CSRF_TOKEN
+            || field(form, "action") != "submitSolutionFormSubmitted"
+        {
+            return respond(
+                StatusCode::FORBIDDEN,
+                "text/html; charset=utf-8",
+                "<html><body>Forbidden</body></html>",
+            );
+        }
+        let (contest_id, index) = match problem {
+            Some((contest_id, index))
+                if fixtures.problem(&contest_id.to_string(), &index).is_some() =>
+            {
+                (contest_id, index)
+            }
+            _ => {
+                return submit_error(
+                    handle,
+                    action,
+                    "submittedProblemIndex",
+                    "Choose valid problem",
+                )
+            }
+        };
+        let language = match LANGUAGES
+            .iter()
+            .find(|(id, _)| id.to_string() == field(form, "programTypeId"))
+        {
+            Some((_, language)) => language.to_string(),
+            None => return submit_error(handle, action, "programTypeId", "Choose valid language"),
+        };
+        let source = field(form, "source");
+        if source.trim().is_empty() {
+            return submit_error(handle, action, "source", "Source should not be empty");
+        }
+        if self
+            .submissions
+            .iter()
+            .any(|submission| submission.handle == handle && submission.source == source)
+        {
+            return submit_error(
+                handle,
+                action,
+                "source",
+                "You have submitted exactly the same code before",
+            );
+        }
+        let id = FIRST_SUBMISSION_ID + self.submissions.len() as i32;
+        self.submissions.push(MockSubmission {
+            id,
+            handle: handle.to_string(),
+            contest_id,
+            index,
+            language,
+            source: source.to_string(),
+            creation_time: now(),
+            requested: false,
+        });
+        redirect(my, &[])
+    }
+
+    fn my_page(&self, handle: &str, contest_id: Option<i32>) -> Response<Body> {
+        let rows: String = self
+            .submissions
+            .iter()
+            .rev()
+            .filter(|submission| submission.handle == handle)
+            .filter(|submission| contest_id.is_none_or(|id| submission.contest_id == id))
+            .map(|submission| {
+                format!(
+                    "<tr data-submission-id=\"{}\"><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    submission.id, submission.id, submission.index, submission.language
+                )
+            })
+            .collect();
+        let content = format!("<table class=\"status-frame-datatable\">\n{rows}</table>");
+        page(Some(handle), "My Submissions", &content)
+    }
+
+    /// Adds the submissions made through the server to the recorded `contest.status` or
+    /// `user.status` response, newest first.
+    pub fn add_submissions(
+        &mut self,
+        fixtures: &Fixtures,
+        json: String,
+        query: Option<&str>,
+    ) -> String {
+        if self.submissions.is_empty() {
+            return json;
+        }
+        let mut response: serde_json::Value = match serde_json::from_str(&json) {
+            Ok(response) => response,
+            Err(_) => return json,
+        };
+        let template = match response.pointer("/result/0") {
+            Some(template) => template.clone(),
+            None => return json,
+        };
+        let contest_id: Option<i32> =
+            query_parameter(query, "contestId").and_then(|id| id.parse().ok());
+        let handle = query_parameter(query, "handle");
+        let problems: Vec<serde_json::Value> = fixtures
+            .api("contest.standings")
+            .and_then(|standings| serde_json::from_str::<serde_json::Value>(&standings).ok())
+            .and_then(|standings| standings.pointer("/result/problems").cloned())
+            .and_then(|problems| problems.as_array().cloned())
+            .unwrap_or_default();
+        let submissions: Vec<serde_json::Value> = self
+            .submissions
+            .iter_mut()
+            .rev()
+            .filter(|submission| contest_id.is_none_or(|id| submission.contest_id == id))
+            .filter(|submission| handle.is_none_or(|handle| submission.handle == handle))
+            .map(|submission| {
+                let mut json = template.clone();
+                let problem = problems
+                    .iter()
+                    .find(|problem| problem["index"] == submission.index.as_str())
+                    .cloned()
+                    .unwrap_or_else(|| template["problem"].clone());
+                json["id"] = submission.id.into();
+                json["contestId"] = submission.contest_id.into();
+                json["creationTimeSeconds"] = submission.creation_time.into();
+                json["problem"] = problem;
+                json["problem"]["contestId"] = submission.contest_id.into();
+                json["problem"]["index"] = submission.index.as_str().into();
+                json["author"]["contestId"] = submission.contest_id.into();
+                json["author"]["members"] = serde_json::json!([{ "handle": submission.handle }]);
+                json["programmingLanguage"] = submission.language.as_str().into();
+                json["verdict"] = if submission.requested {
+                    "OK"
+                } else {
+                    "TESTING"
+                }
+                .into();
+                json["passedTestCount"] = if submission.requested { 10 } else { 0 }.into();
+                submission.requested = true;
+                json
+            })
+            .collect();
+        if let Some(serde_json::Value::Array(results)) = response.get_mut("result") {
+            results.splice(0..0, submissions);
+        }
+        response.to_string()
+    }
+
+    /// Serves the web pages of the session, returning `None` for other paths.
+    pub fn route(
+        &mut self,
+        fixtures: &Fixtures,
+        parts: &Parts,
+        body: &[u8],
+    ) -> Option<Response<Body>> {
+        let path = parts.uri.path();
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        let handle = self.session_handle(parts);
+        let visitor = session_cookie(parts).is_none();
+        let form = decode_form(&String::from_utf8_lossy(body));
+        let mut response = match (&parts.method, segments.as_slice()) {
+            (&Method::GET, []) => page(handle.as_deref(), "Codeforces", ""),
+            (&Method::GET, ["enter"]) => login_page(""),
+            (&Method::POST, ["enter"]) => self.login(&form),
+            (&Method::GET, [_, "logout"]) => {
+                if let Some(session) = session_cookie(parts) {
+                    self.sessions.remove(session);
+                }
+                redirect(
+                    "/",
+                    &[
+                        format!("{SESSION_COOKIE}=; Path=/; Max-Age=0"),
+                        format!("{LOGIN_COOKIE}=; Path=/; Max-Age=0"),
+                    ],
+                )
+            }
+            (method, ["contest", id, "submit", ..])
+            | (method, ["gym", id, "submit", ..])
+            | (method, ["group", _, "contest", id, "submit", ..]) => {
+                let contest_id = id.parse().ok()?;
+                let action = &path[..path.find("/submit")? + "/submit".len()];
+                let my = format!("{}/my", &path[..path.find("/submit")?]);
+                let problem = (
+                    contest_id,
+                    field(&form, "submittedProblemIndex").to_string(),
+                );
+                match (method, handle) {
+                    (_, None) => redirect("/enter", &[]),
+                    (&Method::POST, Some(handle)) => {
+                        self.submit(fixtures, &handle, action, Some(problem), &my, &form)
+                    }
+                    (_, Some(handle)) => submit_page(&handle, action),
+                }
+            }
+            (method, ["problemset", "submit", ..]) => {
+                let action = "/problemset/submit";
+                // Problems are given as contest id followed by index, like `1A`.
+                let code = field(&form, "submittedProblemCode");
+                let split = code
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(code.len());
+                let problem = code[..split]
+                    .parse()
+                    .ok()
+                    .map(|contest_id| (contest_id, code[split..].to_string()));
+                match (method, handle) {
+                    (_, None) => redirect("/enter", &[]),
+                    (&Method::POST, Some(handle)) => self.submit(
+                        fixtures,
+                        &handle,
+                        action,
+                        problem,
+                        "/problemset/status?my=on",
+                        &form,
+                    ),
+                    (_, Some(handle)) => submit_page(&handle, action),
+                }
+            }
+            (method, ["problemsets", "acmsguru", "submit", ..]) => {
+                let action = "/problemsets/acmsguru/submit/99999";
+                let problem = (
+                    ACMSGURU_CONTEST_ID,
+                    field(&form, "submittedProblemCode").to_string(),
+                );
+                match (method, handle) {
+                    (_, None) => redirect("/enter", &[]),
+                    (&Method::POST, Some(handle)) => self.submit(
+                        fixtures,
+                        &handle,
+                        action,
+                        Some(problem),
+                        "/problemsets/acmsguru/status?my=on",
+                        &form,
+                    ),
+                    (_, Some(handle)) => submit_page(&handle, action),
+                }
+            }
+            (&Method::GET, ["contest", id, "my"])
+            | (&Method::GET, ["gym", id, "my"])
+            | (&Method::GET, ["group", _, "contest", id, "my"]) => match handle {
+                Some(handle) => self.my_page(&handle, id.parse().ok()),
+                None => redirect("/enter", &[]),
+            },
+            (&Method::GET, ["problemset", "status"])
+            | (&Method::GET, ["problemsets", "acmsguru", "status"]) => match handle {
+                Some(handle) => self.my_page(&handle, None),
+                None => redirect("/enter", &[]),
+            },
+            _ => return None,
+        };
+        if visitor && !response.headers().contains_key(SET_COOKIE) {
+            self.visitors += 1;
+            let session = format!("visitor-{}-{}", self.visitors, now());
+            set_cookies(
+                &mut response,
+                &[format!("{SESSION_COOKIE}={session}; Path=/")],
+            );
+        }
+        Some(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forms() {
+        assert_eq!(
+            decode_form("a=1+2&b=%3D%26&c"),
+            vec![
+                (String::from("a"), String::from("1 2")),
+                (String::from("b"), String::from("=&")),
+                (String::from("c"), String::new()),
+            ]
+        );
+    }
+}
@@ -1,8 +1,13 @@
 use std::{
-    collections::HashMap,
-    fs::{DirBuilder, OpenOptions},
-    io::ErrorKind,
-    path::PathBuf,
+    collections::{BTreeMap, HashMap},
+    env,
+    fmt::Display,
+    fs::{self, DirBuilder, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    thread,
+    time::{Duration, SystemTime},
 };
 
 use color_eyre::{
@@ -13,34 +18,30 @@ use config::{Config, File, FileFormat};
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 
+use crate::credentials::{get_credentials_file_path, load_credentials, CREDENTIALS};
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct CFTemplate {
     pub alias: String,
     pub lang: String,
     pub path: PathBuf,
+    /// The language codes generated from this template are submitted in.
+    pub program_type_id: Option<u32>,
 }
 
-impl From<CFCommand> for CFScripts {
-    fn from(command: CFCommand) -> Self {
-        Self {
-            before_script: Some(command.before_command),
-            script: command.command,
-            after_script: Some(command.after_command),
-            open_script: Some(command.open_command),
+impl CFTemplate {
+    /// Returns the path of the template code, a relative path being in `config_dir/templates`.
+    pub fn full_path(&self) -> Result<PathBuf> {
+        match self.path.is_absolute() {
+            true => Ok(self.path.clone()),
+            false => Ok(get_config_file_path()?
+                .with_file_name("templates")
+                .join(&self.path)),
         }
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct CFCommand {
-    pub ext: String,
-    pub before_command: String,
-    pub command: String,
-    pub after_command: String,
-    pub open_command: String,
-}
-
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
 pub struct CFScripts {
     pub before_script: Option<String>,
     pub script: String,
@@ -48,35 +49,647 @@ pub struct CFScripts {
     pub open_script: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+/// How requests to Codeforces are made.
+///
+/// The client is built once, so changing anything but the URLs takes a restart.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct CFHttp {
+    /// A proxy for every request, e.g. `http://127.0.0.1:7890` or `socks5://127.0.0.1:1080`.
+    pub proxy: Option<String>,
+    /// Seconds to wait for a connection to be made.
+    pub connect_timeout: Option<u64>,
+    /// Seconds to wait for a whole response to be read.
+    pub read_timeout: Option<u64>,
+    pub user_agent: Option<String>,
+    /// The website to use instead of `https://codeforces.com/`, e.g. a mirror.
+    pub base_url: Option<String>,
+    /// The API to use instead of `api/` of `base_url`.
+    pub api_base_url: Option<String>,
+}
+
+/// The keys of the TUI.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct CFKeybindings {
+    /// The keys to start from, `vim` or `emacs`.
+    pub preset: Option<String>,
+    /// Keys of actions replacing those of the preset, e.g. `"submit": ["s", "ctrl-s"]`.
+    pub keys: Option<HashMap<String, Vec<String>>>,
+}
+
+/// The colors of the TUI, or of a user theme in `themes`.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct CFTheme {
+    /// The theme to start from, a built-in one like `dark` or `light`, or for `theme` also a
+    /// user theme.
+    pub preset: Option<String>,
+    /// Colors replacing those of the preset, e.g. `"accepted": "#00aa00"`.
+    pub colors: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
 pub struct CFSettings {
+    /// The version of the configuration, see [`CONFIG_VERSION`].
+    pub version: Option<u32>,
     pub username: Option<String>,
     pub key: Option<String>,
     pub secret: Option<String>,
+    pub password: Option<String>,
+    /// A command printing the API key, e.g. `pass show cf/key`.
+    pub key_command: Option<String>,
+    /// A command printing the API secret.
+    pub secret_command: Option<String>,
+    /// A command printing the password.
+    pub password_command: Option<String>,
     pub templates: Option<Vec<CFTemplate>>,
     pub commands: Option<HashMap<String, CFScripts>>,
+    /// The language codes of each extension are submitted in, by `programTypeId`.
+    pub languages: Option<HashMap<String, u32>>,
     pub home_dir: Option<PathBuf>,
+    pub http: Option<CFHttp>,
+    pub keybindings: Option<CFKeybindings>,
+    pub theme: Option<CFTheme>,
+    /// User themes by name, which `theme` can start from.
+    pub themes: Option<HashMap<String, CFTheme>>,
+}
+
+/// A value shared by the running app and replaced as a whole, so every reader sees either the
+/// old value or the new one.
+pub struct Shared<T> {
+    current: RwLock<Arc<T>>,
+}
+
+impl<T> Shared<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            current: RwLock::new(Arc::new(value)),
+        }
+    }
+
+    /// Returns the current value, which stays the same for the caller even if it is replaced.
+    pub fn get(&self) -> Arc<T> {
+        match self.current.read() {
+            Ok(current) => current.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    pub fn set(&self, value: T) {
+        let value = Arc::new(value);
+        match self.current.write() {
+            Ok(mut current) => *current = value,
+            Err(poisoned) => *poisoned.into_inner() = value,
+        }
+    }
 }
 
 lazy_static! {
-    pub static ref SETTINGS: CFSettings = load_settings().unwrap();
+    /// The settings of the running app, which are the defaults if they cannot be loaded.
+    /// [`init_settings`] reports why at startup instead.
+    pub static ref SETTINGS: Shared<CFSettings> =
+        Shared::new(load_settings().unwrap_or_else(|err| {
+            tracing::warn!("Using the default settings: {err:#}");
+            CFSettings::default()
+        }));
+    /// The `http` settings [`CLIENT`] is built with, which reloading the settings does not change.
+    ///
+    /// [`CLIENT`]: crate::api::methods::CLIENT
+    pub static ref CLIENT_HTTP: CFHttp = SETTINGS.get().http.clone().unwrap_or_default();
 }
 
-pub fn load_settings() -> Result<CFSettings> {
+/// Returns whether the `http` settings have changed since [`CLIENT_HTTP`], which takes a restart.
+/// The URLs are read for every request, so they do not count.
+pub fn client_needs_restart() -> bool {
+    let without_urls = |http: &CFHttp| CFHttp {
+        base_url: None,
+        api_base_url: None,
+        ..http.clone()
+    };
+    let current = SETTINGS.get().http.clone().unwrap_or_default();
+    without_urls(&current) != without_urls(&CLIENT_HTTP)
+}
+
+/// The configuration file of a workspace, found by walking up from the current directory.
+pub static WORKSPACE_CONFIG_FILE_NAME: &str = ".cf.json";
+
+lazy_static! {
+    static ref SETTING_OVERRIDES: RwLock<Vec<(String, serde_json::Value)>> = RwLock::new(vec![]);
+}
+
+/// Sets the `KEY=VALUE` settings given on the command line, which take precedence over every
+/// other source.
+///
+/// Must be called before [`SETTINGS`] is first used.
+pub fn set_overrides<'a>(overrides: impl IntoIterator<Item = &'a String>) -> Result<()> {
+    let overrides = overrides
+        .into_iter()
+        .map(|setting| {
+            let (key, text) = setting
+                .split_once('=')
+                .ok_or(eyre!("Invalid setting `{setting}`, expected KEY=VALUE"))?;
+            let key = key.trim();
+            if !is_known_setting(key) {
+                bail!("Unknown setting `{key}`");
+            }
+            Ok((key.to_string(), parse_value(text)?))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if let Ok(mut current) = SETTING_OVERRIDES.write() {
+        *current = overrides;
+    }
+    Ok(())
+}
+
+/// Where a setting comes from, from the lowest precedence to the highest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SettingOrigin {
+    Global(PathBuf),
+    Workspace(PathBuf),
+    Environment(String),
+    CommandLine,
+}
+
+impl Display for SettingOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Global(path) => write!(f, "{}", path.display()),
+            Self::Workspace(path) => write!(f, "{}", path.display()),
+            Self::Environment(var) => write!(f, "environment variable {var}"),
+            Self::CommandLine => write!(f, "command line"),
+        }
+    }
+}
+
+/// The settings merged from every source.
+#[derive(Debug)]
+pub struct LayeredSettings {
+    pub settings: CFSettings,
+    /// The merged configuration as it would be written to `cf.json`.
+    pub value: serde_json::Value,
+    /// Where each setting comes from, by its dotted key, e.g. `http.proxy`.
+    pub origins: BTreeMap<String, SettingOrigin>,
+}
+
+/// Merges `source` into `target`, tables item by item and everything else as a whole.
+fn merge(
+    target: &mut serde_json::Value,
+    source: serde_json::Value,
+    key: &str,
+    origin: &SettingOrigin,
+    origins: &mut BTreeMap<String, SettingOrigin>,
+) {
+    use serde_json::Value;
+    let child = |name: &str| match key {
+        "" => name.to_string(),
+        key => format!("{key}.{name}"),
+    };
+    match source {
+        // Unset items, e.g. `"password": null`, leave the settings below as they are.
+        Value::Null => {}
+        Value::Object(source) if !source.is_empty() => {
+            if !target.is_object() {
+                origins.remove(key);
+                *target = Value::Object(Default::default());
+            }
+            if let Value::Object(target) = target {
+                for (name, value) in source {
+                    merge(
+                        target.entry(name.clone()).or_insert(Value::Null),
+                        value,
+                        &child(&name),
+                        origin,
+                        origins,
+                    );
+                }
+            }
+        }
+        source => {
+            let prefix = format!("{key}.");
+            origins.retain(|name, _| name != key && !name.starts_with(&prefix));
+            origins.insert(key.to_string(), origin.clone());
+            *target = source;
+        }
+    }
+}
+
+/// Builds `{"a": {"b": value}}` from the key `a.b`.
+fn nest(key: &str, value: serde_json::Value) -> serde_json::Value {
+    key.rsplit('.').fold(value, |value, name| {
+        serde_json::Value::Object(serde_json::Map::from_iter([(name.to_string(), value)]))
+    })
+}
+
+/// Reads a value given as text, which is JSON if it is an array or a table.
+fn parse_value(text: &str) -> Result<serde_json::Value> {
+    match text.trim_start().starts_with(['[', '{']) {
+        true => serde_json::from_str(text).wrap_err(format!("Invalid JSON `{text}`")),
+        false => Ok(serde_json::Value::String(text.to_string())),
+    }
+}
+
+/// Returns the tables of the settings with their fields, a field named `*` standing for any key of
+/// a map.
+fn settings_schema() -> serde_json::Value {
+    let mut schema = serde_json::to_value(CFSettings::default()).unwrap_or_default();
+    let mut theme = serde_json::to_value(CFTheme::default()).unwrap_or_default();
+    theme["colors"] = serde_json::json!({ "*": null });
+    let mut keybindings = serde_json::to_value(CFKeybindings::default()).unwrap_or_default();
+    keybindings["keys"] = serde_json::json!({ "*": null });
+    schema["http"] = serde_json::to_value(CFHttp::default()).unwrap_or_default();
+    schema["keybindings"] = keybindings;
+    schema["themes"] = serde_json::json!({ "*": theme.clone() });
+    schema["theme"] = theme;
+    schema["commands"] =
+        serde_json::json!({ "*": serde_json::to_value(CFScripts::default()).unwrap_or_default() });
+    schema["languages"] = serde_json::json!({ "*": null });
+    schema
+}
+
+/// Returns whether every name of the dotted `key` is a field of the settings, e.g. `http.proxy`
+/// or `commands.py.script`.
+pub fn is_known_setting(key: &str) -> bool {
+    let schema = settings_schema();
+    key.split('.')
+        .try_fold(&schema, |table, name| match table {
+            serde_json::Value::Object(fields) if !name.is_empty() => {
+                fields.get(name).or_else(|| fields.get("*"))
+            }
+            _ => None,
+        })
+        .is_some()
+}
+
+/// Returns the settings of `CF_*` environment variables, e.g. `CF_HTTP__PROXY` for `http.proxy`.
+fn environment_settings(
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> Vec<(String, String, String)> {
+    let mut settings: Vec<_> = vars
+        .into_iter()
+        .filter_map(|(var, value)| {
+            let key = var.strip_prefix("CF_")?.to_lowercase().replace("__", ".");
+            is_known_setting(&key).then_some((var, key, value))
+        })
+        .collect();
+    settings.sort();
+    settings
+}
+
+/// The version of the configuration this cf-tool writes.
+pub static CONFIG_VERSION: u32 = 1;
+
+/// Upgrades of the configuration, the one at index `n` from version `n` to `n + 1`.
+///
+/// A configuration without `version` is of version 0.
+static MIGRATIONS: &[fn(&mut serde_json::Map<String, serde_json::Value>)] = &[migrate_commands];
+
+/// Version 1 names the scripts of `commands` as `CFScripts` does, where `cf-tui config` used to
+/// take them as `before_command`, `command`, `after_command` and `open_command` of an `ext`.
+fn migrate_commands(config: &mut serde_json::Map<String, serde_json::Value>) {
+    let Some(serde_json::Value::Object(commands)) = config.get_mut("commands") else {
+        return;
+    };
+    for scripts in commands.values_mut() {
+        let Some(scripts) = scripts.as_object_mut() else {
+            continue;
+        };
+        scripts.remove("ext");
+        for (old, new) in [
+            ("before_command", "before_script"),
+            ("command", "script"),
+            ("after_command", "after_script"),
+            ("open_command", "open_script"),
+        ] {
+            if let Some(script) = scripts.remove(old) {
+                scripts.entry(new).or_insert(script);
+            }
+        }
+    }
+}
+
+/// Upgrades `config` to [`CONFIG_VERSION`], returning the version it was of.
+pub fn migrate(config: &mut serde_json::Value) -> Result<u32> {
+    let Some(config) = config.as_object_mut() else {
+        bail!("The configuration is not a table");
+    };
+    let version = match config.get("version") {
+        None | Some(serde_json::Value::Null) => 0,
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or(eyre!("Invalid version {version}"))?,
+    };
+    if version > CONFIG_VERSION {
+        bail!("The configuration is of version {version}, newer than the version {CONFIG_VERSION} this cf-tool supports.\nPlease upgrade cf-tool.");
+    }
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(config);
+    }
+    config.insert(String::from("version"), CONFIG_VERSION.into());
+    Ok(version)
+}
+
+/// Reads the configuration file at `path`, upgrading it to [`CONFIG_VERSION`] in memory if it is older.
+///
+/// With `write_back`, an upgraded file is also written back, keeping the old one next to it as a
+/// backup. This is best-effort: failing to do so is logged and the upgraded configuration is used.
+fn read_json(path: &Path, write_back: bool) -> Result<serde_json::Value> {
+    let content = fs::read_to_string(path).wrap_err(format!(
+        "Failed when reading configuration from {}",
+        path.display()
+    ))?;
+    let mut config: serde_json::Value =
+        serde_json::from_str(&content).wrap_err(format!("Failed to parse {}", path.display()))?;
+    let version = migrate(&mut config).wrap_err(format!("Failed to upgrade {}", path.display()))?;
+    if write_back && version < CONFIG_VERSION {
+        if let Err(err) = write_upgraded(path, version, &config) {
+            tracing::warn!("{err:?}");
+        }
+    }
+    Ok(config)
+}
+
+/// Writes the upgraded `config` back to `path`, keeping the file of `version` as a backup.
+fn write_upgraded(path: &Path, version: u32, config: &serde_json::Value) -> Result<()> {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(format!(".v{version}.bak"));
+    let backup = PathBuf::from(backup);
+    // Copying keeps the permissions of files holding credentials.
+    fs::copy(path, &backup).wrap_err(format!("Failed to back up {}", path.display()))?;
+    fs::write(path, serde_json::to_string_pretty(config)?)
+        .wrap_err(format!("Error occured when writing to {}", path.display()))?;
+    tracing::info!(
+        "Upgraded {} to version {CONFIG_VERSION}, keeping the old one at {}",
+        path.display(),
+        backup.display()
+    );
+    Ok(())
+}
+
+/// Deserializes settings from a configuration merged from every source.
+pub fn settings_from_value(value: &serde_json::Value) -> Result<CFSettings> {
+    let config = Config::builder()
+        .add_source(File::from_str(&value.to_string(), FileFormat::Json))
+        .build()
+        .wrap_err("Failed to build config")?;
+    deserialize_config_into_settings(config)
+}
+
+/// Returns the paths a workspace configuration file of `dir` can have, closest first, whether
+/// they exist or not.
+fn workspace_config_file_paths(dir: &Path) -> impl Iterator<Item = PathBuf> + '_ {
+    dir.ancestors()
+        .map(|dir| dir.join(WORKSPACE_CONFIG_FILE_NAME))
+}
+
+/// Returns the workspace configuration file in `dir` or its closest parent.
+pub fn find_workspace_config_file(dir: &Path) -> Option<PathBuf> {
+    workspace_config_file_paths(dir).find(|path| path.is_file())
+}
+
+/// Loads the settings of the global configuration file, then a workspace configuration file,
+/// then `CF_*` environment variables, then the settings given on the command line.
+pub fn load_layered_settings() -> Result<LayeredSettings> {
+    let mut value = serde_json::Value::Object(Default::default());
+    let mut origins = BTreeMap::new();
+
     let config_file_path = get_config_file_path()?;
-    let file = OpenOptions::new().read(true).open(&config_file_path);
-    match file {
-        Err(err) => {
-            match err.kind() {
-                ErrorKind::NotFound => bail!("No configuration file found at {}.\nPlease add a configuration file or run `cf-tui config`.", config_file_path.display()),
-                _ => return Err(err).wrap_err(format!("Failed when reading configuration from {}", config_file_path.display())),
+    let workspace_file_path = env::current_dir()
+        .ok()
+        .and_then(|dir| find_workspace_config_file(&dir));
+    if !config_file_path.is_file() && workspace_file_path.is_none() {
+        bail!("No configuration file found at {}.\nPlease add a configuration file or run `cf-tui config`.", config_file_path.display());
+    }
+    if config_file_path.is_file() {
+        let origin = SettingOrigin::Global(config_file_path.clone());
+        merge(
+            &mut value,
+            read_json(&config_file_path, true)?,
+            "",
+            &origin,
+            &mut origins,
+        );
+    }
+    if let Some(path) = workspace_file_path {
+        let mut workspace = read_json(&path, false)?;
+        // A relative home_dir is relative to the workspace, which can be moved around.
+        if let (Some(home_dir), Some(dir)) = (workspace.get_mut("home_dir"), path.parent()) {
+            if let Some(relative) = home_dir.as_str().filter(|dir| Path::new(dir).is_relative()) {
+                *home_dir = serde_json::Value::String(dir.join(relative).display().to_string());
             }
         }
-        _ => drop(file),
+        merge(
+            &mut value,
+            workspace,
+            "",
+            &SettingOrigin::Workspace(path),
+            &mut origins,
+        );
     }
-    let config = get_config(config_file_path)?;
-    let settings = deserialize_config_into_settings(config)?;
-    Ok(settings)
+    for (var, key, text) in environment_settings(env::vars()) {
+        let origin = SettingOrigin::Environment(var);
+        merge(
+            &mut value,
+            nest(&key, parse_value(&text)?),
+            "",
+            &origin,
+            &mut origins,
+        );
+    }
+    let overrides = SETTING_OVERRIDES
+        .read()
+        .map(|overrides| overrides.clone())
+        .unwrap_or_default();
+    for (key, setting) in overrides {
+        merge(
+            &mut value,
+            nest(&key, setting),
+            "",
+            &SettingOrigin::CommandLine,
+            &mut origins,
+        );
+    }
+
+    let settings = settings_from_value(&value)?;
+    Ok(LayeredSettings {
+        settings,
+        value,
+        origins,
+    })
+}
+
+pub fn load_settings() -> Result<CFSettings> {
+    Ok(load_layered_settings()?.settings)
+}
+
+/// Loads the settings again and replaces [`SETTINGS`] and [`CREDENTIALS`], so the running app
+/// uses them without a restart.
+///
+/// [`CREDENTIALS`]: crate::credentials::CREDENTIALS
+pub fn reload_settings() -> Result<()> {
+    let settings = load_settings()?;
+    CREDENTIALS.set(load_credentials(&settings));
+    SETTINGS.set(settings);
+    Ok(())
+}
+
+/// Loads the settings at startup, so a malformed configuration is reported before anything
+/// uses [`SETTINGS`]. Without any configuration file the defaults are used.
+pub fn init_settings() -> Result<()> {
+    let workspace_file_path = env::current_dir()
+        .ok()
+        .and_then(|dir| find_workspace_config_file(&dir));
+    if !get_config_file_path()?.is_file() && workspace_file_path.is_none() {
+        return Ok(());
+    }
+    reload_settings().wrap_err("Failed to load the configuration")
+}
+
+/// The modification times and sizes of files, to find out when they change.
+pub struct FileWatcher {
+    files: Vec<(PathBuf, Option<(SystemTime, u64)>)>,
+}
+
+impl FileWatcher {
+    pub fn new(paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        let files = paths
+            .into_iter()
+            .map(|path| {
+                let state = Self::state(&path);
+                (path, state)
+            })
+            .collect();
+        Self { files }
+    }
+
+    fn state(path: &Path) -> Option<(SystemTime, u64)> {
+        let metadata = fs::metadata(path).ok()?;
+        Some((metadata.modified().ok()?, metadata.len()))
+    }
+
+    /// Returns whether any file is created, removed or modified since the last call.
+    pub fn changed(&mut self) -> bool {
+        let mut changed = false;
+        for (path, state) in &mut self.files {
+            let current = Self::state(path);
+            if *state != current {
+                *state = current;
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+/// Watches `cf.json`, `credentials.json` and the workspace configuration files, reloading
+/// [`SETTINGS`] every time one of them is created, removed or modified.
+///
+/// Every path a workspace configuration file can have is watched, so one added while running
+/// is used as well. Settings which fail to load are reported in the log, and the last ones are
+/// kept.
+pub fn watch_settings(interval: Duration) -> Result<thread::JoinHandle<()>> {
+    let mut paths = vec![get_config_file_path()?, get_credentials_file_path()?];
+    if let Ok(dir) = env::current_dir() {
+        paths.extend(workspace_config_file_paths(&dir));
+    }
+    let mut watcher = FileWatcher::new(paths);
+    Ok(thread::spawn(move || loop {
+        thread::sleep(interval);
+        if watcher.changed() {
+            match reload_settings() {
+                Ok(()) if client_needs_restart() => tracing::warn!(
+                    "Reloaded the settings, the http settings take effect after a restart"
+                ),
+                Ok(()) => tracing::info!("Reloaded the settings"),
+                Err(err) => tracing::error!("Failed to reload the settings: {err:#}"),
+            }
+        }
+    }))
+}
+
+/// Loads the settings of the global configuration file only, e.g. to change and write it back.
+pub fn load_global_settings() -> Result<CFSettings> {
+    let config_file_path = get_config_file_path()?;
+    if !config_file_path.try_exists()? {
+        bail!("No configuration file found at {}.\nPlease add a configuration file or run `cf-tui config`.", config_file_path.display());
+    }
+    settings_from_value(&read_json(&config_file_path, true)?)
+}
+
+/// Reads the global configuration file, which is empty if it does not exist yet.
+pub fn read_global_config() -> Result<serde_json::Value> {
+    let config_file_path = get_config_file_path()?;
+    match config_file_path.try_exists()? {
+        true => read_json(&config_file_path, true),
+        false => Ok(serde_json::json!({ "version": CONFIG_VERSION })),
+    }
+}
+
+/// Writes `config` to the global configuration file, as of [`CONFIG_VERSION`], if it is valid.
+pub fn write_global_config(config: &serde_json::Value) -> Result<()> {
+    settings_from_value(config)?;
+    let config_file_path = get_config_file_path()?;
+    let mut config = config.clone();
+    config["version"] = CONFIG_VERSION.into();
+    if let Some(dir) = config_file_path.parent() {
+        DirBuilder::new().recursive(true).create(dir)?;
+    }
+    fs::write(&config_file_path, serde_json::to_string_pretty(&config)?).wrap_err(format!(
+        "Error occured when writing to {}",
+        config_file_path.display()
+    ))
+}
+
+/// Writes `settings` to the global configuration file, as of [`CONFIG_VERSION`].
+pub fn save_global_settings(settings: &CFSettings) -> Result<()> {
+    write_global_config(&serde_json::to_value(settings)?)
+}
+
+/// Returns the setting of the dotted `key` in `config`, e.g. `http.proxy`.
+pub fn get_setting<'a>(config: &'a serde_json::Value, key: &str) -> Option<&'a serde_json::Value> {
+    key.split('.')
+        .try_fold(config, |config, name| config.get(name))
+        .filter(|value| !value.is_null())
+}
+
+/// Sets the dotted `key` in `config` to `value`, creating the tables on the way.
+pub fn set_setting(
+    config: &mut serde_json::Value,
+    key: &str,
+    value: serde_json::Value,
+) -> Result<()> {
+    if !is_known_setting(key) {
+        bail!("Unknown setting `{key}`");
+    }
+    let mut target = config;
+    for name in key.split('.') {
+        if !target.is_object() {
+            *target = serde_json::Value::Object(Default::default());
+        }
+        target = target
+            .as_object_mut()
+            .ok_or(eyre!("`{key}` is not in a table"))?
+            .entry(name)
+            .or_insert(serde_json::Value::Null);
+    }
+    *target = value;
+    Ok(())
+}
+
+/// Removes the dotted `key` from `config`, returning whether it was set.
+pub fn unset_setting(config: &mut serde_json::Value, key: &str) -> bool {
+    let (table, name) = match key.rsplit_once('.') {
+        Some((table, name)) => (table, name),
+        None => ("", key),
+    };
+    let table = match table {
+        "" => Some(config),
+        table => table
+            .split('.')
+            .try_fold(config, |config, name| config.get_mut(name)),
+    };
+    table
+        .and_then(|table| table.as_object_mut())
+        .and_then(|table| table.remove(name))
+        .is_some_and(|value| !value.is_null())
 }
 
 pub fn get_config_file_path() -> Result<PathBuf> {
@@ -86,6 +699,32 @@ pub fn get_config_file_path() -> Result<PathBuf> {
     Ok(config_file_path)
 }
 
+/// Writes `content` to a file only the current user can read and write, as it holds secrets.
+pub fn write_private_file(path: &Path, content: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        DirBuilder::new().recursive(true).create(dir)?;
+    }
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // The mode only applies to new files, so files created before are restricted as well.
+        if path.exists() {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600)).wrap_err(
+                format!("Failed to restrict permissions of {}", path.display()),
+            )?;
+        }
+    }
+    let mut file = options
+        .open(path)
+        .wrap_err(format!("Error occured when writing to {}", path.display()))?;
+    file.write_all(content.as_bytes())
+        .wrap_err(format!("Error occured when writing to {}", path.display()))?;
+    Ok(())
+}
+
 pub fn get_config(config_file_path: PathBuf) -> Result<Config> {
     let config = Config::builder()
         .add_source(File::new(
@@ -123,4 +762,234 @@ mod tests {
         let config = Config::builder().build().unwrap();
         let _settings = deserialize_config_into_settings(config).unwrap();
     }
+
+    #[test]
+    fn layers() {
+        let global = SettingOrigin::Global(PathBuf::from("cf.json"));
+        let workspace = SettingOrigin::Workspace(PathBuf::from(".cf.json"));
+        let mut value = serde_json::json!({});
+        let mut origins = BTreeMap::new();
+        merge(
+            &mut value,
+            serde_json::json!({
+                "username": "tourist",
+                "password": null,
+                "http": {"proxy": "http://127.0.0.1:7890", "read_timeout": 30},
+                "templates": [{"alias": "cpp"}],
+            }),
+            "",
+            &global,
+            &mut origins,
+        );
+        merge(
+            &mut value,
+            serde_json::json!({
+                "password": null,
+                "http": {"read_timeout": 60},
+                "templates": [{"alias": "java"}],
+            }),
+            "",
+            &workspace,
+            &mut origins,
+        );
+        let origin = SettingOrigin::Environment(String::from("CF_HTTP"));
+        merge(
+            &mut value,
+            nest(
+                "http.proxy",
+                parse_value("socks5://127.0.0.1:1080").unwrap(),
+            ),
+            "",
+            &origin,
+            &mut origins,
+        );
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "username": "tourist",
+                "password": null,
+                "http": {"proxy": "socks5://127.0.0.1:1080", "read_timeout": 60},
+                "templates": [{"alias": "java"}],
+            })
+        );
+        assert_eq!(
+            origins.into_iter().collect::<Vec<_>>(),
+            vec![
+                (String::from("http.proxy"), origin),
+                (String::from("http.read_timeout"), workspace.clone()),
+                (String::from("templates"), workspace),
+                (String::from("username"), global),
+            ]
+        );
+    }
+
+    #[test]
+    fn known_settings() {
+        for key in [
+            "username",
+            "http",
+            "http.proxy",
+            "theme.colors.accepted",
+            "themes.solarized.preset",
+            "keybindings.keys.submit",
+            "commands.py.script",
+            "languages.cpp",
+            "templates",
+        ] {
+            assert!(is_known_setting(key), "{key}");
+        }
+        for key in [
+            "bogus",
+            "http.bogus",
+            "http.proxy.host",
+            "theme.nonsense",
+            "themes.solarized.bogus",
+            "commands.py.bogus",
+            "languages.cpp.id",
+            "templates.0",
+            "http.",
+        ] {
+            assert!(!is_known_setting(key), "{key}");
+        }
+    }
+
+    #[test]
+    fn environment() {
+        let vars = [
+            ("CF_USERNAME", "tourist"),
+            ("CF_HTTP__READ_TIMEOUT", "60"),
+            ("CF_BASE_URL", "http://127.0.0.1:8080/"),
+            ("HOME", "/root"),
+        ]
+        .map(|(var, value)| (var.to_string(), value.to_string()));
+        assert_eq!(
+            environment_settings(vars),
+            vec![
+                (
+                    String::from("CF_HTTP__READ_TIMEOUT"),
+                    String::from("http.read_timeout"),
+                    String::from("60")
+                ),
+                (
+                    String::from("CF_USERNAME"),
+                    String::from("username"),
+                    String::from("tourist")
+                ),
+            ]
+        );
+        assert_eq!(
+            parse_value(r#"{"cpp": 54}"#).unwrap(),
+            serde_json::json!({"cpp": 54})
+        );
+        assert!(parse_value("[1,").is_err());
+    }
+
+    #[test]
+    fn migrations() {
+        let mut config = serde_json::json!({
+            "username": "tourist",
+            "commands": {
+                "cpp": {
+                    "ext": "cpp",
+                    "before_command": "g++ <% full %>",
+                    "command": "./<% file %>",
+                    "after_command": "",
+                    "open_command": "vim <% full %>",
+                },
+                "py": {"script": "python3 <% full %>"},
+            },
+        });
+        assert_eq!(migrate(&mut config).unwrap(), 0);
+        assert_eq!(
+            config,
+            serde_json::json!({
+                "version": CONFIG_VERSION,
+                "username": "tourist",
+                "commands": {
+                    "cpp": {
+                        "before_script": "g++ <% full %>",
+                        "script": "./<% file %>",
+                        "after_script": "",
+                        "open_script": "vim <% full %>",
+                    },
+                    "py": {"script": "python3 <% full %>"},
+                },
+            })
+        );
+        let migrated = config.clone();
+        assert_eq!(migrate(&mut config).unwrap(), CONFIG_VERSION);
+        assert_eq!(config, migrated);
+
+        let mut config = serde_json::json!({"version": CONFIG_VERSION + 1});
+        assert!(migrate(&mut config).is_err());
+    }
+
+    #[test]
+    fn workspace_config_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path().join("contests").join("1");
+        fs::create_dir_all(&workspace).unwrap();
+        let paths: Vec<_> = workspace_config_file_paths(&workspace).take(3).collect();
+        assert_eq!(
+            paths,
+            vec![
+                workspace.join(".cf.json"),
+                dir.path().join("contests").join(".cf.json"),
+                dir.path().join(".cf.json"),
+            ]
+        );
+
+        // A workspace configuration file added later is found and noticed.
+        let mut watcher = FileWatcher::new(workspace_config_file_paths(&workspace));
+        fs::write(dir.path().join(".cf.json"), "{}").unwrap();
+        assert!(watcher.changed());
+        assert_eq!(
+            find_workspace_config_file(&workspace),
+            Some(dir.path().join(".cf.json"))
+        );
+    }
+
+    #[test]
+    fn file_watcher() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cf.json");
+        let mut watcher = FileWatcher::new([path.clone()]);
+        assert!(!watcher.changed());
+        fs::write(&path, "{}").unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+        fs::write(&path, r#"{"username": "tourist"}"#).unwrap();
+        assert!(watcher.changed());
+        fs::remove_file(&path).unwrap();
+        assert!(watcher.changed());
+    }
+
+    #[test]
+    fn dotted_keys() {
+        let mut config = serde_json::json!({"username": "tourist", "http": null});
+        assert_eq!(
+            get_setting(&config, "username"),
+            Some(&serde_json::json!("tourist"))
+        );
+        assert_eq!(get_setting(&config, "http.proxy"), None);
+
+        set_setting(
+            &mut config,
+            "http.proxy",
+            serde_json::json!("socks5://proxy"),
+        )
+        .unwrap();
+        set_setting(&mut config, "http.read_timeout", serde_json::json!(30)).unwrap();
+        assert_eq!(
+            config["http"],
+            serde_json::json!({"proxy": "socks5://proxy", "read_timeout": 30})
+        );
+        assert!(set_setting(&mut config, "handle", serde_json::json!("tourist")).is_err());
+
+        assert!(unset_setting(&mut config, "http.proxy"));
+        assert!(!unset_setting(&mut config, "http.proxy"));
+        assert!(!unset_setting(&mut config, "languages.cpp"));
+        assert!(unset_setting(&mut config, "username"));
+        assert_eq!(config, serde_json::json!({"http": {"read_timeout": 30}}));
+    }
 }
@@ -0,0 +1,138 @@
+mod common;
+
+use cf::{
+    api::{
+        methods::*,
+        parse::{parse_statement, parse_testcase},
+        utils::base_url,
+    },
+    settings::CFHttp,
+};
+use futures::{StreamExt, TryStreamExt};
+
+#[tokio::test]
+async fn blog_entry() {
+    common::init();
+    assert_eq!(blogEntry_view(79).await.unwrap().id, 79);
+    assert!(!blogEntry_comments(79).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn contest() {
+    common::init();
+    let contests = contest_list(None).await.unwrap();
+    assert!(contests.iter().any(|contest| contest.id == 1));
+    assert!(!contest_hacks(1).await.unwrap().is_empty());
+    assert_eq!(contest_ratingChanges(1).await.unwrap()[0].contestId, 1);
+    let standings = contest_standings(1, None, None, None, None, Some(true))
+        .await
+        .unwrap();
+    assert_eq!(standings.problems.len(), 3);
+    assert_eq!(standings.rows[0].problemResults.len(), 3);
+    let submissions = contest_status(1, Some("tourist".to_string()), None, None)
+        .await
+        .unwrap();
+    assert_eq!(submissions.len(), 3);
+}
+
+#[tokio::test]
+async fn status_stream() {
+    common::init();
+    let handle = String::from("tourist");
+    let submissions: Vec<_> = contest_status_stream(1, Some(handle.clone()), 2)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(submissions.len(), 3);
+    let submissions: Vec<_> = user_status_stream(handle, 2)
+        .take(1)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(submissions.len(), 1);
+}
+
+#[tokio::test]
+async fn problemset() {
+    common::init();
+    let problemset = problemset_problems(None, None).await.unwrap();
+    assert_eq!(
+        problemset.problems.len(),
+        problemset.problemStatistics.len()
+    );
+    assert!(!problemset_recentStatus(10, None).await.unwrap().is_empty());
+    assert!(!recentActions(30).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn user() {
+    common::init();
+    let handle = String::from("tourist");
+    assert_eq!(
+        user_info(vec![handle.clone()]).await.unwrap()[0].handle,
+        handle
+    );
+    assert!(!user_blogEntries(handle.clone()).await.unwrap().is_empty());
+    assert!(!user_friends(None).await.unwrap().is_empty());
+    assert!(!user_ratedList(Some(true), None, None)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(!user_rating(handle.clone()).await.unwrap().is_empty());
+    assert_eq!(user_status(handle, None, None).await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn parse() {
+    common::init();
+    let test_cases = parse_testcase(format!("{}contest/1/problem/A", base_url()))
+        .await
+        .unwrap();
+    assert_eq!(test_cases.len(), 1);
+    assert_eq!(test_cases[0].input, "6 6 4\n");
+    assert_eq!(test_cases[0].answer, "4\n");
+}
+
+#[tokio::test]
+async fn parse_missing_problem() {
+    common::init();
+    let test_cases = parse_testcase(format!("{}contest/1/problem/Z", base_url()))
+        .await
+        .unwrap();
+    assert!(test_cases.is_empty());
+}
+
+#[tokio::test]
+async fn statement() {
+    common::init();
+    let statement = parse_statement(format!("{}problemset/problem/1/C", base_url()))
+        .await
+        .unwrap();
+    assert_eq!(statement.title, "C. Ancient Berland Circus");
+    assert_eq!(statement.time_limit, "2 seconds");
+    assert_eq!(statement.samples.len(), 1);
+    assert_eq!(statement.images.len(), 1);
+    assert!(
+        parse_statement(format!("{}contest/1/problem/Z", base_url()))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn read_timeout() {
+    common::init();
+    // A server that accepts connections but never responds.
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    let client = build_client(Some(&CFHttp {
+        read_timeout: Some(1),
+        ..Default::default()
+    }))
+    .unwrap();
+    let start = std::time::Instant::now();
+    let err = client.get(url).send().await.unwrap_err();
+    assert!(err.is_timeout());
+    assert!(start.elapsed() < std::time::Duration::from_secs(5));
+    drop(listener);
+}
@@ -0,0 +1,415 @@
+mod common;
+
+use std::{
+    fs,
+    io::Write,
+    path::Path,
+    process::{Command, Output, Stdio},
+};
+
+/// Runs `cf-cli` against the mock server with its own configuration, like a separate run.
+fn cf_cli(config_dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_cf-cli"))
+        .args(args)
+        .arg("--base-url")
+        .arg(common::server_url())
+        .env("XDG_CONFIG_HOME", config_dir)
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+fn stderr(output: &Output) -> String {
+    assert!(!output.status.success());
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+#[test]
+fn session_across_runs() {
+    common::init();
+    let config_dir = common::scratch_dir("cli-config");
+    fs::create_dir_all(config_dir.join("cf")).unwrap();
+    let config = serde_json::json!({
+        "username": "tourist",
+        "password": "password",
+    });
+    fs::write(config_dir.join("cf").join("cf.json"), config.to_string()).unwrap();
+    let session_file = config_dir.join("cf").join("session.json");
+
+    assert!(stderr(&cf_cli(&config_dir, &["whoami"])).contains("Not logged in"));
+    // A visitor gets a session as well, which is not a login that expired.
+    assert!(fs::read_to_string(&session_file)
+        .unwrap()
+        .contains("JSESSIONID=visitor-"));
+    assert!(stderr(&cf_cli(&config_dir, &["whoami"])).contains("Not logged in"));
+    assert_eq!(
+        stdout(&cf_cli(&config_dir, &["login"])),
+        "Logged in as tourist."
+    );
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&session_file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+    assert_eq!(stdout(&cf_cli(&config_dir, &["whoami"])), "tourist");
+
+    // The server no longer knows a session it did not give out.
+    let session = fs::read_to_string(&session_file).unwrap();
+    fs::write(
+        &session_file,
+        session.replace("JSESSIONID=mock-", "JSESSIONID=stale-"),
+    )
+    .unwrap();
+    assert!(stderr(&cf_cli(&config_dir, &["whoami"])).contains("The session has expired"));
+
+    stdout(&cf_cli(&config_dir, &["login"]));
+    assert_eq!(
+        stdout(&cf_cli(&config_dir, &["logout"])),
+        "Logged out from tourist."
+    );
+    assert!(!session_file.exists());
+    assert!(stderr(&cf_cli(&config_dir, &["whoami"])).contains("Not logged in"));
+}
+
+#[test]
+fn layered_config() {
+    common::init();
+    let config_dir = common::scratch_dir("layered-config");
+    fs::create_dir_all(config_dir.join("cf")).unwrap();
+    let config = serde_json::json!({
+        "username": "tourist",
+        "secret": "secret",
+        "http": {"read_timeout": 30, "connect_timeout": 10},
+    });
+    fs::write(config_dir.join("cf").join("cf.json"), config.to_string()).unwrap();
+    let workspace = common::scratch_dir("layered-workspace");
+    fs::create_dir_all(workspace.join("A")).unwrap();
+    let config = serde_json::json!({
+        "home_dir": "codes",
+        "http": {"read_timeout": 60},
+    });
+    fs::write(workspace.join(".cf.json"), config.to_string()).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cf-cli"))
+        .args(["config", "show", "--origin", "--set", "username=jiangly"])
+        .current_dir(workspace.join("A"))
+        .env("XDG_CONFIG_HOME", &config_dir)
+        .env("CF_HTTP__CONNECT_TIMEOUT", "5")
+        .output()
+        .unwrap();
+    let global = config_dir.join("cf").join("cf.json");
+    let workspace_file = workspace.join(".cf.json");
+    assert_eq!(
+        stdout(&output).lines().collect::<Vec<_>>(),
+        vec![
+            format!(
+                "home_dir = \"{}\"  ({})",
+                workspace.join("codes").display(),
+                workspace_file.display()
+            ),
+            String::from(
+                "http.connect_timeout = \"5\"  (environment variable CF_HTTP__CONNECT_TIMEOUT)"
+            ),
+            format!("http.read_timeout = 60  ({})", workspace_file.display()),
+            format!("secret = \"********\"  ({})", global.display()),
+            String::from("username = \"jiangly\"  (command line)"),
+            format!("version = 1  ({})", workspace_file.display()),
+        ]
+    );
+    // Only the global file is upgraded on disk.
+    assert_eq!(
+        fs::read_to_string(&workspace_file).unwrap(),
+        config.to_string()
+    );
+    assert!(!workspace.join(".cf.json.v0.bak").exists());
+}
+
+#[test]
+fn config_subcommands() {
+    common::init();
+    let config_dir = common::scratch_dir("config-subcommands");
+    let cf_tui = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_cf-tui"))
+            .args(args)
+            .env("XDG_CONFIG_HOME", &config_dir)
+            .output()
+            .unwrap()
+    };
+    let config_file = config_dir.join("cf").join("cf.json");
+    let read_config = || {
+        serde_json::from_str::<serde_json::Value>(&fs::read_to_string(&config_file).unwrap())
+            .unwrap()
+    };
+
+    stdout(&cf_tui(&["config", "set", "username", "tourist"]));
+    stdout(&cf_tui(&["config", "set", "http.read_timeout", "30"]));
+    stdout(&cf_tui(&["config", "set", "secret", "secret"]));
+    assert_eq!(stdout(&cf_tui(&["config", "get", "username"])), "tourist");
+    assert_eq!(
+        stdout(&cf_tui(&["config", "get", "http.read_timeout"])),
+        "30"
+    );
+    assert_eq!(stdout(&cf_tui(&["config", "get", "secret"])), "secret");
+    assert_eq!(read_config()["secret"], serde_json::Value::Null);
+    assert!(stderr(&cf_tui(&["config", "set", "handle", "tourist"])).contains("Unknown setting"));
+    stdout(&cf_tui(&["config", "unset", "http.read_timeout"]));
+    assert!(stderr(&cf_tui(&["config", "get", "http.read_timeout"])).contains("not set"));
+
+    let template = config_dir.join("template.cpp");
+    fs::write(&template, "// <% username %>").unwrap();
+    let template = template.display().to_string();
+    let add = [
+        "config",
+        "templates",
+        "add",
+        "--alias",
+        "cpp",
+        "--lang",
+        "C++",
+        "--path",
+        &template,
+    ];
+    stdout(&cf_tui(&add));
+    assert!(stderr(&cf_tui(&add)).contains("already exists"));
+    stdout(&cf_tui(&[
+        "config",
+        "templates",
+        "edit",
+        "--alias",
+        "cpp",
+        "--new-alias",
+        "c++",
+        "--program-type-id",
+        "54",
+    ]));
+    assert_eq!(
+        read_config()["templates"],
+        serde_json::json!([{
+            "alias": "c++",
+            "lang": "C++",
+            "path": template,
+            "program_type_id": 54,
+        }])
+    );
+    stdout(&cf_tui(&["config", "templates", "rm", "--alias", "c++"]));
+    assert!(
+        stderr(&cf_tui(&["config", "templates", "rm", "--alias", "c++"])).contains("No template")
+    );
+
+    stdout(&cf_tui(&[
+        "config",
+        "commands",
+        "add",
+        "--ext",
+        "py",
+        "--script",
+        "python3 <% full %>",
+    ]));
+    stdout(&cf_tui(&[
+        "config",
+        "commands",
+        "edit",
+        "--ext",
+        "py",
+        "--open-script",
+        "vim <% full %>",
+    ]));
+    assert_eq!(
+        read_config()["commands"]["py"]["open_script"],
+        serde_json::json!("vim <% full %>")
+    );
+    stdout(&cf_tui(&["config", "commands", "rm", "--ext", "py"]));
+    assert_eq!(read_config()["commands"], serde_json::json!({}));
+}
+
+#[test]
+fn config_login() {
+    common::init();
+    let config_dir = common::scratch_dir("config-login");
+    let login = |input: &str| {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_cf-tui"))
+            .args(["config", "login"])
+            .env("XDG_CONFIG_HOME", &config_dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.as_bytes())
+            .unwrap();
+        stdout(&child.wait_with_output().unwrap())
+    };
+    let read_json = |name: &str| {
+        let path = config_dir.join("cf").join(name);
+        serde_json::from_str::<serde_json::Value>(&fs::read_to_string(path).unwrap()).unwrap()
+    };
+
+    login("tourist\nkey\nsecret\n pass word \n");
+    let credentials = serde_json::json!({
+        "key": "key",
+        "secret": "secret",
+        "password": " pass word ",
+    });
+    assert_eq!(read_json("credentials.json"), credentials);
+    assert_eq!(read_json("cf.json")["username"], "tourist");
+
+    // Leaving every answer empty keeps what was saved.
+    login("\n\n\n\n");
+    assert_eq!(read_json("credentials.json"), credentials);
+    assert_eq!(read_json("cf.json")["username"], "tourist");
+
+    login("\nkey2\n\n\n");
+    assert_eq!(read_json("credentials.json")["key"], "key2");
+    assert_eq!(read_json("credentials.json")["secret"], "secret");
+}
+
+#[test]
+fn malformed_config() {
+    common::init();
+    let config_dir = common::scratch_dir("malformed-config");
+    fs::create_dir_all(config_dir.join("cf")).unwrap();
+    fs::write(config_dir.join("cf").join("cf.json"), "{\"username\": ").unwrap();
+
+    let error = stderr(&cf_cli(&config_dir, &["whoami"]));
+    assert!(
+        error.contains("Failed to load the configuration"),
+        "{error}"
+    );
+    assert!(!error.contains("panicked"), "{error}");
+
+    fs::write(
+        config_dir.join("cf").join("cf.json"),
+        "{\"http\": {\"proxy\": \"not a proxy\"}}",
+    )
+    .unwrap();
+    let error = stderr(&cf_cli(&config_dir, &["whoami"]));
+    assert!(error.contains("Invalid http settings"), "{error}");
+    assert!(!error.contains("panicked"), "{error}");
+}
+
+#[test]
+fn doctor() {
+    common::init();
+    let config_dir = common::scratch_dir("doctor-config");
+    fs::create_dir_all(config_dir.join("cf").join("templates")).unwrap();
+    fs::write(
+        config_dir.join("cf").join("templates").join("template.cpp"),
+        "// <% username %>",
+    )
+    .unwrap();
+    let config = serde_json::json!({
+        "username": "tourist",
+        "key": "key",
+        "secret": "secret",
+        "templates": [{"alias": "cpp", "lang": "C++", "path": "template.cpp"}],
+        "commands": {"cpp": {"script": "./<% file %>"}},
+        "theme": {"preset": "mine", "colors": {"header": "#268bd2"}},
+        "themes": {"mine": {"preset": "light", "colors": {"accepted": "cyan"}}},
+    });
+    fs::write(config_dir.join("cf").join("cf.json"), config.to_string()).unwrap();
+
+    let output = cf_cli(&config_dir, &["doctor"]);
+    assert!(!output.status.success());
+    let report = String::from_utf8_lossy(&output.stdout);
+    assert!(report.contains("✗ home_dir: Not configured"), "{report}");
+    assert!(report.contains(&format!(
+        "✓ templates[0] (cpp): {}",
+        config_dir
+            .join("cf")
+            .join("templates")
+            .join("template.cpp")
+            .display()
+    )));
+    assert!(report.contains("✓ API: The key and secret are accepted"));
+    assert!(report.contains("✓ theme: Valid"), "{report}");
+    assert!(report.ends_with("1 error, 0 warnings.\n"), "{report}");
+}
+
+#[test]
+fn upgrade_and_import() {
+    common::init();
+    let config_dir = common::scratch_dir("upgrade-config");
+    fs::create_dir_all(config_dir.join("cf")).unwrap();
+    let config = serde_json::json!({
+        "username": "tourist",
+        "commands": {"py": {"ext": "py", "command": "python3 <% full %>"}},
+    });
+    let config_file = config_dir.join("cf").join("cf.json");
+    fs::write(&config_file, config.to_string()).unwrap();
+
+    let show = stdout(&cf_cli(&config_dir, &["config", "show", "--origin"]));
+    assert!(
+        show.contains(r#"commands.py.script = "python3 <% full %>""#),
+        "{show}"
+    );
+    let backup = config_dir.join("cf").join("cf.json.v0.bak");
+    assert_eq!(fs::read_to_string(backup).unwrap(), config.to_string());
+    let upgraded: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&config_file).unwrap()).unwrap();
+    assert_eq!(upgraded["version"], 1);
+
+    let cf_tool = common::scratch_dir("cf-tool");
+    fs::create_dir_all(&cf_tool).unwrap();
+    fs::write(cf_tool.join("java.java"), "// $%U%$").unwrap();
+    let config = serde_json::json!({
+        "template": [{
+            "alias": "java",
+            "lang": "60",
+            "path": cf_tool.join("java.java"),
+            "suffix": ["java"],
+            "before_script": "javac $%full%$",
+            "script": "java $%file%$",
+            "after_script": "",
+        }],
+        "default": 0,
+        "host": "https://codeforces.com",
+        "proxy": "",
+    });
+    fs::write(cf_tool.join("config"), config.to_string()).unwrap();
+    let session = serde_json::json!({
+        "handle": "tourist",
+        "password": "encrypted",
+        "cookies": {"codeforces.com": {"codeforces.com;/;JSESSIONID": {
+            "Name": "JSESSIONID",
+            "Value": "imported",
+            "Path": "/",
+            "Persistent": false,
+            "Expires": "9999-12-31T23:59:59Z",
+        }}},
+    });
+    fs::write(cf_tool.join("session"), session.to_string()).unwrap();
+
+    let notes = stdout(&cf_cli(
+        &config_dir,
+        &["config", "import", cf_tool.to_str().unwrap()],
+    ));
+    assert!(
+        notes.contains("Imported 1 cookies of the session."),
+        "{notes}"
+    );
+    assert_eq!(
+        fs::read_to_string(config_dir.join("cf").join("templates").join("java.java")).unwrap(),
+        "// <% username %>"
+    );
+    let show = stdout(&cf_cli(&config_dir, &["config", "show"]));
+    let config: serde_json::Value = serde_json::from_str(&show).unwrap();
+    assert_eq!(config["templates"][0]["program_type_id"], 60);
+    assert_eq!(config["commands"]["java"]["script"], "java <% file %>");
+    assert_eq!(config["commands"]["py"]["script"], "python3 <% full %>");
+    let session = fs::read_to_string(config_dir.join("cf").join("session.json")).unwrap();
+    assert!(session.contains("JSESSIONID=imported"), "{session}");
+}
@@ -0,0 +1,161 @@
+#![allow(dead_code)]
+use std::{
+    env,
+    fs::{self, DirBuilder},
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, Receiver},
+        Once,
+    },
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+use cf::{
+    api::utils::set_base_url,
+    display::tui::{
+        component::ContentUpdateCmd,
+        event::AppEvent,
+        msg::{ComponentMsg, ViewConstructor},
+        types::Text,
+    },
+    mock::{Fixtures, MockServer},
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use lazy_static::lazy_static;
+
+lazy_static! {
+    static ref SERVER: MockServer = MockServer::start(Fixtures::new()).unwrap();
+    /// The directory of the test target, under the target directory, emptied by every run.
+    static ref ROOT: PathBuf = Path::new(env!("CARGO_TARGET_TMPDIR")).join(env!("CARGO_CRATE_NAME"));
+}
+
+static INIT: Once = Once::new();
+
+/// Starts the mock server and points both the API and the configuration to it.
+///
+/// Must be called at the beginning of every test, before anything touches `SETTINGS`.
+pub fn init() {
+    INIT.call_once(|| {
+        if ROOT.exists() {
+            fs::remove_dir_all(&*ROOT).unwrap();
+        }
+        let config_dir = ROOT.join("config");
+        DirBuilder::new()
+            .recursive(true)
+            .create(config_dir.join("cf"))
+            .unwrap();
+        let config = serde_json::json!({
+            "username": "tourist",
+            "password": "password",
+            "home_dir": home_dir(),
+            "commands": {
+                "sh": {
+                    "script": "sh <% full %>",
+                },
+                "py": {
+                    "script": "python3 <% full %>",
+                },
+                "cpp": {
+                    "script": "./<% file %>",
+                }
+            },
+            "templates": [
+                {"alias": "cpp20", "lang": "C++", "path": "cpp20.cpp", "program_type_id": 89},
+                {"alias": "cpp17", "lang": "C++", "path": "cpp17.cpp", "program_type_id": 54}
+            ]
+        });
+        let templates_dir = config_dir.join("cf").join("templates");
+        DirBuilder::new().create(&templates_dir).unwrap();
+        for alias in ["cpp20", "cpp17"] {
+            fs::write(
+                templates_dir.join(format!("{alias}.cpp")),
+                format!("// {alias} by <% username %>\n"),
+            )
+            .unwrap();
+        }
+        fs::write(
+            config_dir.join("cf").join("cf.json"),
+            serde_json::to_string_pretty(&config).unwrap(),
+        )
+        .unwrap();
+        env::set_var("XDG_CONFIG_HOME", config_dir);
+        set_base_url(&SERVER.base_url());
+    });
+}
+
+pub fn home_dir() -> PathBuf {
+    ROOT.join("home")
+}
+
+/// Returns a directory of the test run that nothing else uses.
+pub fn scratch_dir(name: &str) -> PathBuf {
+    let dir = ROOT.join(name);
+    DirBuilder::new().recursive(true).create(&dir).unwrap();
+    dir
+}
+
+pub fn server_url() -> String {
+    SERVER.base_url()
+}
+
+/// Returns the number of requests the mock server got for `path`.
+pub fn requests(path: &str) -> usize {
+    SERVER.requests(path)
+}
+
+pub static TIMEOUT: Duration = Duration::from_secs(10);
+
+pub fn key(c: char) -> AppEvent {
+    AppEvent::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+}
+
+pub fn wait_until(mut tick: impl FnMut() -> bool) {
+    let start = Instant::now();
+    while !tick() {
+        assert!(start.elapsed() < TIMEOUT, "Timed out waiting for update");
+        sleep(Duration::from_millis(10));
+    }
+}
+
+/// Runs the popup opened by the last key press and collects its content until `done` holds.
+pub fn run_popup(receiver: &Receiver<ComponentMsg>, done: impl Fn(&Text) -> bool) -> Text {
+    let update = match receiver.recv_timeout(TIMEOUT) {
+        Ok(ComponentMsg::EnterNewView(ViewConstructor::UpdatablePopup(_, update, _, text))) => {
+            (update, text)
+        }
+        Ok(ComponentMsg::EnterNewView(ViewConstructor::ErrorPopup(title, text))) => {
+            panic!("{title}: {text}")
+        }
+        _ => panic!("No popup opened"),
+    };
+    let (update, mut text) = update;
+    let (update_sender, update_receiver) = mpsc::channel();
+    let (popup_sender, popup_receiver) = mpsc::channel();
+    update(update_sender, popup_sender);
+    wait_until(|| {
+        if let Ok(ComponentMsg::EnterNewView(ViewConstructor::ErrorPopup(title, text))) =
+            popup_receiver.try_recv()
+        {
+            panic!("{title}: {text}");
+        }
+        while let Ok(cmd) = update_receiver.try_recv() {
+            match cmd {
+                ContentUpdateCmd::Push(line) => {
+                    text.push(line);
+                }
+                ContentUpdateCmd::PushLines(lines) => {
+                    for line in lines.lines {
+                        text.push(line);
+                    }
+                }
+                ContentUpdateCmd::Change(index, line) => {
+                    text.change(index, line);
+                }
+                ContentUpdateCmd::Set(lines) => text = lines,
+            }
+        }
+        done(&text)
+    });
+    text
+}
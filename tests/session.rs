@@ -0,0 +1,135 @@
+//! Submissions change what the mock server lists, so they are tested apart from `views`.
+mod common;
+
+use std::{
+    fs::{self, DirBuilder},
+    sync::mpsc,
+};
+
+use cf::{
+    api::{
+        languages::{load_languages, refresh_languages},
+        locator::{ProblemLocator, ProblemSource},
+        methods::contest_list,
+        objects::SubmissionVerdict,
+        session::{current_handle, ensure_login, login, submit},
+    },
+    display::tui::{
+        component::{generate_view, submit_view, ProblemsList, SubmissionsList},
+        msg::{ComponentMsg, ViewConstructor},
+        Component,
+    },
+    mock::web::PASSWORD,
+};
+use common::{key, run_popup, wait_until, TIMEOUT};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn login_and_submit() {
+    common::init();
+    assert!(login("tourist", "wrong").await.is_err());
+    assert_eq!(login("tourist", PASSWORD).await.unwrap(), "tourist");
+    assert_eq!(current_handle().await.unwrap().as_deref(), Some("tourist"));
+    assert_eq!(ensure_login("tourist", None).await.unwrap(), "tourist");
+
+    let locator = ProblemLocator::new(ProblemSource::Contest(1), "B");
+    let source = "print(input())\n";
+    let id = submit(&locator, 31, source).await.unwrap();
+    assert!(id >= 200000000);
+    let err = submit(&locator, 31, source).await.unwrap_err();
+    assert!(format!("{err}").contains("exactly the same code"));
+    let unknown = ProblemLocator::new(ProblemSource::Contest(1), "Z");
+    assert!(submit(&unknown, 31, "pass\n").await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn submit_and_track() {
+    common::init();
+    let contest = contest_list(None)
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|contest| contest.id == 1)
+        .unwrap();
+    let problem_dir = common::home_dir().join("Contests").join("1").join("A");
+    DirBuilder::new()
+        .recursive(true)
+        .create(&problem_dir)
+        .unwrap();
+    fs::write(problem_dir.join("A.py"), "print(4)\n").unwrap();
+
+    let (sender, receiver) = mpsc::channel();
+    let mut list = ProblemsList::new(sender, contest.clone(), ProblemSource::Contest(1));
+    list.update();
+    wait_until(|| {
+        list.tick();
+        !list.is_updating()
+    });
+    list.on(&key('s')).unwrap();
+    let text = run_popup(&receiver, |text| text.to_string().starts_with("Submitted"));
+    let id = match receiver.recv_timeout(TIMEOUT) {
+        Ok(ComponentMsg::Submitted(id)) => id,
+        _ => panic!("Submission not reported"),
+    };
+    assert_eq!(
+        text.to_string(),
+        format!("Submitted A.py in Python 3.8.10 as #{id}")
+    );
+
+    let (sender, _receiver) = mpsc::channel();
+    let mut submissions = SubmissionsList::new(sender, contest);
+    submissions.track(id);
+    let verdict = |submissions: &SubmissionsList| {
+        submissions
+            .submissions()
+            .iter()
+            .find(|submission| submission.id == id)
+            .and_then(|submission| submission.verdict.clone())
+    };
+    wait_until(|| {
+        submissions.tick();
+        verdict(&submissions).is_some()
+    });
+    assert_eq!(verdict(&submissions), Some(SubmissionVerdict::TESTING));
+    wait_until(|| {
+        submissions.tick();
+        submissions.tracking().is_none()
+    });
+    assert_eq!(verdict(&submissions), Some(SubmissionVerdict::OK));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn submit_in_template_language() {
+    common::init();
+    let locator = ProblemLocator::new(ProblemSource::Contest(1), "C");
+    let handle_selection = match generate_view(&locator).unwrap() {
+        ViewConstructor::SelectPopup(_, handle_selection, ..) => handle_selection,
+        _ => panic!("Templates not listed"),
+    };
+    // The second template is in the same language as the first one, but for another compiler.
+    handle_selection(1).unwrap();
+
+    let (sender, receiver) = mpsc::channel();
+    let (notify, _notified) = mpsc::channel();
+    sender
+        .send(ComponentMsg::EnterNewView(
+            submit_view(&locator, notify).unwrap(),
+        ))
+        .unwrap();
+    let text = run_popup(&receiver, |text| text.to_string().starts_with("Submitted"));
+    assert!(
+        text.to_string()
+            .starts_with("Submitted C.cpp in GNU G++17 7.3.0 as #"),
+        "{text}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn refresh_language_catalog() {
+    common::init();
+    ensure_login("tourist", Some(PASSWORD)).await.unwrap();
+    let languages = refresh_languages().await.unwrap();
+    let language = languages.iter().find(|language| language.id == 73).unwrap();
+    assert_eq!(language.to_string(), "GNU G++20 11.2.0 (64 bit, winlibs)");
+    assert_eq!(language.extensions, vec!["cpp", "cc", "cxx"]);
+    assert_eq!(load_languages(), languages);
+}
@@ -0,0 +1,73 @@
+//! Saving settings changes the configuration every test reads, so it is tested apart from `views`.
+mod common;
+
+use std::sync::mpsc;
+
+use cf::{
+    display::tui::{
+        component::SettingsForm,
+        event::AppEvent,
+        msg::{ComponentMsg, ViewConstructor},
+        Component,
+    },
+    settings::SETTINGS,
+};
+use common::key;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn settings_form() {
+    common::init();
+    let (sender, receiver) = mpsc::channel();
+    let mut form = SettingsForm::new(sender).unwrap();
+    let press = |form: &mut SettingsForm, code: KeyCode| {
+        form.on(&AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE)))
+            .unwrap();
+    };
+    let type_text = |form: &mut SettingsForm, text: &str| {
+        for c in text.chars() {
+            form.on(&key(c)).unwrap();
+        }
+    };
+
+    // Add the commands of `rb` on the commands section, whose script cannot be found.
+    press(&mut form, KeyCode::Tab);
+    press(&mut form, KeyCode::Tab);
+    type_text(&mut form, "a");
+    for _ in 0.."ext1".len() {
+        press(&mut form, KeyCode::Backspace);
+    }
+    type_text(&mut form, "rb");
+    press(&mut form, KeyCode::Enter);
+    type_text(&mut form, "jj");
+    press(&mut form, KeyCode::Enter);
+    type_text(&mut form, "no-such-ruby <% full %>");
+    press(&mut form, KeyCode::Enter);
+    type_text(&mut form, "w");
+    match receiver.try_recv() {
+        Ok(ComponentMsg::EnterNewView(ViewConstructor::ErrorPopup(title, text))) => {
+            assert_eq!(title, "Settings are not saved");
+            assert!(text.contains("commands.rb"), "{text}");
+        }
+        _ => panic!("Saved invalid settings"),
+    }
+    assert!(!SETTINGS.get().commands.as_ref().unwrap().contains_key("rb"));
+
+    // Saving valid settings reloads them at once.
+    press(&mut form, KeyCode::Enter);
+    press(&mut form, KeyCode::Home);
+    for _ in 0.."no-such-ruby".len() {
+        press(&mut form, KeyCode::Delete);
+    }
+    type_text(&mut form, "sh");
+    press(&mut form, KeyCode::Enter);
+    type_text(&mut form, "w");
+    assert!(receiver.try_recv().is_err());
+    assert_eq!(
+        SETTINGS.get().commands.as_ref().unwrap()["rb"].script,
+        "sh <% full %>"
+    );
+
+    type_text(&mut form, "dw");
+    assert!(!SETTINGS.get().commands.as_ref().unwrap().contains_key("rb"));
+}
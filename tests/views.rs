@@ -0,0 +1,325 @@
+mod common;
+
+use std::{
+    fs::{self, DirBuilder},
+    sync::mpsc,
+};
+
+use cf::{
+    api::{
+        locator::{ProblemLocator, ProblemSource},
+        methods::contest_list,
+    },
+    display::tui::{
+        component::{
+            open_contest, ContestList, ProblemsList, ProblemsetList, Statement, SubmissionsList,
+        },
+        event::AppEvent,
+        keymap::Action,
+        msg::{ComponentMsg, ViewConstructor, ViewMsg},
+        view::ContestBrowser,
+        Component, View,
+    },
+};
+use common::{key, run_popup, wait_until, TIMEOUT};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+async fn contest() -> cf::api::objects::Contest {
+    contest_list(None)
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|contest| contest.id == 1)
+        .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn contest_list_view() {
+    common::init();
+    let (sender, _receiver) = mpsc::channel();
+    let mut list = ContestList::new(sender);
+    list.update();
+    wait_until(|| {
+        list.tick();
+        !list.is_updating()
+    });
+    assert_eq!(list.contests().len(), 2);
+    assert_eq!(list.contests()[0].id, 1780);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn problemset_list_view() {
+    common::init();
+    let (sender, _receiver) = mpsc::channel();
+    let mut list = ProblemsetList::new(sender);
+    list.update();
+    wait_until(|| {
+        list.tick();
+        !list.is_updating()
+    });
+    assert_eq!(list.problems().len(), 3);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn submissions_list_view() {
+    common::init();
+    let (sender, _receiver) = mpsc::channel();
+    let mut list = SubmissionsList::new(sender, contest().await);
+    list.update();
+    wait_until(|| {
+        list.tick();
+        !list.is_updating()
+    });
+    assert_eq!(list.submissions().len(), 3);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn tracking_keeps_loaded_pages() {
+    common::init();
+    let (sender, _receiver) = mpsc::channel();
+    let mut list = SubmissionsList::new(sender, contest().await);
+    list.set_page_size(2).update();
+    wait_until(|| {
+        list.tick();
+        !list.is_updating()
+    });
+    assert_eq!(list.submissions().len(), 2);
+    // Moving down loads the next page.
+    list.on(&key('j')).unwrap();
+    wait_until(|| {
+        list.tick();
+        list.submissions().len() == 3
+    });
+    list.on(&key('j')).unwrap();
+    let selected = list.submissions()[2].id;
+
+    list.track(list.submissions()[0].id);
+    wait_until(|| {
+        list.tick();
+        !list.is_updating()
+    });
+    let ids: Vec<i32> = list
+        .submissions()
+        .iter()
+        .map(|submission| submission.id)
+        .collect();
+    assert_eq!(ids.len(), 3);
+    assert_eq!(ids[2], selected);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn problems_list_parse_and_test() {
+    common::init();
+    let (sender, receiver) = mpsc::channel();
+    let mut list = ProblemsList::new(sender, contest().await, ProblemSource::Contest(1));
+    list.update();
+    wait_until(|| {
+        list.tick();
+        !list.is_updating()
+    });
+    let indexes: Vec<&str> = list
+        .problems()
+        .iter()
+        .map(|problem| problem.index.as_str())
+        .collect();
+    assert_eq!(indexes, vec!["A", "B", "C"]);
+
+    list.on(&key('p')).unwrap();
+    let text = run_popup(&receiver, |text| text.to_string().starts_with("Parsed"));
+    assert_eq!(text.to_string(), "Parsed 1 test cases for Problem A");
+    let problem_dir = common::home_dir().join("Contests").join("1").join("A");
+    assert_eq!(
+        fs::read_to_string(problem_dir.join("in1.txt")).unwrap(),
+        "6 6 4\n"
+    );
+    assert_eq!(
+        fs::read_to_string(problem_dir.join("ans1.txt")).unwrap(),
+        "4\n"
+    );
+
+    DirBuilder::new()
+        .recursive(true)
+        .create(&problem_dir)
+        .unwrap();
+    fs::write(problem_dir.join("A.sh"), "echo 4\n").unwrap();
+    list.on(&key('t')).unwrap();
+    let text = run_popup(&receiver, |text| text.height() > 1);
+    assert_eq!(text.to_string(), "Testing #1...\nPassed #1.");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn parse_gym_problem() {
+    common::init();
+    let (sender, receiver) = mpsc::channel();
+    let mut list = ProblemsList::new(sender, contest().await, ProblemSource::Gym(1));
+    list.update();
+    wait_until(|| {
+        list.tick();
+        !list.is_updating()
+    });
+
+    list.on(&key('p')).unwrap();
+    let text = run_popup(&receiver, |text| text.to_string().starts_with("Parsed"));
+    assert_eq!(text.to_string(), "Parsed 1 test cases for Problem A");
+    let problem_dir = common::home_dir().join("Gym").join("1").join("A");
+    assert_eq!(
+        fs::read_to_string(problem_dir.join("in1.txt")).unwrap(),
+        "6 6 4\n"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn parse_all_problems() {
+    common::init();
+    // The problemset has no page of all problems, so every problem is fetched on its own.
+    for source in [ProblemSource::Contest(1), ProblemSource::Problemset(1)] {
+        let (sender, receiver) = mpsc::channel();
+        let mut list = ProblemsList::new(sender, contest().await, source);
+        list.update();
+        wait_until(|| {
+            list.tick();
+            !list.is_updating()
+        });
+
+        list.on(&AppEvent::Key(KeyEvent::new(
+            KeyCode::Char('P'),
+            KeyModifiers::SHIFT,
+        )))
+        .unwrap();
+        let text = run_popup(&receiver, |text| !text.to_string().contains("Parsing..."));
+        assert_eq!(
+            text.to_string(),
+            "Parsed 1 test cases for Problem A\n\
+             Parsed 1 test cases for Problem B\n\
+             Parsed 1 test cases for Problem C"
+        );
+    }
+    let contest_dir = common::home_dir().join("Contests").join("1");
+    assert_eq!(
+        fs::read_to_string(contest_dir.join("B").join("in1.txt")).unwrap(),
+        "2\nR23C55\nBC23\n"
+    );
+    assert!(contest_dir.join("C").join("1-1C.png").exists());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn parse_all_problems_in_one_request() {
+    common::init();
+    // A group of its own, so the requests of other tests are not counted.
+    let source = ProblemSource::Group(String::from("counted"), 1);
+    let (sender, receiver) = mpsc::channel();
+    let mut list = ProblemsList::new(sender, contest().await, source);
+    list.update();
+    wait_until(|| {
+        list.tick();
+        !list.is_updating()
+    });
+
+    list.on(&AppEvent::Key(KeyEvent::new(
+        KeyCode::Char('P'),
+        KeyModifiers::SHIFT,
+    )))
+    .unwrap();
+    let text = run_popup(&receiver, |text| !text.to_string().contains("Parsing..."));
+    assert_eq!(
+        text.to_string(),
+        "Parsed 1 test cases for Problem A\n\
+         Parsed 1 test cases for Problem B\n\
+         Parsed 1 test cases for Problem C"
+    );
+    assert_eq!(common::requests("/group/counted/contest/1/problems"), 1);
+    for index in ["A", "B", "C"] {
+        let path = format!("/group/counted/contest/1/problem/{index}");
+        assert_eq!(common::requests(&path), 0, "{path}");
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn parse_saves_statement() {
+    common::init();
+    let (sender, receiver) = mpsc::channel();
+    let mut list = ProblemsList::new(sender, contest().await, ProblemSource::Contest(1));
+    list.update();
+    wait_until(|| {
+        list.tick();
+        !list.is_updating()
+    });
+    // The first move only selects the first problem.
+    for _ in 0..3 {
+        list.on(&key('j')).unwrap();
+    }
+    while receiver.try_recv().is_ok() {}
+
+    list.on(&key('p')).unwrap();
+    let text = run_popup(&receiver, |text| text.to_string().starts_with("Parsed"));
+    assert_eq!(text.to_string(), "Parsed 1 test cases for Problem C");
+    let problem_dir = common::home_dir().join("Contests").join("1").join("C");
+    let statement = fs::read_to_string(problem_dir.join("statement.md")).unwrap();
+    assert!(statement.starts_with("# C. Ancient Berland Circus\n"));
+    assert!(statement.contains("![](1-1C.png)"));
+    let image = fs::read(problem_dir.join("1-1C.png")).unwrap();
+    assert!(image.starts_with(b"\x89PNG"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn statement_view() {
+    common::init();
+    let (sender, receiver) = mpsc::channel();
+    let mut list = ProblemsList::new(sender, contest().await, ProblemSource::Contest(1));
+    list.update();
+    wait_until(|| {
+        list.tick();
+        !list.is_updating()
+    });
+    list.on(&AppEvent::Key(KeyEvent::new(
+        KeyCode::Enter,
+        KeyModifiers::NONE,
+    )))
+    .unwrap();
+    let (locator, problem) = match receiver.recv_timeout(TIMEOUT) {
+        Ok(ComponentMsg::EnterNewView(ViewConstructor::Statement(locator, problem))) => {
+            (locator, problem)
+        }
+        _ => panic!("No statement opened"),
+    };
+    assert_eq!(locator, ProblemLocator::new(ProblemSource::Contest(1), "A"));
+    assert_eq!(problem.index, "A");
+
+    let (sender, receiver) = mpsc::channel();
+    let mut statement = Statement::new(sender, locator, problem);
+    statement.update();
+    wait_until(|| {
+        statement.tick();
+        !statement.is_updating()
+    });
+    let text = statement.text().to_string();
+    assert!(text.starts_with("A. Theatre Square\ntime limit per test: 1 second"));
+    assert!(text.contains("(1 ≤ n, m, a ≤ 10⁹)"));
+    assert!(text.contains("┌─ Input #1 ─┐\n│ 6 6 4      │\n└────────────┘"));
+
+    statement.on(&key('p')).unwrap();
+    let text = run_popup(&receiver, |text| text.to_string().starts_with("Parsed"));
+    assert_eq!(text.to_string(), "Parsed 1 test cases for Problem A");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn palette_commands() {
+    common::init();
+    let (sender, receiver) = mpsc::channel();
+    let mut browser =
+        ContestBrowser::new(sender.clone(), contest().await, ProblemSource::Contest(1));
+    assert_eq!(browser.tabs(), ["Problems", "Standings", "Submissions"]);
+    assert!(browser.actions().contains(&Action::Submit));
+    browser.select_tab(1).unwrap();
+    assert!(!browser.actions().contains(&Action::Submit));
+    assert!(browser.select_tab(3).is_err());
+
+    open_contest(sender, ProblemSource::Contest(1));
+    match receiver.recv_timeout(TIMEOUT) {
+        Ok(ViewMsg::EnterNewView(ViewConstructor::ContestBrowser(contest, source))) => {
+            assert_eq!(contest.id, 1);
+            assert_eq!(source, ProblemSource::Contest(1));
+        }
+        _ => panic!("The contest is not opened"),
+    }
+}